@@ -0,0 +1,285 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::process::Command;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::dbus_connection::DBusConnection;
+use crate::kernel_power::{KernelPower, PROCESS_STATUS_PATH};
+use crate::logger::LogEntry;
+use crate::logind::{Logind, CAN_POWER_OFF_METHOD, CAN_SUSPEND_METHOD};
+use crate::power_options::{InhibitorPolicy, PowerBackend, PowerOptions};
+use crate::terminal_broadcast::{TerminalBroadcast, UTMP_PATH};
+
+const SUDO_COMMAND: &str = "sudo";
+const SUDO_NON_INTERACTIVE_LIST_ARGS: [&str; 2] = ["-n", "-l"];
+const SUDO_OTHER_USER_ARG: &str = "-U";
+const ROOT_UID: libc::uid_t = 0;
+const SYSTEMCTL_POWER_OFF_ARGS: [&str; 2] = ["systemctl", "poweroff"];
+const SYSTEMCTL_IGNORE_INHIBITORS_ARG: &str = "-i";
+const SHUTDOWN_INHIBITOR: &str = "shutdown";
+
+const POWER_OFF_ACTION: &str = "poweroff";
+const SUSPEND_ACTION: &str = "suspend";
+const LOCK_ACTION: &str = "lock";
+const LUKS_SUSPEND_ACTION: &str = "luks-suspend";
+const WEBHOOK_ACTION: &str = "webhook";
+const EMAIL_ACTION: &str = "email";
+const DESKTOP_ACTION: &str = "desktop";
+const WALL_ACTION: &str = "wall";
+const COMMAND_ACTION: &str = "command";
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Action {
+    PowerOff,
+    Suspend,
+    Lock,
+    LuksSuspend,
+    Webhook,
+    Email,
+    Desktop,
+    Wall,
+    Command,
+}
+
+impl Action {
+    pub fn execute(&self, options: &PowerOptions) -> Result<(), Box<dyn Error>> {
+        return match self {
+            Action::PowerOff => {
+                let (backend, ignore_inhibitors) = Self::select_power_off_backend(options);
+                match backend {
+                    PowerBackend::Sudo => Self::power_off(ignore_inhibitors),
+                    // logind lets the power off past inhibitors when polkit
+                    // allows the caller to ignore them.
+                    PowerBackend::Logind => Logind::power_off(&mut Self::connect(options)?),
+                    PowerBackend::Kernel => KernelPower::power_off(PROCESS_STATUS_PATH),
+                }
+            }
+            Action::Suspend => Logind::suspend(&mut Self::connect(options)?),
+            Action::Lock => Logind::lock_sessions(&mut Self::connect(options)?),
+            Action::LuksSuspend | Action::Command => Err(format!("{} requires its options", self))?,
+            Action::Webhook | Action::Email | Action::Desktop | Action::Wall => {
+                Err(format!("{} notifications are sent by a notifier", self))?
+            }
+        };
+    }
+
+    pub fn check(&self, options: &PowerOptions) -> Result<(), Box<dyn Error>> {
+        return match self {
+            Action::PowerOff => {
+                let ignore_inhibitors = options.inhibitors == InhibitorPolicy::Ignore;
+                Self::check_power_off(options, options.backend, ignore_inhibitors)?;
+                if options.inhibitors == InhibitorPolicy::Fallback {
+                    Self::check_power_off(options, options.fallback_backend, true)?;
+                }
+                Ok(())
+            }
+            Action::Suspend => {
+                Logind::check_allowed(&mut Self::connect(options)?, CAN_SUSPEND_METHOD)
+            }
+            // logind has no way to ask whether LockSessions is allowed.
+            Action::Lock => Logind::ping(&mut Self::connect(options)?),
+            // Its other steps are best effort, the power off matters.
+            Action::LuksSuspend => Action::PowerOff.check(options),
+            Action::Webhook | Action::Email | Action::Desktop | Action::Command => Ok(()),
+            Action::Wall => Self::check_wall(),
+        };
+    }
+
+    fn connect(options: &PowerOptions) -> Result<DBusConnection, Box<dyn Error>> {
+        let timeout = Duration::from_secs(options.timeout_seconds as u64);
+        return DBusConnection::open(&options.system_bus, timeout);
+    }
+
+    fn check_wall() -> Result<(), Box<dyn Error>> {
+        TerminalBroadcast::terminals(UTMP_PATH)?;
+        return Ok(());
+    }
+
+    /// Logs the inhibitors of a shutdown and returns the backend to power off
+    /// with, and whether it has to force the power off past inhibitors.
+    fn select_power_off_backend(options: &PowerOptions) -> (PowerBackend, bool) {
+        let ignore_inhibitors = options.inhibitors == InhibitorPolicy::Ignore;
+        // reboot(2) does not know about inhibitors.
+        if options.inhibitors == InhibitorPolicy::None || options.backend == PowerBackend::Kernel {
+            return (options.backend, ignore_inhibitors);
+        }
+        let inhibitors = match Self::connect(options).and_then(|mut connection| {
+            return Logind::inhibitors(&mut connection);
+        }) {
+            Ok(inhibitors) => inhibitors,
+            Err(error) => {
+                let message = format!("Unable to list inhibitors: {}", error);
+                LogEntry::warning("inhibitor_error", &message).log();
+                return (options.backend, ignore_inhibitors);
+            }
+        };
+        let mut blocked = false;
+        for inhibitor in &inhibitors {
+            if !inhibitor.inhibits(SHUTDOWN_INHIBITOR) {
+                continue;
+            }
+            let message = format!(
+                "Shutdown inhibited by {} (uid {}, pid {}, mode {}): {}",
+                inhibitor.who, inhibitor.uid, inhibitor.pid, inhibitor.mode, inhibitor.why
+            );
+            LogEntry::warning("inhibitor_found", &message).log();
+            blocked |= inhibitor.is_blocking();
+        }
+        if !blocked || options.inhibitors == InhibitorPolicy::Ignore {
+            return (options.backend, ignore_inhibitors);
+        }
+        let message = format!(
+            "Shutdown is blocked, powering off with the {} backend",
+            options.fallback_backend
+        );
+        LogEntry::warning("inhibitor_fallback", &message).log();
+        return (options.fallback_backend, true);
+    }
+
+    fn check_power_off(
+        options: &PowerOptions,
+        backend: PowerBackend,
+        ignore_inhibitors: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        return match backend {
+            PowerBackend::Sudo => Self::check_sudo_power_off(options, ignore_inhibitors),
+            PowerBackend::Logind => {
+                Logind::check_allowed(&mut Self::connect(options)?, CAN_POWER_OFF_METHOD)
+            }
+            PowerBackend::Kernel => KernelPower::check(PROCESS_STATUS_PATH),
+        };
+    }
+
+    fn systemctl_power_off_args(ignore_inhibitors: bool) -> Vec<&'static str> {
+        let mut args = SYSTEMCTL_POWER_OFF_ARGS.to_vec();
+        if ignore_inhibitors {
+            args.push(SYSTEMCTL_IGNORE_INHIBITORS_ARG);
+        }
+        return args;
+    }
+
+    /// Checks the rights of the current user, or of the configured sudo user
+    /// when running as root, as check-config usually does.
+    fn check_sudo_power_off(
+        options: &PowerOptions,
+        ignore_inhibitors: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let systemctl_args = Self::systemctl_power_off_args(ignore_inhibitors);
+        let is_root = unsafe { libc::geteuid() } == ROOT_UID;
+        let output = match Command::new(SUDO_COMMAND)
+            .args(Self::sudo_list_args(options, is_root))
+            .args(&systemctl_args)
+            .output()
+        {
+            Ok(output) => output,
+            Err(error) => Err(format!("Unable to run {}: {}", SUDO_COMMAND, error))?,
+        };
+        if !output.status.success() {
+            let user = match is_root {
+                true => options.sudo_user.as_str(),
+                false => "the current user",
+            };
+            Err(format!(
+                "{} does not allow {} to run \"{}\" without password",
+                SUDO_COMMAND,
+                user,
+                systemctl_args.join(" ")
+            ))?;
+        }
+        return Ok(());
+    }
+
+    fn sudo_list_args(options: &PowerOptions, is_root: bool) -> Vec<String> {
+        let mut args: Vec<String> = SUDO_NON_INTERACTIVE_LIST_ARGS
+            .iter()
+            .map(|arg| {
+                return arg.to_string();
+            })
+            .collect();
+        if is_root {
+            args.push(String::from(SUDO_OTHER_USER_ARG));
+            args.push(options.sudo_user.clone());
+        }
+        return args;
+    }
+
+    fn power_off(ignore_inhibitors: bool) -> Result<(), Box<dyn Error>> {
+        let systemctl_args = Self::systemctl_power_off_args(ignore_inhibitors);
+        let output = match Command::new(SUDO_COMMAND).args(&systemctl_args).output() {
+            Ok(output) => output,
+            Err(error) => Err(format!("Unable to shutdown: {}", error))?,
+        };
+        let output_data = if output.status.success() {
+            output.stdout
+        } else {
+            output.stderr
+        };
+        Self::log_output(output_data);
+        if !output.status.success() {
+            Err(format!(
+                "\"{}\" failed with {}",
+                systemctl_args.join(" "),
+                output.status
+            ))?;
+        }
+        return Ok(());
+    }
+
+    fn log_output(output_data: Vec<u8>) {
+        if output_data.is_empty() {
+            return;
+        }
+        match String::from_utf8(output_data) {
+            Ok(output_message) => LogEntry::info(
+                "action_output",
+                &format!("Shutdown output: {}", output_message),
+            )
+            .log(),
+            Err(error) => {
+                let message = format!("Error converting command output to string: {}", error);
+                LogEntry::error("action_error", &message).log();
+            }
+        };
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Action::PowerOff => POWER_OFF_ACTION,
+            Action::Suspend => SUSPEND_ACTION,
+            Action::Lock => LOCK_ACTION,
+            Action::LuksSuspend => LUKS_SUSPEND_ACTION,
+            Action::Webhook => WEBHOOK_ACTION,
+            Action::Email => EMAIL_ACTION,
+            Action::Desktop => DESKTOP_ACTION,
+            Action::Wall => WALL_ACTION,
+            Action::Command => COMMAND_ACTION,
+        };
+        return write!(formatter, "{}", name);
+    }
+}
+
+impl FromStr for Action {
+    type Err = Box<dyn Error>;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return match name {
+            POWER_OFF_ACTION => Ok(Action::PowerOff),
+            SUSPEND_ACTION => Ok(Action::Suspend),
+            LOCK_ACTION => Ok(Action::Lock),
+            LUKS_SUSPEND_ACTION => Ok(Action::LuksSuspend),
+            WEBHOOK_ACTION => Ok(Action::Webhook),
+            EMAIL_ACTION => Ok(Action::Email),
+            DESKTOP_ACTION => Ok(Action::Desktop),
+            WALL_ACTION => Ok(Action::Wall),
+            COMMAND_ACTION => Ok(Action::Command),
+            _ => Err(format!("Unknown action {}", name))?,
+        };
+    }
+}
+
+#[cfg(test)]
+#[path = "./action_tests.rs"]
+mod tests;
@@ -0,0 +1,114 @@
+use std::ffi::CStr;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local, SecondsFormat};
+
+use crate::action::Action;
+use crate::json::Json;
+
+const HOSTNAME_BUFFER_SIZE: usize = 256;
+const UNKNOWN_HOST: &str = "unknown";
+
+/// Details about what made the actions fire, passed to actions that report
+/// the event somewhere.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ActionContext {
+    pub host: String,
+    pub user: Option<String>,
+    pub service: Option<String>,
+    pub counter: i32,
+    pub max_failed_attempts: i32,
+    pub limit_actions: Vec<Action>,
+    pub attempts: Vec<FailedAttempt>,
+}
+
+/// A counted failed attempt with the counter value after it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FailedAttempt {
+    pub time: SystemTime,
+    pub counter: i32,
+    pub line: String,
+}
+
+impl ActionContext {
+    pub fn hostname() -> String {
+        let mut buffer = [0u8; HOSTNAME_BUFFER_SIZE];
+        let result =
+            unsafe { libc::gethostname(buffer.as_mut_ptr() as *mut libc::c_char, buffer.len()) };
+        if result != 0 {
+            return String::from(UNKNOWN_HOST);
+        }
+        return match CStr::from_bytes_until_nul(&buffer) {
+            Ok(hostname) => hostname.to_string_lossy().into_owned(),
+            Err(_) => String::from(UNKNOWN_HOST),
+        };
+    }
+
+    pub fn to_json(&self) -> String {
+        let timestamp = Local::now().to_rfc3339_opts(SecondsFormat::Secs, false);
+        return Json::object(&[
+            ("timestamp", Json::string(&timestamp)),
+            ("host", Json::string(&self.host)),
+            ("user", Json::optional_string(self.user.as_deref())),
+            ("service", Json::optional_string(self.service.as_deref())),
+            ("counter", self.counter.to_string()),
+            ("lines", Json::string_array(&self.lines())),
+        ]);
+    }
+
+    /// One line description for the users of the machine, such as
+    /// "3 failed authentication attempt(s); the machine will power off after 5".
+    pub fn summary(&self) -> String {
+        if !self.is_warning() {
+            return format!(
+                "{} failed authentication attempt(s); the limit of {} has been reached",
+                self.counter, self.max_failed_attempts
+            );
+        }
+        return format!(
+            "{} failed authentication attempt(s); {} after {}",
+            self.counter,
+            self.consequence(),
+            self.max_failed_attempts
+        );
+    }
+
+    /// Whether the actions run as a warning, before the limit is reached.
+    pub fn is_warning(&self) -> bool {
+        return self.counter < self.max_failed_attempts;
+    }
+
+    /// What happens when the limit is reached, such as "the machine will
+    /// power off".
+    pub fn consequence(&self) -> String {
+        let powers_off = self.limit_actions.iter().any(|action| {
+            return matches!(action, Action::PowerOff | Action::LuksSuspend);
+        });
+        return match powers_off {
+            true => String::from("the machine will power off"),
+            false => format!(
+                "{} will be executed",
+                self.limit_actions
+                    .iter()
+                    .map(Action::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        };
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        return self
+            .attempts
+            .iter()
+            .map(|attempt| {
+                return attempt.line.clone();
+            })
+            .collect();
+    }
+
+    pub fn format_time(time: SystemTime) -> String {
+        let time: DateTime<Local> = DateTime::from(time);
+        return time.to_rfc3339_opts(SecondsFormat::Secs, false);
+    }
+}
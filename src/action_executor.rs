@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use crate::action::Action;
+use crate::action_context::ActionContext;
+use crate::auth_monitor_params::AuthMonitorParams;
+use crate::command_action::CommandAction;
+use crate::command_options::CommandOptions;
+use crate::desktop_notifier::DesktopNotifier;
+use crate::desktop_options::DesktopOptions;
+use crate::email_notifier::EmailNotifier;
+use crate::logger::LogEntry;
+use crate::luks_suspend::LuksSuspend;
+use crate::luks_suspend_options::LuksSuspendOptions;
+use crate::power_options::PowerOptions;
+use crate::terminal_broadcast::{TerminalBroadcast, UTMP_PATH};
+use crate::webhook_notifier::WebhookNotifier;
+
+/// Longest time power actions wait for queued notifications, so that an
+/// unreachable endpoint cannot hold off a power off for long.
+const MAX_NOTIFICATION_WAIT: Duration = Duration::from_secs(20);
+
+pub struct ActionExecutor {
+    webhook_notifier: Option<WebhookNotifier>,
+    email_notifier: Option<EmailNotifier>,
+    desktop_options: DesktopOptions,
+    power_options: PowerOptions,
+    luks_suspend_options: LuksSuspendOptions,
+    command_options: CommandOptions,
+}
+
+impl ActionExecutor {
+    pub fn new(params: &AuthMonitorParams) -> Result<ActionExecutor, Box<dyn Error>> {
+        let mut action_executor = ActionExecutor {
+            webhook_notifier: None,
+            email_notifier: None,
+            desktop_options: params.desktop.clone(),
+            power_options: params.power.clone(),
+            luks_suspend_options: params.luks_suspend.clone(),
+            command_options: params.command.clone(),
+        };
+        action_executor.configure(params)?;
+        return Ok(action_executor);
+    }
+
+    fn configure(&mut self, params: &AuthMonitorParams) -> Result<(), Box<dyn Error>> {
+        let webhook_changed = match &self.webhook_notifier {
+            Some(webhook_notifier) => webhook_notifier.options() != &params.webhook,
+            None => true,
+        };
+        if webhook_changed {
+            self.webhook_notifier = match params.webhook.url {
+                Some(_) => Some(WebhookNotifier::new(&params.webhook)?),
+                None => None,
+            };
+        }
+        let email_changed = match &self.email_notifier {
+            Some(email_notifier) => email_notifier.options() != &params.email,
+            None => true,
+        };
+        if email_changed {
+            self.email_notifier = match params.email.server {
+                Some(_) => Some(EmailNotifier::new(&params.email)?),
+                None => None,
+            };
+        }
+        self.desktop_options = params.desktop.clone();
+        self.power_options = params.power.clone();
+        self.luks_suspend_options = params.luks_suspend.clone();
+        self.command_options = params.command.clone();
+        return Ok(());
+    }
+
+    /// Executes the actions in order and returns the number of failed ones.
+    /// Notifications are queued, but power actions following them wait a
+    /// limited time for the delivery, so that a notification gets out before
+    /// a power off.
+    pub fn execute(&self, actions: &[Action], context: &ActionContext) -> u64 {
+        let mut failures = 0;
+        for action in actions {
+            let result = match action {
+                Action::Webhook => self.send_webhook(context),
+                Action::Email => self.send_email(context),
+                Action::Desktop => DesktopNotifier::send(&self.desktop_options, context),
+                Action::Wall => Self::broadcast(context),
+                Action::Command => CommandAction::execute(&self.command_options, context),
+                Action::LuksSuspend => {
+                    self.wait_for_notifications();
+                    LuksSuspend::new(&self.luks_suspend_options, &self.power_options).execute()
+                }
+                _ => {
+                    self.wait_for_notifications();
+                    action.execute(&self.power_options)
+                }
+            };
+            if let Err(error) = result {
+                let message = format!("Error executing {}: {}", action, error);
+                LogEntry::error("action_error", &message).log();
+                failures += 1;
+            }
+        }
+        return failures;
+    }
+
+    fn send_webhook(&self, context: &ActionContext) -> Result<(), Box<dyn Error>> {
+        return match &self.webhook_notifier {
+            Some(webhook_notifier) => webhook_notifier.send(context),
+            None => Err("Webhook URL not specified")?,
+        };
+    }
+
+    fn send_email(&self, context: &ActionContext) -> Result<(), Box<dyn Error>> {
+        return match &self.email_notifier {
+            Some(email_notifier) => email_notifier.send(context),
+            None => Err("Email server not specified")?,
+        };
+    }
+
+    fn broadcast(context: &ActionContext) -> Result<(), Box<dyn Error>> {
+        let written = TerminalBroadcast::broadcast(UTMP_PATH, &context.summary())?;
+        let message = format!("Warning written to {} terminal(s)", written);
+        LogEntry::debug("broadcast_sent", &message).log();
+        return Ok(());
+    }
+
+    fn wait_for_notifications(&self) {
+        let deadline = Instant::now() + MAX_NOTIFICATION_WAIT;
+        if let Some(webhook_notifier) = &self.webhook_notifier {
+            webhook_notifier.wait_until_idle(deadline);
+        }
+        if let Some(email_notifier) = &self.email_notifier {
+            email_notifier.wait_until_idle(deadline);
+        }
+    }
+}
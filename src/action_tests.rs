@@ -0,0 +1,259 @@
+use crate::action::Action;
+use crate::assert_error;
+use crate::dbus_message::{DBusMessage, DBusValue};
+use crate::power_options::{InhibitorPolicy, PowerBackend, PowerOptions};
+use crate::test_utils::dbus_stand_in::DBusStandIn;
+
+/// Stands in for logind, answering the Can* methods with `can_result`.
+fn start_logind(can_result: &'static str) -> DBusStandIn {
+    return DBusStandIn::start(move |call| {
+        return match call.member.as_deref() {
+            Some("PowerOff") | Some("Suspend") | Some("LockSessions") | Some("Ping") => {
+                Ok(Vec::new())
+            }
+            Some("CanPowerOff") | Some("CanSuspend") => Ok(vec![DBusValue::string(can_result)]),
+            _ => Err(String::from("org.freedesktop.DBus.Error.UnknownMethod")),
+        };
+    });
+}
+
+fn options(bus: &DBusStandIn) -> PowerOptions {
+    return PowerOptions {
+        backend: PowerBackend::Logind,
+        inhibitors: InhibitorPolicy::None,
+        fallback_backend: PowerBackend::Kernel,
+        system_bus: bus.address(),
+        timeout_seconds: 5,
+        ..PowerOptions::default()
+    };
+}
+
+fn single_call(bus: &DBusStandIn) -> DBusMessage {
+    let calls = bus.calls();
+    assert_eq!(calls.len(), 1);
+    return calls[0].clone();
+}
+
+#[test]
+fn when_logind_backend_powers_off_then_non_interactive_power_off_is_called() {
+    let bus = start_logind("yes");
+
+    let result = Action::PowerOff.execute(&options(&bus));
+
+    assert!(result.is_ok(), "{:?}", result.err());
+    let call = single_call(&bus);
+    assert_eq!(call.destination.as_deref(), Some("org.freedesktop.login1"));
+    assert_eq!(call.path.as_deref(), Some("/org/freedesktop/login1"));
+    assert_eq!(
+        call.interface.as_deref(),
+        Some("org.freedesktop.login1.Manager")
+    );
+    assert_eq!(call.member.as_deref(), Some("PowerOff"));
+    assert_eq!(call.body, [DBusValue::Boolean(false)]);
+}
+
+#[test]
+fn when_suspending_or_locking_then_logind_is_called() {
+    let bus = start_logind("yes");
+
+    Action::Suspend.execute(&options(&bus)).unwrap();
+    Action::Lock.execute(&options(&bus)).unwrap();
+
+    let calls = bus.calls();
+    assert_eq!(calls[0].member.as_deref(), Some("Suspend"));
+    assert_eq!(calls[0].body, [DBusValue::Boolean(false)]);
+    assert_eq!(calls[1].member.as_deref(), Some("LockSessions"));
+    assert!(calls[1].body.is_empty());
+}
+
+#[test]
+fn when_logind_refuses_power_off_then_return_error() {
+    let bus = DBusStandIn::start(|_| {
+        return Err(String::from(
+            "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired",
+        ));
+    });
+
+    let result = Action::PowerOff.execute(&options(&bus));
+
+    assert_error!(
+        result,
+        "org.freedesktop.login1.Manager.PowerOff failed: org.freedesktop.DBus.Error.InteractiveAuthorizationRequired Refused by test bus"
+    );
+}
+
+#[test]
+fn when_polkit_allows_power_off_then_check_succeeds() {
+    let bus = start_logind("yes");
+
+    let result = Action::PowerOff.check(&options(&bus));
+
+    assert!(result.is_ok(), "{:?}", result.err());
+    assert_eq!(single_call(&bus).member.as_deref(), Some("CanPowerOff"));
+}
+
+#[test]
+fn when_polkit_requires_authentication_then_check_fails() {
+    let bus = start_logind("challenge");
+
+    assert_error!(
+        Action::PowerOff.check(&options(&bus)),
+        "CanPowerOff requires authentication, polkit does not allow it"
+    );
+    assert_error!(
+        Action::Suspend.check(&options(&bus)),
+        "CanSuspend requires authentication, polkit does not allow it"
+    );
+}
+
+#[test]
+fn when_checking_lock_then_logind_is_pinged() {
+    let bus = start_logind("yes");
+
+    let result = Action::Lock.check(&options(&bus));
+
+    assert!(result.is_ok(), "{:?}", result.err());
+    let call = single_call(&bus);
+    assert_eq!(call.interface.as_deref(), Some("org.freedesktop.DBus.Peer"));
+    assert_eq!(call.member.as_deref(), Some("Ping"));
+}
+
+#[test]
+fn when_system_bus_is_unavailable_then_return_error() {
+    let options = PowerOptions {
+        backend: PowerBackend::Logind,
+        inhibitors: InhibitorPolicy::None,
+        fallback_backend: PowerBackend::Kernel,
+        system_bus: String::from("unix:path=/nonexistent/auth-monitor-bus"),
+        timeout_seconds: 1,
+        ..PowerOptions::default()
+    };
+
+    let result = Action::Lock.execute(&options);
+
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .starts_with("Unable to connect to D-Bus /nonexistent/auth-monitor-bus"));
+}
+
+fn inhibitor(what: &str, mode: &str) -> DBusValue {
+    return DBusValue::Struct(vec![
+        DBusValue::string(what),
+        DBusValue::string("Firefox"),
+        DBusValue::string("Downloading a file"),
+        DBusValue::string(mode),
+        DBusValue::UInt32(1000),
+        DBusValue::UInt32(4242),
+    ]);
+}
+
+/// Stands in for logind holding an inhibitor of `what` with `mode`.
+fn start_inhibited_logind(what: &'static str, mode: &'static str) -> DBusStandIn {
+    return DBusStandIn::start(move |call| {
+        return match call.member.as_deref() {
+            Some("ListInhibitors") => Ok(vec![DBusValue::Array(
+                String::from("(ssssuu)"),
+                vec![inhibitor("sleep", "block"), inhibitor(what, mode)],
+            )]),
+            Some("PowerOff") => Ok(Vec::new()),
+            _ => Err(String::from("org.freedesktop.DBus.Error.UnknownMethod")),
+        };
+    });
+}
+
+fn inhibitor_options(bus: &DBusStandIn, inhibitors: InhibitorPolicy) -> PowerOptions {
+    return PowerOptions {
+        backend: PowerBackend::Sudo,
+        inhibitors,
+        fallback_backend: PowerBackend::Logind,
+        system_bus: bus.address(),
+        timeout_seconds: 5,
+        ..PowerOptions::default()
+    };
+}
+
+#[test]
+fn when_shutdown_is_blocked_and_policy_is_fallback_then_fallback_backend_is_selected() {
+    let bus = start_inhibited_logind("shutdown:sleep", "block");
+
+    let selected =
+        Action::select_power_off_backend(&inhibitor_options(&bus, InhibitorPolicy::Fallback));
+
+    assert_eq!(selected, (PowerBackend::Logind, true));
+    assert_eq!(single_call(&bus).member.as_deref(), Some("ListInhibitors"));
+}
+
+#[test]
+fn when_shutdown_is_blocked_and_policy_is_ignore_then_backend_forces_power_off() {
+    let bus = start_inhibited_logind("shutdown", "block-weak");
+
+    let selected =
+        Action::select_power_off_backend(&inhibitor_options(&bus, InhibitorPolicy::Ignore));
+
+    assert_eq!(selected, (PowerBackend::Sudo, true));
+}
+
+#[test]
+fn when_shutdown_is_only_delayed_then_backend_is_kept() {
+    let bus = start_inhibited_logind("shutdown", "delay");
+
+    let selected =
+        Action::select_power_off_backend(&inhibitor_options(&bus, InhibitorPolicy::Fallback));
+
+    assert_eq!(selected, (PowerBackend::Sudo, false));
+}
+
+#[test]
+fn when_inhibitors_are_not_checked_then_logind_is_not_called() {
+    let bus = start_inhibited_logind("shutdown", "block");
+
+    let selected =
+        Action::select_power_off_backend(&inhibitor_options(&bus, InhibitorPolicy::None));
+
+    assert_eq!(selected, (PowerBackend::Sudo, false));
+    assert!(bus.calls().is_empty());
+}
+
+#[test]
+fn when_inhibitors_cannot_be_listed_then_backend_is_kept() {
+    let bus = start_logind("yes");
+
+    let selected =
+        Action::select_power_off_backend(&inhibitor_options(&bus, InhibitorPolicy::Fallback));
+
+    assert_eq!(selected, (PowerBackend::Sudo, false));
+}
+
+#[test]
+fn when_logind_backend_ignores_inhibitors_then_power_off_follows_listing() {
+    let bus = start_inhibited_logind("shutdown", "block");
+    let mut options = inhibitor_options(&bus, InhibitorPolicy::Ignore);
+    options.backend = PowerBackend::Logind;
+
+    let result = Action::PowerOff.execute(&options);
+
+    assert!(result.is_ok(), "{:?}", result.err());
+    let members: Vec<String> = bus
+        .calls()
+        .iter()
+        .map(|call| {
+            return call.member.clone().unwrap_or_default();
+        })
+        .collect();
+    assert_eq!(members, ["ListInhibitors", "PowerOff"]);
+}
+
+#[test]
+fn when_checking_sudo_as_root_then_service_user_is_listed() {
+    let options = PowerOptions {
+        sudo_user: String::from("monitor"),
+        ..PowerOptions::default()
+    };
+
+    assert_eq!(
+        Action::sudo_list_args(&options, true),
+        vec!["-n", "-l", "-U", "monitor"]
+    );
+    assert_eq!(Action::sudo_list_args(&options, false), vec!["-n", "-l"]);
+}
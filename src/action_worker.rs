@@ -0,0 +1,79 @@
+use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+use crate::action::Action;
+use crate::action_context::ActionContext;
+use crate::action_executor::ActionExecutor;
+use crate::logger::LogEntry;
+
+const WORKER_NAME: &str = "actions";
+
+/// Executes actions on a background thread in the order they fire, so that
+/// slow actions never delay monitoring or the watchdog notifications.
+pub struct ActionWorker {
+    sender: Sender<ActionRequest>,
+    failures: Arc<AtomicU64>,
+}
+
+enum ActionRequest {
+    Execute(Vec<Action>, ActionContext),
+    Replace(Box<ActionExecutor>),
+}
+
+impl ActionWorker {
+    pub fn new(action_executor: ActionExecutor) -> Result<ActionWorker, Box<dyn Error>> {
+        let (sender, receiver) = mpsc::channel();
+        let failures = Arc::new(AtomicU64::new(0));
+        let worker_failures = failures.clone();
+        thread::Builder::new()
+            .name(String::from(WORKER_NAME))
+            .spawn(move || {
+                Self::run(action_executor, receiver, &worker_failures);
+            })?;
+        return Ok(ActionWorker { sender, failures });
+    }
+
+    fn run(
+        mut action_executor: ActionExecutor,
+        receiver: Receiver<ActionRequest>,
+        failures: &AtomicU64,
+    ) {
+        for request in receiver {
+            match request {
+                ActionRequest::Execute(actions, context) => {
+                    let count = action_executor.execute(&actions, &context);
+                    failures.fetch_add(count, Ordering::SeqCst);
+                }
+                ActionRequest::Replace(replacement) => action_executor = *replacement,
+            }
+        }
+    }
+
+    pub fn execute(&self, actions: &[Action], context: &ActionContext) {
+        self.send(ActionRequest::Execute(actions.to_vec(), context.clone()));
+    }
+
+    /// Actions already queued are still executed by the previous executor.
+    pub fn replace(&self, action_executor: ActionExecutor) {
+        self.send(ActionRequest::Replace(Box::new(action_executor)));
+    }
+
+    /// Number of actions that failed since the last call.
+    pub fn take_failures(&self) -> u64 {
+        return self.failures.swap(0, Ordering::SeqCst);
+    }
+
+    fn send(&self, request: ActionRequest) {
+        if self.sender.send(request).is_err() {
+            LogEntry::error("action_error", "Action worker has stopped").log();
+            self.failures.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./action_worker_tests.rs"]
+mod tests;
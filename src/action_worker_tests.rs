@@ -0,0 +1,79 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::action::Action;
+use crate::action_context::ActionContext;
+use crate::action_executor::ActionExecutor;
+use crate::action_worker::ActionWorker;
+use crate::auth_monitor_params::AuthMonitorParams;
+use crate::command_options::CommandOptions;
+
+fn context() -> ActionContext {
+    return ActionContext {
+        host: String::from("workstation"),
+        user: None,
+        service: None,
+        counter: 5,
+        max_failed_attempts: 5,
+        limit_actions: vec![Action::Command],
+        attempts: vec![],
+    };
+}
+
+fn executor(script: &str) -> ActionExecutor {
+    return ActionExecutor::new(&AuthMonitorParams {
+        command: CommandOptions {
+            exec: vec![
+                String::from("/bin/sh"),
+                String::from("-c"),
+                String::from(script),
+            ],
+            ..CommandOptions::default()
+        },
+        ..AuthMonitorParams::default()
+    })
+    .unwrap();
+}
+
+fn wait_for_failures(action_worker: &ActionWorker, timeout: Duration) -> u64 {
+    let deadline = Instant::now() + timeout;
+    let mut failures = 0;
+    while failures == 0 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(20));
+        failures = action_worker.take_failures();
+    }
+    return failures;
+}
+
+#[test]
+fn when_actions_are_slow_then_execute_returns_before_they_finish() {
+    let action_worker = ActionWorker::new(executor("sleep 1; exit 1")).unwrap();
+
+    let start = Instant::now();
+    action_worker.execute(&[Action::Command], &context());
+    let elapsed = start.elapsed();
+
+    assert!(elapsed < Duration::from_millis(500), "{:?}", elapsed);
+    assert_eq!(action_worker.take_failures(), 0);
+    assert_eq!(
+        wait_for_failures(&action_worker, Duration::from_secs(10)),
+        1
+    );
+}
+
+#[test]
+fn when_executor_is_replaced_then_later_actions_use_it() {
+    let action_worker = ActionWorker::new(executor("exit 1")).unwrap();
+
+    action_worker.replace(executor("exit 0"));
+    action_worker.execute(&[Action::Command], &context());
+    action_worker.replace(executor("exit 1"));
+    action_worker.execute(&[Action::Command], &context());
+
+    assert_eq!(
+        wait_for_failures(&action_worker, Duration::from_secs(10)),
+        1
+    );
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(action_worker.take_failures(), 0);
+}
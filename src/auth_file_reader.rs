@@ -2,6 +2,8 @@ use std::error::Error;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
 
+use crate::logger::LogEntry;
+
 pub struct AuthFileReader {
     reader: BufReader<File>,
     file_content_buffer: String,
@@ -21,14 +23,15 @@ impl AuthFileReader {
         return Ok(());
     }
 
-    pub fn read_new_lines(&mut self, mut parse_line: impl FnMut(&String)) {
+    pub fn read_new_lines(&mut self, mut parse_line: impl FnMut(&String)) -> u64 {
+        let mut truncation_count = 0;
         loop {
             self.file_content_buffer.clear();
             let bytes_read = self
                 .reader
                 .read_line(&mut self.file_content_buffer)
                 .unwrap_or_else(|error| {
-                    eprintln!("Error reading file: {}", error);
+                    LogEntry::error("file_error", &format!("Error reading file: {}", error)).log();
                     return 0;
                 });
             if bytes_read > 0 {
@@ -39,27 +42,38 @@ impl AuthFileReader {
                 break;
             }
             match self.reader.seek(SeekFrom::Start(0)) {
-                Ok(position) => println!("Resetting position in file to {}", position),
+                Ok(position) => {
+                    LogEntry::info(
+                        "file_truncated",
+                        &format!("Resetting position in file to {}", position),
+                    )
+                    .log();
+                    truncation_count += 1;
+                }
                 Err(error) => {
-                    eprintln!("Error resetting position in file: {}", error);
+                    let message = format!("Error resetting position in file: {}", error);
+                    LogEntry::error("file_error", &message).log();
                     break;
                 }
             }
         }
+        return truncation_count;
     }
 
     fn is_file_has_been_truncated(&self) -> bool {
         let length = match self.reader.get_ref().metadata() {
             Ok(metadata) => metadata.len(),
             Err(error) => {
-                eprintln!("Error getting file metadata: {}", error);
+                let message = format!("Error getting file metadata: {}", error);
+                LogEntry::error("file_error", &message).log();
                 return false;
             }
         };
         let position = match self.reader.get_ref().stream_position() {
             Ok(position) => position,
             Err(error) => {
-                eprintln!("Error getting current position in file: {}", error);
+                let message = format!("Error getting current position in file: {}", error);
+                LogEntry::error("file_error", &message).log();
                 return false;
             }
         };
@@ -1,11 +1,15 @@
 use std::error::Error;
 use std::io::ErrorKind;
+use std::mem;
+use std::time::SystemTime;
 
 use inotify::{Inotify, WatchMask};
 
 use crate::auth_file_reader::AuthFileReader;
 use crate::file_event_filter::{FileAction, FileEventFilter};
 use crate::file_path::FilePath;
+use crate::logger::LogEntry;
+use crate::tamper_detector::TamperEvent;
 
 const EVENT_BUFFER_SIZE: usize = 1024;
 const READER_BUFFER_SIZE: usize = 1024;
@@ -16,6 +20,11 @@ pub struct AuthFileWatcher {
     event_buffer: [u8; EVENT_BUFFER_SIZE],
     reader: Option<AuthFileReader>,
     event_filter: FileEventFilter,
+    reopen_count: u64,
+    rotation_count: u64,
+    tamper_events: Vec<TamperEvent>,
+    missing_since: Option<SystemTime>,
+    last_line_time: SystemTime,
 }
 
 impl AuthFileWatcher {
@@ -33,6 +42,11 @@ impl AuthFileWatcher {
             event_buffer: [0u8; EVENT_BUFFER_SIZE],
             reader: None,
             event_filter: FileEventFilter::new(&filename),
+            reopen_count: 0,
+            rotation_count: 0,
+            tamper_events: Vec::new(),
+            missing_since: Some(SystemTime::now()),
+            last_line_time: SystemTime::now(),
         };
         auth_file_watcher.open_existing_file();
         return Ok(auth_file_watcher);
@@ -42,7 +56,8 @@ impl AuthFileWatcher {
         self.open_file();
         if let Some(reader) = &mut self.reader {
             reader.seek_to_end().unwrap_or_else(|error| {
-                eprintln!("Error seeking to end of file: {}", error);
+                let message = format!("Error seeking to end of file: {}", error);
+                LogEntry::error("file_error", &message).log();
             });
         }
     }
@@ -51,7 +66,10 @@ impl AuthFileWatcher {
         let reader = match AuthFileReader::new(&self.filepath, READER_BUFFER_SIZE) {
             Ok(reader) => reader,
             Err(error) => {
-                eprintln!("Unable to open monitored file: {}", error);
+                let message = format!("Unable to open monitored file: {}", error);
+                LogEntry::warning("file_error", &message)
+                    .file(&self.filepath)
+                    .log();
                 return;
             }
         };
@@ -62,20 +80,28 @@ impl AuthFileWatcher {
         {
             Ok(_) => {}
             Err(error) => {
-                eprintln!("Error adding file watch: {}", error);
+                let message = format!("Error adding file watch: {}", error);
+                LogEntry::error("file_error", &message)
+                    .file(&self.filepath)
+                    .log();
                 return;
             }
         }
-        println!("Monitored file opened");
+        LogEntry::info("file_opened", "Monitored file opened")
+            .file(&self.filepath)
+            .log();
         self.reader = Some(reader);
+        self.missing_since = None;
+        self.last_line_time = SystemTime::now();
     }
 
-    pub fn update(&mut self, parse_line: impl FnMut(&String)) {
+    pub fn update(&mut self, mut parse_line: impl FnMut(&String)) {
         let events = match self.inotify.read_events(&mut self.event_buffer) {
             Ok(events) => events,
             Err(error) => {
                 if error.kind() != ErrorKind::WouldBlock {
-                    eprintln!("Failed to read inotify events: {}", error);
+                    let message = format!("Failed to read inotify events: {}", error);
+                    LogEntry::error("file_error", &message).log();
                 }
                 return;
             }
@@ -90,7 +116,9 @@ impl AuthFileWatcher {
             }
             match action.unwrap() {
                 FileAction::Created => {
-                    println!("New monitored file has been created");
+                    LogEntry::info("file_created", "New monitored file has been created")
+                        .file(&self.filepath)
+                        .log();
                     self.open_new_file();
                     file_modified = true;
                     break;
@@ -98,9 +126,16 @@ impl AuthFileWatcher {
                 FileAction::Modified => {
                     file_modified = true;
                 }
-                FileAction::Moved | FileAction::Deleted => {
-                    println!("Monitored file has been deleted or moved");
+                removed @ (FileAction::Moved | FileAction::Deleted) => {
+                    LogEntry::warning("file_removed", "Monitored file has been deleted or moved")
+                        .file(&self.filepath)
+                        .log();
+                    if matches!(removed, FileAction::Deleted) {
+                        self.tamper_events.push(TamperEvent::Deleted);
+                    }
                     self.reader = None;
+                    self.missing_since.get_or_insert_with(SystemTime::now);
+                    self.rotation_count += 1;
                     continue;
                 }
             };
@@ -111,11 +146,50 @@ impl AuthFileWatcher {
         }
 
         if let Some(reader) = &mut self.reader {
-            reader.read_new_lines(parse_line);
+            let mut lines_read = 0;
+            let truncation_count = reader.read_new_lines(|line| {
+                lines_read += 1;
+                parse_line(line);
+            });
+            if lines_read > 0 {
+                self.last_line_time = SystemTime::now();
+            }
+            if truncation_count > 0 {
+                self.tamper_events.push(TamperEvent::Truncated);
+            }
+            self.rotation_count += truncation_count;
         };
     }
 
     fn open_new_file(&mut self) {
         self.open_file();
+        if self.reader.is_some() {
+            self.reopen_count += 1;
+        }
+    }
+
+    /// Deletions and truncations of the monitored file since the last call.
+    pub fn take_tamper_events(&mut self) -> Vec<TamperEvent> {
+        return mem::take(&mut self.tamper_events);
+    }
+
+    /// Time since which the monitored file could not be opened.
+    pub fn missing_since(&self) -> Option<SystemTime> {
+        return self.missing_since;
+    }
+
+    /// Time a line was last read, or the file was opened.
+    pub fn last_line_time(&self) -> SystemTime {
+        return self.last_line_time;
+    }
+
+    /// Number of times the monitored file has been opened again after being recreated.
+    pub fn reopen_count(&self) -> u64 {
+        return self.reopen_count;
+    }
+
+    /// Number of times the monitored file has been moved, deleted or truncated.
+    pub fn rotation_count(&self) -> u64 {
+        return self.rotation_count;
     }
 }
@@ -0,0 +1,145 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
+use flate2::read::MultiGzDecoder;
+
+use crate::auth_message_parser::AuthMessageParser;
+use crate::auth_monitor_options::AuthMonitorOptions;
+use crate::failed_attempts_counter::FailedAttemptsCounter;
+use crate::log_timestamp_parser::LogTimestampParser;
+
+const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+
+pub struct ReplayEvent {
+    pub filepath: String,
+    pub line_number: usize,
+    pub time: SystemTime,
+    pub failed_attempts: i32,
+}
+
+pub struct AuthLogReplay {
+    counter: FailedAttemptsCounter,
+    auth_message_parser: AuthMessageParser,
+    timestamp_parser: LogTimestampParser,
+    lines_read: usize,
+    failed_attempts: usize,
+    events: Vec<ReplayEvent>,
+}
+
+impl AuthLogReplay {
+    pub fn new(
+        options: AuthMonitorOptions,
+        auth_message_parser: AuthMessageParser,
+    ) -> AuthLogReplay {
+        return AuthLogReplay {
+            counter: FailedAttemptsCounter::new(options),
+            auth_message_parser,
+            timestamp_parser: LogTimestampParser::new(),
+            lines_read: 0,
+            failed_attempts: 0,
+            events: Vec::new(),
+        };
+    }
+
+    pub fn replay_file(&mut self, filepath: &str) -> Result<(), Box<dyn Error>> {
+        let mut file = File::open(filepath)?;
+        let reference_time = file.metadata()?.modified()?;
+        let mut magic_bytes = [0u8; GZIP_MAGIC_BYTES.len()];
+        let is_gzip = match file.read_exact(&mut magic_bytes) {
+            Ok(_) => magic_bytes == GZIP_MAGIC_BYTES,
+            Err(_) => false,
+        };
+        file.seek(SeekFrom::Start(0))?;
+        let reader: Box<dyn Read> = match is_gzip {
+            true => Box::new(MultiGzDecoder::new(file)),
+            false => Box::new(file),
+        };
+        return self.replay_lines(filepath, BufReader::new(reader), reference_time);
+    }
+
+    pub fn replay_lines(
+        &mut self,
+        filepath: &str,
+        mut reader: impl BufRead,
+        reference_time: SystemTime,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        let mut line_number = 0;
+        loop {
+            buffer.clear();
+            if reader.read_until(b'\n', &mut buffer)? == 0 {
+                break;
+            }
+            line_number += 1;
+            self.lines_read += 1;
+            let line = String::from_utf8_lossy(&buffer);
+            if !self.auth_message_parser.is_auth_failed_message(&line) {
+                continue;
+            }
+            let time = match self.timestamp_parser.parse(&line, reference_time) {
+                Some(time) => time,
+                None => {
+                    eprintln!(
+                        "{}:{}: Skipping failed attempt without timestamp",
+                        filepath, line_number
+                    );
+                    continue;
+                }
+            };
+            self.process_failed_attempt(filepath, line_number, time);
+        }
+        return Ok(());
+    }
+
+    fn process_failed_attempt(&mut self, filepath: &str, line_number: usize, time: SystemTime) {
+        self.failed_attempts += 1;
+        if self.counter.should_reset(time) {
+            self.counter.reset(time);
+        }
+        self.counter.increase(1, time);
+        if !self.counter.is_limit_reached() {
+            return;
+        }
+        let event = ReplayEvent {
+            filepath: String::from(filepath),
+            line_number,
+            time,
+            failed_attempts: self.counter.failed_attempts(),
+        };
+        println!("{}", event);
+        self.events.push(event);
+        // The action would have stopped the machine, so counting starts from scratch.
+        self.counter.reset(time);
+    }
+
+    pub fn print_summary(&self) {
+        println!(
+            "Replay finished: {} line(s) read, {} failed attempt(s), action would have fired {} time(s)",
+            self.lines_read,
+            self.failed_attempts,
+            self.events.len()
+        );
+    }
+}
+
+impl Display for ReplayEvent {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let time: DateTime<Local> = DateTime::from(self.time);
+        return write!(
+            formatter,
+            "{}:{}: {} action would fire after {} failed attempt(s)",
+            self.filepath,
+            self.line_number,
+            time.to_rfc3339(),
+            self.failed_attempts
+        );
+    }
+}
+
+#[cfg(test)]
+#[path = "./auth_log_replay_tests.rs"]
+mod tests;
@@ -0,0 +1,164 @@
+use std::env::temp_dir;
+use std::fs::{remove_file, File};
+use std::io::{Cursor, Write};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Duration, FixedOffset};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::auth_log_replay::AuthLogReplay;
+use crate::auth_message_parser::AuthMessageParser;
+use crate::auth_monitor_options::AuthMonitorOptions;
+use crate::test_utils::test_file::AUTH_FAILED_TEST_MESSAGES;
+
+const OPTIONS: AuthMonitorOptions = AuthMonitorOptions {
+    max_failed_attempts: 3,
+    warning_failed_attempts: 0,
+    reset_after_seconds: 60,
+    clock_skew_tolerance_seconds: 60,
+    grace_period_seconds: 0,
+};
+
+const START_TIME: &str = "2024-02-10T14:27:16.721168+01:00";
+const OTHER_MESSAGE: &str =
+    "workstation CRON[9419]: pam_unix(cron:session): session closed for user root";
+
+struct LogBuilder {
+    time: DateTime<FixedOffset>,
+    content: String,
+}
+
+impl LogBuilder {
+    fn new() -> LogBuilder {
+        return LogBuilder {
+            time: DateTime::parse_from_rfc3339(START_TIME).unwrap(),
+            content: String::new(),
+        };
+    }
+
+    fn wait(mut self, seconds: i64) -> LogBuilder {
+        self.time += Duration::seconds(seconds);
+        return self;
+    }
+
+    fn failed(mut self, count: usize) -> LogBuilder {
+        for i in 0..count {
+            let message = AUTH_FAILED_TEST_MESSAGES[i % AUTH_FAILED_TEST_MESSAGES.len()];
+            self.content += &format!("{} {}\n", self.time.to_rfc3339(), message);
+        }
+        return self;
+    }
+
+    fn failed_by(mut self, user: &str) -> LogBuilder {
+        let message = format!(
+            "workstation sudo: pam_unix(sudo:auth): authentication failure; logname={} uid=1000 euid=0 tty=/dev/pts/7 ruser={} rhost=  user={}",
+            user, user, user
+        );
+        self.content += &format!("{} {}\n", self.time.to_rfc3339(), message);
+        return self;
+    }
+
+    fn other(mut self) -> LogBuilder {
+        self.content += &format!("{} {}\n", self.time.to_rfc3339(), OTHER_MESSAGE);
+        return self;
+    }
+}
+
+fn replay(content: &str) -> AuthLogReplay {
+    return replay_with_parser(content, AuthMessageParser::with_rules(&[], &[], &[]));
+}
+
+fn replay_with_parser(content: &str, auth_message_parser: AuthMessageParser) -> AuthLogReplay {
+    let mut replay = AuthLogReplay::new(OPTIONS, auth_message_parser);
+    replay
+        .replay_lines("auth.log", Cursor::new(content), SystemTime::now())
+        .expect("Error replaying lines");
+    return replay;
+}
+
+#[test]
+fn when_limit_is_reached_then_event_is_recorded_at_triggering_line() {
+    let log = LogBuilder::new().other().failed(2).other().failed(1);
+    let replay = replay(&log.content);
+    let events = replay.events;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].filepath, "auth.log");
+    assert_eq!(events[0].line_number, 5);
+    assert_eq!(events[0].failed_attempts, 3);
+}
+
+#[test]
+fn when_failures_are_further_apart_than_reset_time_in_log_then_no_event_is_recorded() {
+    let log = LogBuilder::new()
+        .failed(2)
+        .wait(OPTIONS.reset_after_seconds as i64 + 1)
+        .failed(2)
+        .wait(OPTIONS.reset_after_seconds as i64 + 1)
+        .failed(2);
+    assert!(replay(&log.content).events.is_empty());
+}
+
+#[test]
+fn when_failures_are_within_reset_time_in_log_then_event_is_recorded() {
+    let log = LogBuilder::new()
+        .failed(1)
+        .wait(OPTIONS.reset_after_seconds as i64)
+        .failed(1)
+        .wait(OPTIONS.reset_after_seconds as i64)
+        .failed(1);
+    assert_eq!(replay(&log.content).events.len(), 1);
+}
+
+#[test]
+fn when_limit_is_reached_then_counting_starts_again() {
+    let log = LogBuilder::new().failed(7);
+    let replay = replay(&log.content);
+    let line_numbers: Vec<usize> = replay
+        .events
+        .iter()
+        .map(|event| {
+            return event.line_number;
+        })
+        .collect();
+    assert_eq!(line_numbers, [3, 6]);
+}
+
+#[test]
+fn when_failed_attempt_has_no_timestamp_then_it_is_skipped() {
+    let content = format!(
+        "{0}\n{0}\n{0}\n{0}\n",
+        "workstation unix_chkpwd[222793]: password check failed for user (john)"
+    );
+    assert!(replay(&content).events.is_empty());
+}
+
+#[test]
+fn when_file_is_gzip_compressed_then_it_is_decompressed() {
+    let log = LogBuilder::new().failed(3);
+    let path = temp_dir().join("auth-monitor-replay-test.log.gz");
+    let mut encoder = GzEncoder::new(
+        File::create(&path).expect("Error creating test file"),
+        Compression::default(),
+    );
+    encoder.write_all(log.content.as_bytes()).unwrap();
+    encoder.finish().unwrap();
+
+    let path = path.to_str().unwrap();
+    let mut replay = AuthLogReplay::new(OPTIONS, AuthMessageParser::with_rules(&[], &[], &[]));
+    let result = replay.replay_file(path);
+    remove_file(path).expect("Unable to remove test file");
+
+    result.expect("Error replaying file");
+    assert_eq!(replay.events.len(), 1);
+    assert_eq!(replay.events[0].line_number, 3);
+}
+
+#[test]
+fn when_user_is_ignored_then_their_failures_are_not_counted() {
+    let log = LogBuilder::new().failed(2).failed_by("guest");
+    let parser = AuthMessageParser::with_rules(&[], &[String::from("guest")], &[]);
+
+    assert_eq!(replay(&log.content).events.len(), 1);
+    assert!(replay_with_parser(&log.content, parser).events.is_empty());
+}
@@ -1,14 +1,47 @@
+use std::fmt::{Display, Formatter};
+
+const PAM_UNIX_PREFIX: &str = "pam_unix(";
+const PAM_SESSION_OPENED_MESSAGE: &str = ":session): session opened for user ";
+const PAM_SESSION_OPENED_BY: &str = " by ";
+const PAM_NON_INTERACTIVE_SERVICES: [&str; 2] = ["cron", "systemd-user"];
+const SSHD_PROGRAMS: [&str; 2] = ["sshd", "sshd-session"];
+const SSHD_ACCEPTED_MESSAGE: &str = "Accepted ";
+const SSHD_ACCEPTED_USER_PREFIX: &str = " for ";
+const FAILED_USER_PREFIXES: [&str; 3] = [" user=", " for user ", " for invalid user "];
+const PAM_MODULE_PREFIX: &str = "pam_";
+const SYSLOG_TAG_END: &str = ": ";
+
 pub struct AuthMessageParser {
     patterns: Vec<AuthFailedMessagePattern>,
+    ignore_users: Vec<String>,
+    ignore_services: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct AuthFailedMessagePattern {
+    pub prefix: String,
+    pub message: String,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct AuthFailedMessage {
+    pub user: Option<String>,
+    pub service: Option<String>,
+    pub pattern: String,
 }
 
-struct AuthFailedMessagePattern {
-    prefix: String,
-    message: String,
+impl Display for AuthFailedMessagePattern {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(formatter, "{}: {}", self.prefix, self.message);
+    }
 }
 
 impl AuthMessageParser {
-    pub fn new() -> AuthMessageParser {
+    pub fn with_rules(
+        extra_patterns: &[AuthFailedMessagePattern],
+        ignore_users: &[String],
+        ignore_services: &[String],
+    ) -> AuthMessageParser {
         let pam_message = AuthFailedMessagePattern {
             prefix: String::from("pam_unix"),
             message: String::from("authentication failure"),
@@ -17,25 +50,168 @@ impl AuthMessageParser {
             prefix: String::from("unix_chkpwd"),
             message: String::from("password check failed"),
         };
+        let mut patterns = vec![pam_message, unix_chkpwd_message];
+        patterns.extend_from_slice(extra_patterns);
         return AuthMessageParser {
-            patterns: vec![pam_message, unix_chkpwd_message],
+            patterns,
+            ignore_users: ignore_users.to_vec(),
+            ignore_services: ignore_services.to_vec(),
         };
     }
 
+    pub fn pattern_count(&self) -> usize {
+        return self.patterns.len();
+    }
+
     pub fn is_auth_failed_message(&self, message: &str) -> bool {
+        return self.parse_auth_failed_message(message).is_some();
+    }
+
+    pub fn parse_auth_failed_message(&self, message: &str) -> Option<AuthFailedMessage> {
+        let pattern = self.find_auth_failed_pattern(message)?;
+        let auth_failed_message = AuthFailedMessage {
+            user: Self::get_failed_user(message),
+            service: Self::get_service(message),
+            pattern: pattern.to_string(),
+        };
+        if self.is_ignored(&auth_failed_message) {
+            return None;
+        }
+        return Some(auth_failed_message);
+    }
+
+    fn find_auth_failed_pattern(&self, message: &str) -> Option<&AuthFailedMessagePattern> {
         for pattern in &self.patterns {
             match message.find(&pattern.prefix) {
                 None => {}
                 Some(prefix_position) => {
                     let message_after_prefix = &message[prefix_position + pattern.prefix.len()..];
                     if message_after_prefix.contains(&pattern.message) {
-                        return true;
+                        return Some(pattern);
                     }
                 }
             };
         }
+        return None;
+    }
+
+    fn is_ignored(&self, message: &AuthFailedMessage) -> bool {
+        if let Some(user) = &message.user {
+            if self.ignore_users.contains(user) {
+                return true;
+            }
+        }
+        if let Some(service) = &message.service {
+            if self.ignore_services.contains(service) {
+                return true;
+            }
+        }
         return false;
     }
+
+    fn get_failed_user(message: &str) -> Option<String> {
+        for prefix in FAILED_USER_PREFIXES {
+            if let Some(message_after_prefix) = Self::after(message, prefix) {
+                let user = Self::take_token(message_after_prefix)
+                    .trim_start_matches('(')
+                    .trim_end_matches([')', ';']);
+                if !user.is_empty() {
+                    return Some(String::from(user));
+                }
+            }
+        }
+        return None;
+    }
+
+    fn get_service(message: &str) -> Option<String> {
+        if let Some(message_after_module) = Self::after(message, PAM_MODULE_PREFIX) {
+            let service = Self::after(message_after_module, "(")
+                .and_then(|message_after_parenthesis| {
+                    return message_after_parenthesis.split(':').next();
+                })
+                .unwrap_or("");
+            if !service.is_empty() && !service.contains(char::is_whitespace) {
+                return Some(String::from(service));
+            }
+        }
+        let tag_end = message.find(SYSLOG_TAG_END)?;
+        let tag = message[..tag_end].split_whitespace().last()?;
+        let service = tag.split('[').next().unwrap_or(tag);
+        return Self::non_empty_string(service);
+    }
+
+    fn take_token(message: &str) -> &str {
+        let end = message.find(char::is_whitespace).unwrap_or(message.len());
+        return &message[..end];
+    }
+
+    pub fn get_auth_succeeded_user(&self, message: &str) -> Option<String> {
+        return match self.get_pam_session_user(message) {
+            Some(user) => Some(user),
+            None => self.get_sshd_accepted_user(message),
+        };
+    }
+
+    /// Only the text right after the syslog tag is matched, so that the
+    /// message cannot be smuggled into another one, for example in a user name.
+    fn get_pam_session_user(&self, message: &str) -> Option<String> {
+        let (_, text) = Self::split_tag(message)?;
+        let message_after_prefix = text.strip_prefix(PAM_UNIX_PREFIX)?;
+        let service_end = message_after_prefix.find(PAM_SESSION_OPENED_MESSAGE)?;
+        let service = &message_after_prefix[..service_end];
+        if PAM_NON_INTERACTIVE_SERVICES.contains(&service) {
+            return None;
+        }
+        let message_after_service =
+            &message_after_prefix[service_end + PAM_SESSION_OPENED_MESSAGE.len()..];
+        if let Some(message_after_by) = Self::after(message_after_service, PAM_SESSION_OPENED_BY) {
+            let user = Self::take_user(message_after_by);
+            if !user.is_empty() {
+                return Some(String::from(user));
+            }
+        }
+        return Self::non_empty_string(Self::take_user(message_after_service));
+    }
+
+    fn get_sshd_accepted_user(&self, message: &str) -> Option<String> {
+        let (tag, text) = Self::split_tag(message)?;
+        let (program, pid) = tag.strip_suffix(']')?.split_once('[')?;
+        if !SSHD_PROGRAMS.contains(&program) || pid.parse::<u32>().is_err() {
+            return None;
+        }
+        let message_after_accepted = text.strip_prefix(SSHD_ACCEPTED_MESSAGE)?;
+        let message_after_for = Self::after(message_after_accepted, SSHD_ACCEPTED_USER_PREFIX)?;
+        return Self::non_empty_string(Self::take_user(message_after_for));
+    }
+
+    /// Splits a log line into the syslog tag, such as "sshd[2423]", and the
+    /// text following it.
+    fn split_tag(message: &str) -> Option<(&str, &str)> {
+        let tag_end = message.find(SYSLOG_TAG_END)?;
+        let tag = message[..tag_end].split_whitespace().last()?;
+        return Some((tag, &message[tag_end + SYSLOG_TAG_END.len()..]));
+    }
+
+    fn after<'a>(message: &'a str, pattern: &str) -> Option<&'a str> {
+        let position = message.find(pattern)?;
+        return Some(&message[position + pattern.len()..]);
+    }
+
+    fn take_user(message: &str) -> &str {
+        let end = message
+            .find(|character: char| {
+                return character == '(' || character.is_whitespace();
+            })
+            .unwrap_or(message.len());
+        return &message[..end];
+    }
+
+    fn non_empty_string(value: &str) -> Option<String> {
+        return match value.is_empty() {
+            true => None,
+            false => Some(String::from(value)),
+        };
+    }
 }
 
 #[cfg(test)]
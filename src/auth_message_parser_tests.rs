@@ -1,9 +1,9 @@
-use crate::auth_message_parser::AuthMessageParser;
+use crate::auth_message_parser::{AuthFailedMessage, AuthFailedMessagePattern, AuthMessageParser};
 use crate::test_utils::test_file::AUTH_FAILED_TEST_MESSAGES;
 
 #[test]
 fn when_message_is_auth_failed_message_then_returns_true() {
-    let parser = AuthMessageParser::new();
+    let parser = AuthMessageParser::with_rules(&[], &[], &[]);
     for message in AUTH_FAILED_TEST_MESSAGES {
         assert!(parser.is_auth_failed_message(message));
     }
@@ -50,8 +50,108 @@ fn when_message_is_not_auth_failed_message_then_returns_false() {
 2024-02-10T14:34:24.371421+01:00 workstation sudo:   john : TTY=pts/3 ; PWD=/home/john ; USER=root ; COMMAND=/usr/bin/ls
 2024-02-10T14:34:24.372326+01:00 workstation sudo: pam_unix(sudo:session): session opened for user root(uid=0) by john(uid=1000)
 2024-02-10T14:34:24.374716+01:00 workstation sudo: pam_unix(sudo:session): session closed for user root";
-    let parser = AuthMessageParser::new();
+    let parser = AuthMessageParser::with_rules(&[], &[], &[]);
     for message in messages.split('\n') {
         assert!(!parser.is_auth_failed_message(message));
     }
 }
+
+#[test]
+fn when_message_is_interactive_session_opened_message_then_return_authenticated_user() {
+    let messages = [
+        ("2024-02-10T14:27:21.840142+01:00 workstation sddm-helper: pam_unix(sddm:session): session opened for user john(uid=1000) by (uid=0)", "john"),
+        ("2024-02-10T14:34:24.372326+01:00 workstation sudo: pam_unix(sudo:session): session opened for user root(uid=0) by john(uid=1000)", "john"),
+        ("Feb 10 14:34:24 workstation su: pam_unix(su:session): session opened for user root by alice(uid=1001)", "alice"),
+        ("2024-02-10T14:35:01.000000+01:00 workstation sshd[2423]: Accepted publickey for bob from 192.168.1.10 port 51122 ssh2: ED25519 SHA256:abc", "bob"),
+        ("2024-02-10T14:35:01.000000+01:00 workstation sshd-session[2423]: Accepted password for carol from 192.168.1.10 port 51122 ssh2", "carol"),
+    ];
+    let parser = AuthMessageParser::with_rules(&[], &[], &[]);
+    for (message, user) in messages {
+        assert_eq!(
+            parser.get_auth_succeeded_user(message).as_deref(),
+            Some(user),
+            "Message: {}",
+            message
+        );
+    }
+}
+
+#[test]
+fn when_message_is_not_interactive_auth_success_then_return_no_user() {
+    let messages = [
+        "2024-02-10T14:30:01.170069+01:00 workstation CRON[9419]: pam_unix(cron:session): session opened for user root(uid=0) by (uid=0)",
+        "2024-02-10T14:27:21.889222+01:00 workstation (systemd): pam_unix(systemd-user:session): session opened for user john(uid=1000) by (uid=0)",
+        "2024-02-10T14:34:24.374716+01:00 workstation sudo: pam_unix(sudo:session): session closed for user root",
+        "2024-02-10T14:35:01.000000+01:00 workstation sshd[2423]: Failed password for bob from 192.168.1.10 port 51122 ssh2",
+        "2024-02-10T14:35:01.000000+01:00 workstation sshd[2423]: Invalid user x: Accepted publickey for bob from 192.168.1.10",
+        "2024-02-10T14:35:01.000000+01:00 workstation sudo: john : COMMAND=/bin/echo sshd[1]: Accepted password for bob",
+        "2024-02-10T14:35:01.000000+01:00 workstation sudo: john : COMMAND=/bin/echo pam_unix(sudo:session): session opened for user root by alice(uid=1001)",
+        "2024-02-10T14:35:01.000000+01:00 workstation sshd: Accepted password for bob from 192.168.1.10 port 51122 ssh2",
+        "2024-02-10T14:35:01.000000+01:00 workstation notsshd[2423]: Accepted password for bob from 192.168.1.10 port 51122 ssh2",
+    ];
+    let parser = AuthMessageParser::with_rules(&[], &[], &[]);
+    for message in messages.iter().chain(AUTH_FAILED_TEST_MESSAGES.iter()) {
+        assert_eq!(
+            parser.get_auth_succeeded_user(message),
+            None,
+            "Message: {}",
+            message
+        );
+    }
+}
+
+#[test]
+fn when_parsing_auth_failed_message_then_return_user_and_service() {
+    let pam_unix = "pam_unix: authentication failure";
+    let unix_chkpwd = "unix_chkpwd: password check failed";
+    let expected = [
+        ("john", "sudo", pam_unix),
+        ("john", "kde", pam_unix),
+        ("john", "unix_chkpwd", unix_chkpwd),
+        ("john", "kde", pam_unix),
+        ("john", "kde-fingerprint", pam_unix),
+        ("john", "kde-smartcard", pam_unix),
+    ];
+    let parser = AuthMessageParser::with_rules(&[], &[], &[]);
+    for (message, (user, service, pattern)) in AUTH_FAILED_TEST_MESSAGES.iter().zip(expected) {
+        let expected = AuthFailedMessage {
+            user: Some(String::from(user)),
+            service: Some(String::from(service)),
+            pattern: String::from(pattern),
+        };
+        assert_eq!(parser.parse_auth_failed_message(message), Some(expected));
+    }
+}
+
+#[test]
+fn when_user_or_service_is_ignored_then_message_is_not_auth_failed_message() {
+    let parser =
+        AuthMessageParser::with_rules(&[], &[String::from("john")], &[String::from("unix_chkpwd")]);
+    for message in AUTH_FAILED_TEST_MESSAGES {
+        assert!(!parser.is_auth_failed_message(message));
+    }
+    let parser = AuthMessageParser::with_rules(&[], &[], &[String::from("sudo")]);
+    assert!(!parser.is_auth_failed_message(AUTH_FAILED_TEST_MESSAGES[0]));
+    assert!(parser.is_auth_failed_message(AUTH_FAILED_TEST_MESSAGES[1]));
+}
+
+#[test]
+fn when_extra_pattern_is_configured_then_matching_message_is_auth_failed_message() {
+    let message = "2024-02-10T14:35:01.000000+01:00 workstation sshd[2423]: Failed password for invalid user bob from 192.168.1.10 port 51122 ssh2";
+    assert!(!AuthMessageParser::with_rules(&[], &[], &[]).is_auth_failed_message(message));
+
+    let pattern = AuthFailedMessagePattern {
+        prefix: String::from("sshd"),
+        message: String::from("Failed password"),
+    };
+    let parser = AuthMessageParser::with_rules(&[pattern], &[], &[]);
+    let expected = AuthFailedMessage {
+        user: Some(String::from("bob")),
+        service: Some(String::from("sshd")),
+        pattern: String::from("sshd: Failed password"),
+    };
+    assert_eq!(parser.parse_auth_failed_message(message), Some(expected));
+    for message in AUTH_FAILED_TEST_MESSAGES {
+        assert!(parser.is_auth_failed_message(message));
+    }
+}
@@ -1,75 +1,631 @@
 use std::error::Error;
+use std::mem;
 use std::time::{Duration, SystemTime};
 
+use chrono::{DateTime, Local};
+
+use crate::action::Action;
+use crate::action_context::{ActionContext, FailedAttempt};
 use crate::auth_file_watcher::AuthFileWatcher;
-use crate::auth_message_parser::AuthMessageParser;
+use crate::auth_message_parser::{AuthFailedMessage, AuthMessageParser};
 use crate::auth_monitor_options::AuthMonitorOptions;
 use crate::auth_monitor_params::AuthMonitorParams;
+use crate::auth_monitor_status::AuthMonitorStatus;
+use crate::disarm_state::DisarmState;
+use crate::duration_parser::MAX_DURATION;
+use crate::failed_attempts_counter::FailedAttemptsCounter;
+use crate::log_timestamp_parser::LogTimestampParser;
+use crate::logger::LogEntry;
+use crate::metrics::Metrics;
+use crate::tamper_detector::{TamperDetector, TamperEvent};
+use crate::tamper_options::{TamperOptions, TamperResponse};
+use crate::terminal_broadcast::TerminalBroadcast;
+use crate::termination_log::TerminationLog;
+
+const MAX_RECORDED_ATTEMPTS: usize = 50;
 
 pub struct AuthMonitor {
-    failed_attempts: i32,
-    options: AuthMonitorOptions,
+    filepath: String,
+    counter: FailedAttemptsCounter,
     file_watcher: AuthFileWatcher,
     auth_message_parser: AuthMessageParser,
-    last_failed_auth: SystemTime,
+    timestamp_parser: LogTimestampParser,
+    options: AuthMonitorOptions,
+    actions: Vec<Action>,
+    warning_actions: Vec<Action>,
+    dry_run: bool,
+    dry_run_events: Vec<DryRunEvent>,
+    cancel_users: Vec<String>,
+    countdown_deadline: Option<SystemTime>,
+    disarmed_until: Option<SystemTime>,
+    disarm_state: DisarmState,
+    last_event: Option<(SystemTime, String)>,
+    metrics: Metrics,
+    recorded_attempts: Vec<FailedAttempt>,
+    last_failure: Option<AuthFailedMessage>,
+    tamper: TamperOptions,
+    tamper_detector: TamperDetector,
+    pending_tamper_events: Vec<TamperEvent>,
+    broadcast: fn(&str),
+}
+
+enum AuthLogEvent {
+    Failed(SystemTime, AuthFailedMessage, String),
+    Succeeded(String),
+}
+
+struct DryRunEvent {
+    time: SystemTime,
+    failed_attempts: i32,
 }
 
 impl AuthMonitor {
     pub fn new(params: AuthMonitorParams) -> Result<AuthMonitor, Box<dyn Error>> {
         params.validate()?;
+        let disarm_state = DisarmState::new(&params.state_directory);
+        let disarmed_until = Self::load_disarmed_until(&disarm_state);
+        let pending_tamper_events = Self::check_terminations(
+            &TerminationLog::new(&params.state_directory),
+            &params.tamper,
+        );
         return Ok(AuthMonitor {
-            failed_attempts: 0,
-            options: params.options,
+            filepath: params.filepath.clone(),
+            counter: FailedAttemptsCounter::new(params.options),
             file_watcher: AuthFileWatcher::new(&params.filepath)?,
-            auth_message_parser: AuthMessageParser::new(),
-            last_failed_auth: SystemTime::UNIX_EPOCH,
+            auth_message_parser: AuthMessageParser::with_rules(
+                &params.patterns,
+                &params.ignore_users,
+                &params.ignore_services,
+            ),
+            timestamp_parser: LogTimestampParser::new(),
+            options: params.options,
+            actions: params.actions,
+            warning_actions: params.warning_actions,
+            dry_run: params.dry_run,
+            dry_run_events: Vec::new(),
+            cancel_users: params.cancel_users,
+            countdown_deadline: None,
+            disarmed_until,
+            disarm_state,
+            last_event: None,
+            metrics: Metrics::default(),
+            recorded_attempts: Vec::new(),
+            last_failure: None,
+            tamper: params.tamper,
+            tamper_detector: TamperDetector::default(),
+            pending_tamper_events,
+            broadcast: TerminalBroadcast::send,
         });
     }
 
-    pub fn update(&mut self, on_max_failed_attempts: impl FnOnce()) {
-        if self.should_reset_failed_attempts() {
-            self.reset_failed_attempts();
+    fn load_disarmed_until(disarm_state: &DisarmState) -> Option<SystemTime> {
+        let deadline = match disarm_state.load() {
+            Ok(Some(deadline)) => deadline,
+            Ok(None) => return None,
+            Err(error) => {
+                LogEntry::error("state_error", &format!("{}, staying armed", error)).log();
+                return None;
+            }
+        };
+        let now = SystemTime::now();
+        if deadline <= now {
+            return None;
         }
-        let mut failed_attempts = 0;
+        if deadline.duration_since(now).unwrap_or(Duration::ZERO) > MAX_DURATION {
+            let message = "Disarm state is too far in the future, staying armed";
+            LogEntry::error("state_error", message).log();
+            return None;
+        }
+        let time: DateTime<Local> = DateTime::from(deadline);
+        let message = format!("Actions disarmed until {}", time.to_rfc3339());
+        LogEntry::warning("disarmed", &message).log();
+        return Some(deadline);
+    }
+
+    /// Reports repeated unexpected terminations once, forgetting them so the
+    /// next ones are counted from zero.
+    fn check_terminations(
+        termination_log: &TerminationLog,
+        options: &TamperOptions,
+    ) -> Vec<TamperEvent> {
+        let terminations = match termination_log.load() {
+            Ok(terminations) => terminations,
+            Err(error) => {
+                LogEntry::error("state_error", &error.to_string()).log();
+                return Vec::new();
+            }
+        };
+        if terminations.is_empty() {
+            return Vec::new();
+        }
+        let message = format!(
+            "Monitoring process has been terminated unexpectedly {} time(s) before",
+            terminations.len()
+        );
+        LogEntry::warning("restarted", &message).log();
+        if !TamperDetector::too_many_terminations(options, &terminations, SystemTime::now()) {
+            return Vec::new();
+        }
+        if let Err(error) = termination_log.clear() {
+            LogEntry::error("state_error", &error.to_string()).log();
+        }
+        return vec![TamperEvent::Terminated];
+    }
+
+    pub fn reload(&mut self, params: AuthMonitorParams) -> Result<(), Box<dyn Error>> {
+        params.validate()?;
+        let file_watcher = match params.filepath == self.filepath {
+            true => None,
+            false => Some(AuthFileWatcher::new(&params.filepath)?),
+        };
+        if let Some(file_watcher) = file_watcher {
+            self.metrics.file_reopens += self.file_watcher.reopen_count();
+            self.metrics.file_rotations += self.file_watcher.rotation_count();
+            self.file_watcher = file_watcher;
+            self.filepath = params.filepath;
+        }
+        self.auth_message_parser = AuthMessageParser::with_rules(
+            &params.patterns,
+            &params.ignore_users,
+            &params.ignore_services,
+        );
+        self.counter.set_options(params.options);
+        self.options = params.options;
+        self.actions = params.actions;
+        self.warning_actions = params.warning_actions;
+        self.dry_run = params.dry_run;
+        self.cancel_users = params.cancel_users;
+        self.tamper = params.tamper;
+        return Ok(());
+    }
+
+    pub fn update(&mut self, mut execute_actions: impl FnMut(&[Action], &ActionContext)) {
+        let now = SystemTime::now();
+        if self.counter.should_reset(now) {
+            self.reset_failed_attempts(now);
+        }
+        if self.disarmed_until.is_some_and(|deadline| {
+            return now >= deadline;
+        }) {
+            LogEntry::info("disarm_ended", "Disarm period ended").log();
+            self.arm();
+        }
+        let mut events = Vec::new();
         self.file_watcher.update(|line| {
-            if self.auth_message_parser.is_auth_failed_message(line) {
+            self.metrics.lines_read += 1;
+            if let Some(message) = self.auth_message_parser.parse_auth_failed_message(line) {
+                let time = self.timestamp_parser.parse(line, now).unwrap_or(now);
+                events.push(AuthLogEvent::Failed(
+                    time,
+                    message,
+                    String::from(line.trim_end()),
+                ));
+            } else if let Some(user) = self.auth_message_parser.get_auth_succeeded_user(line) {
+                events.push(AuthLogEvent::Succeeded(user));
+            }
+        });
+        let previous_failed_attempts = self.counter.failed_attempts();
+        let mut failed_attempts = 0;
+        for event in events {
+            match event {
+                AuthLogEvent::Failed(time, message, line) => {
+                    self.metrics.record_failure(&message.pattern);
+                    if self.increase_failed_attempts(time, now) {
+                        failed_attempts += 1;
+                        self.last_event = Some((time, String::from("failed authentication")));
+                        self.log_failed_attempt(&message);
+                        self.record_attempt(time, message, line);
+                    }
+                }
+                AuthLogEvent::Succeeded(user) => {
+                    let description = format!("successful authentication of user {}", user);
+                    LogEntry::debug("auth_succeeded", "Authentication succeeded")
+                        .user(Some(&user))
+                        .file(&self.filepath)
+                        .log();
+                    self.last_event = Some((now, description));
+                    self.cancel_countdown_by_user(&user);
+                }
+            }
+        }
+        let mut tamper_events = mem::take(&mut self.pending_tamper_events);
+        tamper_events.extend(self.file_watcher.take_tamper_events());
+        tamper_events.extend(self.tamper_detector.check(
+            &self.tamper,
+            self.file_watcher.missing_since(),
+            self.file_watcher.last_line_time(),
+            now,
+        ));
+        for event in tamper_events {
+            if self.handle_tamper_event(event, now, &mut execute_actions) {
                 failed_attempts += 1;
             }
+        }
+        if self.should_execute_warning_actions(previous_failed_attempts) {
+            self.metrics.actions_fired += self.warning_actions.len() as u64;
+            execute_actions(&self.warning_actions, &self.action_context());
+        }
+        if self.should_execute_actions(failed_attempts > 0, now) {
+            LogEntry::warning(
+                "limit_reached",
+                "Authentication fail limit reached, shutting down",
+            )
+            .counter(self.counter.failed_attempts())
+            .log();
+            self.metrics.actions_fired += self.actions.len() as u64;
+            execute_actions(&self.actions, &self.action_context());
+        }
+    }
+
+    /// Logs the event and responds to it as configured. Returns whether it
+    /// has been counted as a failed attempt.
+    fn handle_tamper_event(
+        &mut self,
+        event: TamperEvent,
+        now: SystemTime,
+        execute_actions: &mut impl FnMut(&[Action], &ActionContext),
+    ) -> bool {
+        self.metrics.tamper_events += 1;
+        let description = event.description(&self.tamper);
+        LogEntry::warning("tamper_detected", &description)
+            .file(&self.filepath)
+            .log();
+        let actions = match event.response(&self.tamper) {
+            TamperResponse::Ignore => return false,
+            TamperResponse::Count => {
+                if !self.increase_failed_attempts(now, now) {
+                    return false;
+                }
+                self.last_event = Some((now, format!("monitored file {}", event)));
+                LogEntry::info(
+                    "tamper_counted",
+                    &format!(
+                        "Tampering counted as failed attempt, {} in total",
+                        self.counter.failed_attempts()
+                    ),
+                )
+                .counter(self.counter.failed_attempts())
+                .log();
+                self.push_attempt(now, description);
+                return true;
+            }
+            TamperResponse::Actions(actions) => actions.clone(),
+        };
+        let names = actions
+            .iter()
+            .map(Action::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        let message = if self.dry_run {
+            format!("Monitored file {}, dry run: would execute {}", event, names)
+        } else if self.is_disarmed() {
+            format!(
+                "Monitored file {}, {} suppressed while disarmed",
+                event, names
+            )
+        } else {
+            format!("Monitored file {}, executing {}", event, names)
+        };
+        LogEntry::warning("tamper_actions", &message)
+            .file(&self.filepath)
+            .log();
+        if !self.dry_run && !self.is_disarmed() {
+            self.metrics.actions_fired += actions.len() as u64;
+            execute_actions(&actions, &self.action_context());
+        }
+        return false;
+    }
+
+    /// The warning actions are executed once, when the counter crosses the
+    /// warning threshold without reaching the limit.
+    fn should_execute_warning_actions(&self, previous_failed_attempts: i32) -> bool {
+        let warning_failed_attempts = self.options.warning_failed_attempts;
+        let failed_attempts = self.counter.failed_attempts();
+        if self.warning_actions.is_empty()
+            || warning_failed_attempts == 0
+            || previous_failed_attempts >= warning_failed_attempts
+            || failed_attempts < warning_failed_attempts
+            || self.counter.is_limit_reached()
+        {
+            return false;
+        }
+        let actions = self
+            .warning_actions
+            .iter()
+            .map(Action::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        let message = if self.dry_run {
+            format!(
+                "Warning threshold reached, dry run: would execute {}",
+                actions
+            )
+        } else if self.is_disarmed() {
+            format!(
+                "Warning threshold reached, {} suppressed while disarmed",
+                actions
+            )
+        } else {
+            format!("Warning threshold reached, executing {}", actions)
+        };
+        LogEntry::warning("warning_reached", &message)
+            .counter(failed_attempts)
+            .log();
+        return !self.dry_run && !self.is_disarmed();
+    }
+
+    fn record_attempt(&mut self, time: SystemTime, message: AuthFailedMessage, line: String) {
+        self.push_attempt(time, line);
+        self.last_failure = Some(message);
+    }
+
+    fn push_attempt(&mut self, time: SystemTime, line: String) {
+        if self.recorded_attempts.len() == MAX_RECORDED_ATTEMPTS {
+            self.recorded_attempts.remove(0);
+        }
+        self.recorded_attempts.push(FailedAttempt {
+            time,
+            counter: self.counter.failed_attempts(),
+            line,
         });
-        if failed_attempts > 0 {
-            self.increase_failed_attempts(failed_attempts, on_max_failed_attempts);
+    }
+
+    fn action_context(&self) -> ActionContext {
+        let (user, service) = match &self.last_failure {
+            Some(message) => (message.user.clone(), message.service.clone()),
+            None => (None, None),
+        };
+        return ActionContext {
+            host: ActionContext::hostname(),
+            user,
+            service,
+            counter: self.counter.failed_attempts(),
+            max_failed_attempts: self.options.max_failed_attempts,
+            limit_actions: self.actions.clone(),
+            attempts: self.recorded_attempts.clone(),
+        };
+    }
+
+    fn log_failed_attempt(&self, message: &AuthFailedMessage) {
+        let failed_attempts = self.counter.failed_attempts();
+        LogEntry::info(
+            "auth_failed",
+            &format!("Authentication failed {} time(s)", failed_attempts),
+        )
+        .counter(failed_attempts)
+        .user(message.user.as_deref())
+        .service(message.service.as_deref())
+        .file(&self.filepath)
+        .log();
+    }
+
+    fn reset_failed_attempts(&mut self, now: SystemTime) {
+        LogEntry::info("counter_reset", "Resetting failed attempts").log();
+        self.counter.reset(now);
+        self.metrics.counter_resets += 1;
+        self.recorded_attempts.clear();
+    }
+
+    fn increase_failed_attempts(&mut self, time: SystemTime, now: SystemTime) -> bool {
+        if self.counter.is_stale(time, now) {
+            LogEntry::debug(
+                "auth_failed_ignored",
+                "Ignoring failed attempt older than reset time",
+            )
+            .log();
+            return false;
+        }
+        let time = match self.counter.is_in_future(time, now) {
+            true => {
+                LogEntry::warning(
+                    "clock_skew",
+                    "Failed attempt timestamp is in the future, using current time",
+                )
+                .log();
+                now
+            }
+            false => time,
+        };
+        if self.counter.should_reset(time) {
+            self.reset_failed_attempts(time);
+        }
+        self.counter.increase(1, time);
+        if self.counter.is_limit_reached() && self.has_grace_period() {
+            self.start_countdown(now);
+        }
+        return true;
+    }
+
+    fn has_grace_period(&self) -> bool {
+        return !self.dry_run && !self.is_disarmed() && self.options.grace_period_seconds > 0;
+    }
+
+    fn is_disarmed(&self) -> bool {
+        return self.disarmed_until.is_some();
+    }
+
+    pub fn disarm(&mut self, duration: Duration) -> Result<(), Box<dyn Error>> {
+        if duration > MAX_DURATION {
+            Err(format!(
+                "Disarm duration is longer than {} second(s)",
+                MAX_DURATION.as_secs()
+            ))?;
+        }
+        let deadline = match SystemTime::now().checked_add(duration) {
+            Some(deadline) => deadline,
+            None => Err("Disarm duration is too long")?,
+        };
+        self.disarm_state.save(deadline)?;
+        self.disarmed_until = Some(deadline);
+        let time: DateTime<Local> = DateTime::from(deadline);
+        let message = format!("Actions disarmed until {}", time.to_rfc3339());
+        LogEntry::warning("disarmed", &message).log();
+        if self.countdown_deadline.take().is_some() {
+            let message = format!("Execution of {} cancelled by disarm", self.format_actions());
+            LogEntry::info("countdown_cancelled", &message).log();
+            (self.broadcast)(&message);
+        }
+        return Ok(());
+    }
+
+    pub fn arm(&mut self) {
+        if let Err(error) = self.disarm_state.clear() {
+            LogEntry::error("state_error", &error.to_string()).log();
+        }
+        if self.disarmed_until.take().is_some() {
+            LogEntry::info("armed", "Actions armed").log();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.reset_failed_attempts(SystemTime::now());
+        if self.countdown_deadline.take().is_some() {
+            let message = format!("Execution of {} cancelled by reset", self.format_actions());
+            LogEntry::info("countdown_cancelled", &message).log();
+            (self.broadcast)(&message);
+        }
+    }
+
+    pub fn status(&self) -> AuthMonitorStatus {
+        return AuthMonitorStatus {
+            failed_attempts: self.counter.failed_attempts(),
+            max_failed_attempts: self.options.max_failed_attempts,
+            last_event: self.last_event.clone(),
+            disarmed_until: self.disarmed_until,
+            countdown_deadline: self.countdown_deadline,
+            watched_files: vec![self.filepath.clone()],
+        };
+    }
+
+    pub fn record_action_failures(&mut self, count: u64) {
+        self.metrics.action_failures += count;
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        let mut metrics = self.metrics.clone();
+        metrics.file_reopens += self.file_watcher.reopen_count();
+        metrics.file_rotations += self.file_watcher.rotation_count();
+        metrics.failed_attempts = self.counter.failed_attempts();
+        return metrics;
+    }
+
+    fn start_countdown(&mut self, now: SystemTime) {
+        if self.countdown_deadline.is_some() {
+            return;
+        }
+        let grace_period = Duration::from_secs(self.options.grace_period_seconds as u64);
+        self.countdown_deadline = Some(now + grace_period);
+        let message = format!(
+            "Authentication fail limit reached, {} will be executed in {} second(s)",
+            self.format_actions(),
+            grace_period.as_secs()
+        );
+        LogEntry::warning("countdown_started", &message)
+            .counter(self.counter.failed_attempts())
+            .log();
+        (self.broadcast)(&message);
+    }
+
+    fn cancel_countdown_by_user(&mut self, user: &str) {
+        if self.countdown_deadline.is_none() || !self.is_cancel_user(user) {
+            return;
         }
+        self.countdown_deadline = None;
+        self.reset_failed_attempts(SystemTime::now());
+        let message = format!(
+            "Execution of {} cancelled by successful authentication of user {}",
+            self.format_actions(),
+            user
+        );
+        LogEntry::info("countdown_cancelled", &message)
+            .user(Some(user))
+            .log();
+        (self.broadcast)(&message);
+    }
+
+    fn is_cancel_user(&self, user: &str) -> bool {
+        return self.cancel_users.iter().any(|cancel_user| {
+            return cancel_user == user;
+        });
     }
 
-    fn should_reset_failed_attempts(&self) -> bool {
-        if self.failed_attempts <= 0 || self.failed_attempts >= self.options.max_failed_attempts {
+    fn should_execute_actions(&mut self, failed_attempts_increased: bool, now: SystemTime) -> bool {
+        if let Some(deadline) = self.countdown_deadline {
+            if now < deadline {
+                return false;
+            }
+            self.countdown_deadline = None;
+            return true;
+        }
+        if !failed_attempts_increased || !self.counter.is_limit_reached() {
+            return false;
+        }
+        if self.dry_run {
+            self.record_dry_run_event(now);
+            return false;
+        }
+        if self.is_disarmed() {
+            let message = format!(
+                "Authentication fail limit reached, {} suppressed while disarmed",
+                self.format_actions()
+            );
+            LogEntry::warning("actions_suppressed", &message)
+                .counter(self.counter.failed_attempts())
+                .log();
+            self.reset_failed_attempts(now);
             return false;
         }
-        let seconds_from_last_error = SystemTime::now()
-            .duration_since(self.last_failed_auth)
-            .unwrap_or(Duration::ZERO)
-            .as_secs();
-        return seconds_from_last_error > self.options.reset_after_seconds as u64;
+        return !self.has_grace_period();
     }
 
-    fn reset_failed_attempts(&mut self) {
-        println!("Resetting failed attempts");
-        self.failed_attempts = 0;
-        self.last_failed_auth = SystemTime::now();
+    fn record_dry_run_event(&mut self, now: SystemTime) {
+        for action in &self.actions {
+            let message = format!(
+                "Authentication fail limit reached, dry run: would execute {}",
+                action
+            );
+            LogEntry::warning("dry_run", &message)
+                .counter(self.counter.failed_attempts())
+                .log();
+        }
+        self.dry_run_events.push(DryRunEvent {
+            time: now,
+            failed_attempts: self.counter.failed_attempts(),
+        });
+        // Continue as the machine would after being restarted by the action.
+        self.reset_failed_attempts(now);
     }
 
-    fn increase_failed_attempts(
-        &mut self,
-        failed_attempts: i32,
-        on_max_failed_attempts: impl FnOnce(),
-    ) {
-        self.last_failed_auth = SystemTime::now();
-        self.failed_attempts += failed_attempts;
-        println!("Authentication failed {} time(s)", self.failed_attempts);
-        if self.failed_attempts >= self.options.max_failed_attempts {
-            println!("Authentication fail limit reached, shutting down");
-            on_max_failed_attempts();
+    pub fn print_dry_run_summary(&self) {
+        if !self.dry_run {
+            return;
         }
+        let message = format!(
+            "Dry run summary: action would have fired {} time(s)",
+            self.dry_run_events.len()
+        );
+        LogEntry::info("dry_run_summary", &message).log();
+        for event in &self.dry_run_events {
+            let time: DateTime<Local> = DateTime::from(event.time);
+            let message = format!(
+                "{}: would execute {} after {} failed attempt(s)",
+                time.to_rfc3339(),
+                self.format_actions(),
+                event.failed_attempts
+            );
+            LogEntry::info("dry_run_summary", &message)
+                .counter(event.failed_attempts)
+                .log();
+        }
+    }
+
+    fn format_actions(&self) -> String {
+        return self
+            .actions
+            .iter()
+            .map(Action::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
     }
 }
 
@@ -0,0 +1,608 @@
+use std::error::Error;
+use std::fs;
+use std::str::FromStr;
+
+use toml::{Table, Value};
+
+use crate::action::Action;
+use crate::auth_message_parser::AuthFailedMessagePattern;
+use crate::auth_monitor_params::{
+    AuthMonitorParams, CANCEL_USERS_OPTION, CLOCK_SKEW_TOLERANCE_SECONDS_OPTION,
+    CONTROL_SOCKET_OPTION, DRY_RUN_OPTION, GRACE_PERIOD_SECONDS_OPTION, LOG_FORMAT_OPTION,
+    LOG_LEVEL_OPTION, MAX_FAILED_ATTEMPTS_OPTION, RESET_AFTER_SECONDS_OPTION,
+    STATE_DIRECTORY_OPTION, WARNING_FAILED_ATTEMPTS_OPTION,
+};
+use crate::tamper_options::TamperResponse;
+
+const LOG_FILE_KEY: &str = "log-file";
+const THRESHOLDS_KEY: &str = "thresholds";
+const PATTERNS_KEY: &str = "patterns";
+const PATTERN_PREFIX_KEY: &str = "prefix";
+const PATTERN_MESSAGE_KEY: &str = "message";
+const FILTERS_KEY: &str = "filters";
+const IGNORE_USERS_KEY: &str = "ignore-users";
+const IGNORE_SERVICES_KEY: &str = "ignore-services";
+const ACTIONS_KEY: &str = "actions";
+const EXECUTE_KEY: &str = "execute";
+const WARN_KEY: &str = "warn";
+const METRICS_KEY: &str = "metrics";
+const METRICS_TEXTFILE_KEY: &str = "textfile";
+const METRICS_ADDRESS_KEY: &str = "address";
+const WEBHOOK_KEY: &str = "webhook";
+const WEBHOOK_URL_KEY: &str = "url";
+const DESKTOP_KEY: &str = "desktop";
+const DESKTOP_SESSION_BUS_KEY: &str = "session-bus";
+const POWER_KEY: &str = "power";
+const POWER_BACKEND_KEY: &str = "backend";
+const POWER_INHIBITORS_KEY: &str = "inhibitors";
+const POWER_FALLBACK_BACKEND_KEY: &str = "fallback-backend";
+const POWER_SUDO_USER_KEY: &str = "sudo-user";
+const SYSTEM_BUS_KEY: &str = "system-bus";
+const LUKS_SUSPEND_KEY: &str = "luks-suspend";
+const LUKS_SUSPEND_DEVICES_KEY: &str = "devices";
+const LUKS_SUSPEND_HOOK_KEY: &str = "hook";
+const LUKS_SUSPEND_CRYPTSETUP_KEY: &str = "cryptsetup";
+const COMMAND_KEY: &str = "command";
+const COMMAND_EXEC_KEY: &str = "exec";
+const COMMAND_WORKING_DIRECTORY_KEY: &str = "working-directory";
+const TAMPER_KEY: &str = "tamper";
+const TAMPER_DELETED_KEY: &str = "deleted";
+const TAMPER_TRUNCATED_KEY: &str = "truncated";
+const TAMPER_ABSENT_KEY: &str = "absent";
+const TAMPER_ABSENT_SECONDS_KEY: &str = "absent-seconds";
+const TAMPER_SILENT_KEY: &str = "silent";
+const TAMPER_SILENT_SECONDS_KEY: &str = "silent-seconds";
+const TAMPER_TERMINATED_KEY: &str = "terminated";
+const TAMPER_MAX_TERMINATIONS_KEY: &str = "max-terminations";
+const TAMPER_TERMINATIONS_WINDOW_SECONDS_KEY: &str = "terminations-window-seconds";
+const EMAIL_KEY: &str = "email";
+const EMAIL_SERVER_KEY: &str = "server";
+const EMAIL_SECURITY_KEY: &str = "security";
+const EMAIL_USERNAME_KEY: &str = "username";
+const EMAIL_PASSWORD_KEY: &str = "password";
+const EMAIL_FROM_KEY: &str = "from";
+const EMAIL_TO_KEY: &str = "to";
+const TIMEOUT_SECONDS_KEY: &str = "timeout-seconds";
+const RETRIES_KEY: &str = "retries";
+const QUEUE_SIZE_KEY: &str = "queue-size";
+
+const KEY_SEPARATOR: &str = ".";
+
+pub struct AuthMonitorConfig {}
+
+impl AuthMonitorConfig {
+    pub fn load(path: &str, params: &mut AuthMonitorParams) -> Result<(), Box<dyn Error>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) => Err(format!(
+                "Error reading configuration file {}: {}",
+                path, error
+            ))?,
+        };
+        return match Self::apply(&content, params) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(format!("Invalid configuration file {}: {}", path, error))?,
+        };
+    }
+
+    pub fn apply(content: &str, params: &mut AuthMonitorParams) -> Result<(), Box<dyn Error>> {
+        let table = match content.parse::<Table>() {
+            Ok(table) => table,
+            Err(error) => Err(error.message().to_string())?,
+        };
+        for (key, value) in &table {
+            match key.as_str() {
+                LOG_FILE_KEY => params.filepath = Self::get_non_empty_string(key, value)?,
+                CONTROL_SOCKET_OPTION => {
+                    params.control_socket = Self::get_non_empty_string(key, value)?
+                }
+                STATE_DIRECTORY_OPTION => {
+                    params.state_directory = Self::get_non_empty_string(key, value)?
+                }
+                LOG_LEVEL_OPTION => params.log_level = Self::get_parsed(key, value)?,
+                LOG_FORMAT_OPTION => params.log_format = Self::get_parsed(key, value)?,
+                THRESHOLDS_KEY => Self::apply_thresholds(key, value, params)?,
+                PATTERNS_KEY => params.patterns = Self::get_patterns(key, value)?,
+                FILTERS_KEY => Self::apply_filters(key, value, params)?,
+                ACTIONS_KEY => Self::apply_actions(key, value, params)?,
+                METRICS_KEY => Self::apply_metrics(key, value, params)?,
+                WEBHOOK_KEY => Self::apply_webhook(key, value, params)?,
+                EMAIL_KEY => Self::apply_email(key, value, params)?,
+                DESKTOP_KEY => Self::apply_desktop(key, value, params)?,
+                POWER_KEY => Self::apply_power(key, value, params)?,
+                LUKS_SUSPEND_KEY => Self::apply_luks_suspend(key, value, params)?,
+                COMMAND_KEY => Self::apply_command(key, value, params)?,
+                TAMPER_KEY => Self::apply_tamper(key, value, params)?,
+                _ => Err(Self::unknown_key_error(key))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn apply_thresholds(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                MAX_FAILED_ATTEMPTS_OPTION => {
+                    params.options.max_failed_attempts = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        params.options.max_failed_attempts,
+                    )?;
+                }
+                WARNING_FAILED_ATTEMPTS_OPTION => {
+                    params.options.warning_failed_attempts = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_not_negative(
+                        &key_path,
+                        params.options.warning_failed_attempts,
+                    )?;
+                }
+                RESET_AFTER_SECONDS_OPTION => {
+                    params.options.reset_after_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        params.options.reset_after_seconds,
+                    )?;
+                }
+                CLOCK_SKEW_TOLERANCE_SECONDS_OPTION => {
+                    params.options.clock_skew_tolerance_seconds =
+                        Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_not_negative(
+                        &key_path,
+                        params.options.clock_skew_tolerance_seconds,
+                    )?;
+                }
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn get_patterns(
+        path: &str,
+        value: &Value,
+    ) -> Result<Vec<AuthFailedMessagePattern>, Box<dyn Error>> {
+        let mut patterns = Vec::new();
+        for (index, item) in Self::get_array(path, value)?.iter().enumerate() {
+            let item_path = format!("{}[{}]", path, index);
+            let mut prefix = None;
+            let mut message = None;
+            for (key, value) in Self::get_table(&item_path, item)? {
+                let key_path = Self::join(&item_path, key);
+                match key.as_str() {
+                    PATTERN_PREFIX_KEY => {
+                        prefix = Some(Self::get_non_empty_string(&key_path, value)?)
+                    }
+                    PATTERN_MESSAGE_KEY => {
+                        message = Some(Self::get_non_empty_string(&key_path, value)?)
+                    }
+                    _ => Err(Self::unknown_key_error(&key_path))?,
+                }
+            }
+            let prefix = match prefix {
+                Some(prefix) => prefix,
+                None => Err(Self::missing_key_error(&item_path, PATTERN_PREFIX_KEY))?,
+            };
+            let message = match message {
+                Some(message) => message,
+                None => Err(Self::missing_key_error(&item_path, PATTERN_MESSAGE_KEY))?,
+            };
+            patterns.push(AuthFailedMessagePattern { prefix, message });
+        }
+        return Ok(patterns);
+    }
+
+    fn apply_filters(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                IGNORE_USERS_KEY => params.ignore_users = Self::get_strings(&key_path, value)?,
+                IGNORE_SERVICES_KEY => {
+                    params.ignore_services = Self::get_strings(&key_path, value)?
+                }
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn apply_actions(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                EXECUTE_KEY => params.actions = Self::get_actions(&key_path, value)?,
+                WARN_KEY => params.warning_actions = Self::get_actions(&key_path, value)?,
+                DRY_RUN_OPTION => params.dry_run = Self::get_bool(&key_path, value)?,
+                GRACE_PERIOD_SECONDS_OPTION => {
+                    params.options.grace_period_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_not_negative(
+                        &key_path,
+                        params.options.grace_period_seconds,
+                    )?;
+                }
+                CANCEL_USERS_OPTION => params.cancel_users = Self::get_strings(&key_path, value)?,
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn apply_metrics(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                METRICS_TEXTFILE_KEY => {
+                    params.metrics_textfile = Some(Self::get_non_empty_string(&key_path, value)?)
+                }
+                METRICS_ADDRESS_KEY => {
+                    params.metrics_address = Some(Self::get_parsed(&key_path, value)?)
+                }
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn apply_webhook(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        let webhook = &mut params.webhook;
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                WEBHOOK_URL_KEY => {
+                    webhook.url = Some(Self::get_non_empty_string(&key_path, value)?)
+                }
+                TIMEOUT_SECONDS_KEY => {
+                    webhook.timeout_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        webhook.timeout_seconds,
+                    )?;
+                }
+                RETRIES_KEY => {
+                    webhook.retries = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_not_negative(&key_path, webhook.retries)?;
+                }
+                QUEUE_SIZE_KEY => {
+                    webhook.queue_size = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(&key_path, webhook.queue_size)?;
+                }
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn apply_email(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        let email = &mut params.email;
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                EMAIL_SERVER_KEY => {
+                    email.server = Some(Self::get_non_empty_string(&key_path, value)?)
+                }
+                EMAIL_SECURITY_KEY => email.security = Self::get_parsed(&key_path, value)?,
+                EMAIL_USERNAME_KEY => {
+                    email.username = Some(Self::get_non_empty_string(&key_path, value)?)
+                }
+                EMAIL_PASSWORD_KEY => {
+                    email.password = Some(Self::get_non_empty_string(&key_path, value)?)
+                }
+                EMAIL_FROM_KEY => email.from = Some(Self::get_non_empty_string(&key_path, value)?),
+                EMAIL_TO_KEY => email.to = Self::get_strings(&key_path, value)?,
+                TIMEOUT_SECONDS_KEY => {
+                    email.timeout_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        email.timeout_seconds,
+                    )?;
+                }
+                RETRIES_KEY => {
+                    email.retries = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_not_negative(&key_path, email.retries)?;
+                }
+                QUEUE_SIZE_KEY => {
+                    email.queue_size = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(&key_path, email.queue_size)?;
+                }
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn apply_desktop(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        let desktop = &mut params.desktop;
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                SYSTEM_BUS_KEY => {
+                    desktop.system_bus = Self::get_non_empty_string(&key_path, value)?
+                }
+                DESKTOP_SESSION_BUS_KEY => {
+                    desktop.session_bus = Self::get_non_empty_string(&key_path, value)?
+                }
+                TIMEOUT_SECONDS_KEY => {
+                    desktop.timeout_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        desktop.timeout_seconds,
+                    )?;
+                }
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn apply_power(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        let power = &mut params.power;
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                POWER_BACKEND_KEY => power.backend = Self::get_parsed(&key_path, value)?,
+                POWER_INHIBITORS_KEY => power.inhibitors = Self::get_parsed(&key_path, value)?,
+                POWER_FALLBACK_BACKEND_KEY => {
+                    power.fallback_backend = Self::get_parsed(&key_path, value)?
+                }
+                SYSTEM_BUS_KEY => power.system_bus = Self::get_non_empty_string(&key_path, value)?,
+                POWER_SUDO_USER_KEY => {
+                    power.sudo_user = Self::get_non_empty_string(&key_path, value)?
+                }
+                TIMEOUT_SECONDS_KEY => {
+                    power.timeout_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        power.timeout_seconds,
+                    )?;
+                }
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn apply_luks_suspend(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        let luks_suspend = &mut params.luks_suspend;
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                LUKS_SUSPEND_DEVICES_KEY => {
+                    luks_suspend.devices = Self::get_strings(&key_path, value)?
+                }
+                LUKS_SUSPEND_HOOK_KEY => luks_suspend.hook = Self::get_strings(&key_path, value)?,
+                LUKS_SUSPEND_CRYPTSETUP_KEY => {
+                    luks_suspend.cryptsetup = Self::get_non_empty_string(&key_path, value)?
+                }
+                TIMEOUT_SECONDS_KEY => {
+                    luks_suspend.timeout_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        luks_suspend.timeout_seconds,
+                    )?;
+                }
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn apply_command(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        let command = &mut params.command;
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                COMMAND_EXEC_KEY => command.exec = Self::get_strings(&key_path, value)?,
+                COMMAND_WORKING_DIRECTORY_KEY => {
+                    command.working_directory = Self::get_non_empty_string(&key_path, value)?
+                }
+                TIMEOUT_SECONDS_KEY => {
+                    command.timeout_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        command.timeout_seconds,
+                    )?;
+                }
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    fn apply_tamper(
+        path: &str,
+        value: &Value,
+        params: &mut AuthMonitorParams,
+    ) -> Result<(), Box<dyn Error>> {
+        let tamper = &mut params.tamper;
+        for (key, value) in Self::get_table(path, value)? {
+            let key_path = Self::join(path, key);
+            match key.as_str() {
+                TAMPER_DELETED_KEY => tamper.deleted = Self::get_tamper_response(&key_path, value)?,
+                TAMPER_TRUNCATED_KEY => {
+                    tamper.truncated = Self::get_tamper_response(&key_path, value)?
+                }
+                TAMPER_ABSENT_KEY => tamper.absent = Self::get_tamper_response(&key_path, value)?,
+                TAMPER_ABSENT_SECONDS_KEY => {
+                    tamper.absent_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        tamper.absent_seconds,
+                    )?;
+                }
+                TAMPER_SILENT_KEY => tamper.silent = Self::get_tamper_response(&key_path, value)?,
+                TAMPER_SILENT_SECONDS_KEY => {
+                    tamper.silent_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        tamper.silent_seconds,
+                    )?;
+                }
+                TAMPER_TERMINATED_KEY => {
+                    tamper.terminated = Self::get_tamper_response(&key_path, value)?
+                }
+                TAMPER_MAX_TERMINATIONS_KEY => {
+                    tamper.max_terminations = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        tamper.max_terminations,
+                    )?;
+                }
+                TAMPER_TERMINATIONS_WINDOW_SECONDS_KEY => {
+                    tamper.terminations_window_seconds = Self::get_integer(&key_path, value)?;
+                    AuthMonitorParams::validate_greater_than_zero(
+                        &key_path,
+                        tamper.terminations_window_seconds,
+                    )?;
+                }
+                _ => Err(Self::unknown_key_error(&key_path))?,
+            }
+        }
+        return Ok(());
+    }
+
+    /// Either "ignore", "count" or an array of actions.
+    fn get_tamper_response(path: &str, value: &Value) -> Result<TamperResponse, Box<dyn Error>> {
+        if value.is_array() {
+            return Ok(TamperResponse::Actions(Self::get_actions(path, value)?));
+        }
+        let name = match value.as_str() {
+            Some(name) => name,
+            None => Err(Self::invalid_type_error(path, "a string or an array"))?,
+        };
+        return match TamperResponse::parse(name) {
+            Some(response) => Ok(response),
+            None => Err(format!("{} is not a valid value for {}", name, path))?,
+        };
+    }
+
+    fn get_actions(path: &str, value: &Value) -> Result<Vec<Action>, Box<dyn Error>> {
+        let mut actions = Vec::new();
+        for (index, name) in Self::get_strings(path, value)?.iter().enumerate() {
+            match name.parse::<Action>() {
+                Ok(action) => actions.push(action),
+                Err(error) => Err(format!("{}[{}]: {}", path, index, error))?,
+            }
+        }
+        if actions.is_empty() {
+            Err(format!("{} must contain at least one action", path))?;
+        }
+        return Ok(actions);
+    }
+
+    fn get_table<'a>(path: &str, value: &'a Value) -> Result<&'a Table, Box<dyn Error>> {
+        return match value.as_table() {
+            Some(table) => Ok(table),
+            None => Err(Self::invalid_type_error(path, "a table"))?,
+        };
+    }
+
+    fn get_array<'a>(path: &str, value: &'a Value) -> Result<&'a Vec<Value>, Box<dyn Error>> {
+        return match value.as_array() {
+            Some(array) => Ok(array),
+            None => Err(Self::invalid_type_error(path, "an array"))?,
+        };
+    }
+
+    fn get_strings(path: &str, value: &Value) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut strings = Vec::new();
+        for (index, item) in Self::get_array(path, value)?.iter().enumerate() {
+            let item_path = format!("{}[{}]", path, index);
+            strings.push(Self::get_non_empty_string(&item_path, item)?);
+        }
+        return Ok(strings);
+    }
+
+    fn get_non_empty_string(path: &str, value: &Value) -> Result<String, Box<dyn Error>> {
+        return match value.as_str() {
+            Some("") => Err(format!("{} must not be empty", path))?,
+            Some(string) => Ok(String::from(string)),
+            None => Err(Self::invalid_type_error(path, "a string"))?,
+        };
+    }
+
+    fn get_parsed<T: FromStr>(path: &str, value: &Value) -> Result<T, Box<dyn Error>> {
+        let string = Self::get_non_empty_string(path, value)?;
+        return match string.parse::<T>() {
+            Ok(parsed) => Ok(parsed),
+            Err(_) => Err(format!("{} is not a valid value for {}", string, path))?,
+        };
+    }
+
+    fn get_integer(path: &str, value: &Value) -> Result<i32, Box<dyn Error>> {
+        let integer = match value.as_integer() {
+            Some(integer) => integer,
+            None => Err(Self::invalid_type_error(path, "an integer"))?,
+        };
+        return match i32::try_from(integer) {
+            Ok(integer) => Ok(integer),
+            Err(_) => Err(format!("{} is not a valid value for {}", integer, path))?,
+        };
+    }
+
+    fn get_bool(path: &str, value: &Value) -> Result<bool, Box<dyn Error>> {
+        return match value.as_bool() {
+            Some(boolean) => Ok(boolean),
+            None => Err(Self::invalid_type_error(path, "a boolean"))?,
+        };
+    }
+
+    fn join(path: &str, key: &str) -> String {
+        return format!("{}{}{}", path, KEY_SEPARATOR, key);
+    }
+
+    fn unknown_key_error(path: &str) -> String {
+        return format!("Unknown key {}", path);
+    }
+
+    fn missing_key_error(path: &str, key: &str) -> String {
+        return format!("Missing key {}", Self::join(path, key));
+    }
+
+    fn invalid_type_error(path: &str, expected_type: &str) -> String {
+        return format!("{} must be {}", path, expected_type);
+    }
+}
+
+#[cfg(test)]
+#[path = "./auth_monitor_config_tests.rs"]
+mod tests;
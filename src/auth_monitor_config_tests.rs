@@ -0,0 +1,349 @@
+use crate::action::Action;
+use crate::assert_error;
+use crate::auth_message_parser::AuthFailedMessagePattern;
+use crate::auth_monitor_config::AuthMonitorConfig;
+use crate::auth_monitor_params::AuthMonitorParams;
+use crate::command_options::CommandOptions;
+use crate::desktop_options::DesktopOptions;
+use crate::email_options::EmailOptions;
+use crate::logger::{LogFormat, LogLevel};
+use crate::luks_suspend_options::LuksSuspendOptions;
+use crate::power_options::{InhibitorPolicy, PowerBackend, PowerOptions};
+use crate::smtp_client::SmtpSecurity;
+use crate::tamper_options::{TamperOptions, TamperResponse};
+use crate::webhook_options::WebhookOptions;
+
+fn apply(content: &str) -> Result<AuthMonitorParams, Box<dyn std::error::Error>> {
+    let mut params = AuthMonitorParams::default();
+    AuthMonitorConfig::apply(content, &mut params)?;
+    return Ok(params);
+}
+
+#[test]
+fn when_config_contains_all_sections_then_params_have_configured_values() {
+    let content = r#"
+log-file = "/var/log/secure"
+control-socket = "/run/auth-monitor.sock"
+log-level = "debug"
+log-format = "json"
+
+[thresholds]
+max-failed-attempts = 3
+warning-failed-attempts = 2
+reset-after-seconds = 600
+clock-skew-tolerance-seconds = 5
+
+[[patterns]]
+prefix = "sshd"
+message = "Failed password"
+
+[filters]
+ignore-users = ["backup"]
+ignore-services = ["cron"]
+
+[actions]
+execute = ["poweroff"]
+warn = ["desktop"]
+dry-run = true
+grace-period-seconds = 30
+cancel-users = ["john", "alice"]
+
+[metrics]
+textfile = "/var/lib/node_exporter/auth_monitor.prom"
+address = "127.0.0.1:9157"
+
+[webhook]
+url = "https://hooks.example.com/auth-monitor"
+timeout-seconds = 10
+retries = 0
+queue-size = 4
+
+[email]
+server = "mail.example.com:587"
+security = "starttls"
+username = "alerts"
+password = "secret"
+from = "auth-monitor@example.com"
+to = ["admin@example.com", "security@example.com"]
+timeout-seconds = 20
+retries = 1
+queue-size = 8
+
+[desktop]
+system-bus = "unix:path=/run/dbus/test_bus_socket"
+session-bus = "unix:path=/run/user/{uid}/test_bus"
+timeout-seconds = 2
+
+[power]
+backend = "logind"
+inhibitors = "fallback"
+fallback-backend = "kernel"
+system-bus = "unix:path=/run/dbus/test_bus_socket"
+timeout-seconds = 3
+sudo-user = "monitor"
+
+[luks-suspend]
+devices = ["luks-root", "luks-home"]
+hook = ["/usr/local/sbin/wipe-keys", "--all"]
+cryptsetup = "/sbin/cryptsetup"
+timeout-seconds = 4
+
+[command]
+exec = ["/usr/local/bin/snapshot-logs", "--compress"]
+working-directory = "/var/lib/auth-monitor"
+timeout-seconds = 60
+
+[tamper]
+deleted = "count"
+truncated = ["wall", "webhook"]
+absent = "ignore"
+absent-seconds = 120
+silent = ["poweroff"]
+silent-seconds = 3600
+terminated = "count"
+max-terminations = 2
+terminations-window-seconds = 600
+"#;
+    let params = apply(content).unwrap();
+    assert_eq!(params.filepath, "/var/log/secure");
+    assert_eq!(params.control_socket, "/run/auth-monitor.sock");
+    assert_eq!(params.log_level, LogLevel::Debug);
+    assert_eq!(params.log_format, LogFormat::Json);
+    assert_eq!(params.options.max_failed_attempts, 3);
+    assert_eq!(params.options.warning_failed_attempts, 2);
+    assert_eq!(params.options.reset_after_seconds, 600);
+    assert_eq!(params.options.clock_skew_tolerance_seconds, 5);
+    assert_eq!(params.options.grace_period_seconds, 30);
+    assert_eq!(
+        params.patterns,
+        [AuthFailedMessagePattern {
+            prefix: String::from("sshd"),
+            message: String::from("Failed password"),
+        }]
+    );
+    assert_eq!(params.ignore_users, ["backup"]);
+    assert_eq!(params.ignore_services, ["cron"]);
+    assert_eq!(params.actions, [Action::PowerOff]);
+    assert_eq!(params.warning_actions, [Action::Desktop]);
+    assert!(params.dry_run);
+    assert_eq!(params.cancel_users, ["john", "alice"]);
+    assert_eq!(
+        params.metrics_textfile.as_deref(),
+        Some("/var/lib/node_exporter/auth_monitor.prom")
+    );
+    assert_eq!(params.metrics_address, "127.0.0.1:9157".parse().ok());
+    assert_eq!(
+        params.webhook,
+        WebhookOptions {
+            url: Some(String::from("https://hooks.example.com/auth-monitor")),
+            timeout_seconds: 10,
+            retries: 0,
+            queue_size: 4,
+        }
+    );
+    assert_eq!(
+        params.email,
+        EmailOptions {
+            server: Some(String::from("mail.example.com:587")),
+            security: SmtpSecurity::StartTls,
+            username: Some(String::from("alerts")),
+            password: Some(String::from("secret")),
+            from: Some(String::from("auth-monitor@example.com")),
+            to: vec![
+                String::from("admin@example.com"),
+                String::from("security@example.com")
+            ],
+            timeout_seconds: 20,
+            retries: 1,
+            queue_size: 8,
+        }
+    );
+    assert_eq!(
+        params.desktop,
+        DesktopOptions {
+            system_bus: String::from("unix:path=/run/dbus/test_bus_socket"),
+            session_bus: String::from("unix:path=/run/user/{uid}/test_bus"),
+            timeout_seconds: 2,
+        }
+    );
+    assert_eq!(
+        params.power,
+        PowerOptions {
+            backend: PowerBackend::Logind,
+            inhibitors: InhibitorPolicy::Fallback,
+            fallback_backend: PowerBackend::Kernel,
+            system_bus: String::from("unix:path=/run/dbus/test_bus_socket"),
+            timeout_seconds: 3,
+            sudo_user: String::from("monitor"),
+        }
+    );
+    assert_eq!(
+        params.luks_suspend,
+        LuksSuspendOptions {
+            devices: vec![String::from("luks-root"), String::from("luks-home")],
+            hook: vec![
+                String::from("/usr/local/sbin/wipe-keys"),
+                String::from("--all")
+            ],
+            cryptsetup: String::from("/sbin/cryptsetup"),
+            timeout_seconds: 4,
+        }
+    );
+    assert_eq!(
+        params.command,
+        CommandOptions {
+            exec: vec![
+                String::from("/usr/local/bin/snapshot-logs"),
+                String::from("--compress")
+            ],
+            working_directory: String::from("/var/lib/auth-monitor"),
+            timeout_seconds: 60,
+        }
+    );
+    assert_eq!(
+        params.tamper,
+        TamperOptions {
+            deleted: TamperResponse::Count,
+            truncated: TamperResponse::Actions(vec![Action::Wall, Action::Webhook]),
+            absent: TamperResponse::Ignore,
+            absent_seconds: 120,
+            silent: TamperResponse::Actions(vec![Action::PowerOff]),
+            silent_seconds: 3600,
+            terminated: TamperResponse::Count,
+            max_terminations: 2,
+            terminations_window_seconds: 600,
+        }
+    );
+}
+
+#[test]
+fn when_config_is_empty_then_params_have_default_values() {
+    let params = apply("").unwrap();
+    let default_params = AuthMonitorParams::default();
+    assert_eq!(params.filepath, default_params.filepath);
+    assert_eq!(
+        params.options.max_failed_attempts,
+        default_params.options.max_failed_attempts
+    );
+    assert_eq!(params.actions, default_params.actions);
+}
+
+#[test]
+fn when_config_contains_unknown_key_then_return_error_with_key_path() {
+    let configs = [
+        ("unknown = 1", "Unknown key unknown"),
+        ("[thresholds]\nmax-attempts = 1", "Unknown key thresholds.max-attempts"),
+        (
+            "[[patterns]]\nprefix = \"a\"\nmessage = \"b\"\n[[patterns]]\nprefix = \"a\"\nmesage = \"b\"",
+            "Unknown key patterns[1].mesage",
+        ),
+        ("[filters]\nusers = []", "Unknown key filters.users"),
+        ("[actions]\nrun = []", "Unknown key actions.run"),
+    ];
+    for (content, expected) in configs {
+        assert_error!(apply(content), expected);
+    }
+}
+
+#[test]
+fn when_config_contains_invalid_value_then_return_error_with_key_path() {
+    let configs = [
+        ("log-file = 1", "log-file must be a string"),
+        ("log-file = \"\"", "log-file must not be empty"),
+        ("thresholds = 1", "thresholds must be a table"),
+        (
+            "[thresholds]\nmax-failed-attempts = \"5\"",
+            "thresholds.max-failed-attempts must be an integer",
+        ),
+        (
+            "[thresholds]\nmax-failed-attempts = 0",
+            "thresholds.max-failed-attempts must be greater than 0",
+        ),
+        (
+            "[thresholds]\nreset-after-seconds = 99999999999",
+            "99999999999 is not a valid value for thresholds.reset-after-seconds",
+        ),
+        (
+            "[thresholds]\nclock-skew-tolerance-seconds = -1",
+            "thresholds.clock-skew-tolerance-seconds must be greater than or equal to 0",
+        ),
+        (
+            "[[patterns]]\nprefix = \"sshd\"",
+            "Missing key patterns[0].message",
+        ),
+        (
+            "[filters]\nignore-users = [\"backup\", 1]",
+            "filters.ignore-users[1] must be a string",
+        ),
+        (
+            "[actions]\nexecute = [\"explode\"]",
+            "actions.execute[0]: Unknown action explode",
+        ),
+        (
+            "[actions]\nexecute = []",
+            "actions.execute must contain at least one action",
+        ),
+        (
+            "[actions]\ndry-run = 1",
+            "actions.dry-run must be a boolean",
+        ),
+        (
+            "[webhook]\nqueue-size = 0",
+            "webhook.queue-size must be greater than 0",
+        ),
+        (
+            "[thresholds]\nwarning-failed-attempts = -1",
+            "thresholds.warning-failed-attempts must be greater than or equal to 0",
+        ),
+        (
+            "[email]\nsecurity = \"tls\"",
+            "tls is not a valid value for email.security",
+        ),
+        (
+            "[email]\nto = \"admin@example.com\"",
+            "email.to must be an array",
+        ),
+        (
+            "[power]\nbackend = \"systemctl\"",
+            "systemctl is not a valid value for power.backend",
+        ),
+        (
+            "[power]\ninhibitors = \"force\"",
+            "force is not a valid value for power.inhibitors",
+        ),
+        (
+            "[command]\nexec = \"/usr/local/bin/snapshot-logs\"",
+            "command.exec must be an array",
+        ),
+        (
+            "[tamper]\ndeleted = \"panic\"",
+            "panic is not a valid value for tamper.deleted",
+        ),
+        (
+            "[tamper]\nsilent = 1",
+            "tamper.silent must be a string or an array",
+        ),
+        (
+            "[tamper]\ntruncated = [\"explode\"]",
+            "tamper.truncated[0]: Unknown action explode",
+        ),
+        (
+            "[tamper]\nabsent-seconds = 0",
+            "tamper.absent-seconds must be greater than 0",
+        ),
+    ];
+    for (content, expected) in configs {
+        assert_error!(apply(content), expected);
+    }
+}
+
+#[test]
+fn when_shipped_config_is_applied_then_params_are_valid() {
+    let content = include_str!("../etc/auth-monitor.toml");
+
+    let params = apply(content).unwrap();
+
+    assert_eq!(params.filepath, "/var/log/auth.log");
+    assert_eq!(params.power.sudo_user, "auth-monitor");
+    assert!(params.validate().is_ok());
+}
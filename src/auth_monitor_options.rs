@@ -3,14 +3,20 @@ use std::fmt::{Display, Formatter};
 #[derive(Copy, Clone)]
 pub struct AuthMonitorOptions {
     pub max_failed_attempts: i32,
+    pub warning_failed_attempts: i32,
     pub reset_after_seconds: i32,
+    pub clock_skew_tolerance_seconds: i32,
+    pub grace_period_seconds: i32,
 }
 
 impl Default for AuthMonitorOptions {
     fn default() -> Self {
         return AuthMonitorOptions {
             max_failed_attempts: 5,
+            warning_failed_attempts: 0,
             reset_after_seconds: 1800,
+            clock_skew_tolerance_seconds: 60,
+            grace_period_seconds: 0,
         };
     }
 }
@@ -19,8 +25,12 @@ impl Display for AuthMonitorOptions {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
         return write!(
             formatter,
-            "max-failed-attempts={}, reset-after-seconds={}",
-            self.max_failed_attempts, self.reset_after_seconds
+            "max-failed-attempts={}, warning-failed-attempts={}, reset-after-seconds={}, clock-skew-tolerance-seconds={}, grace-period-seconds={}",
+            self.max_failed_attempts,
+            self.warning_failed_attempts,
+            self.reset_after_seconds,
+            self.clock_skew_tolerance_seconds,
+            self.grace_period_seconds
         );
     }
 }
@@ -1,8 +1,23 @@
+use std::env;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
 use std::str::FromStr;
 
+use crate::action::Action;
+use crate::auth_message_parser::AuthFailedMessagePattern;
+use crate::auth_monitor_config::AuthMonitorConfig;
 use crate::auth_monitor_options::AuthMonitorOptions;
+use crate::command_options::CommandOptions;
+use crate::desktop_options::DesktopOptions;
+use crate::email_options::EmailOptions;
+use crate::http_client::HttpUrl;
+use crate::logger::{LogEntry, LogFormat, LogLevel};
+use crate::luks_suspend_options::LuksSuspendOptions;
+use crate::power_options::{InhibitorPolicy, PowerOptions};
+use crate::smtp_client::{SmtpClient, SmtpSecurity};
+use crate::tamper_options::TamperOptions;
+use crate::webhook_options::WebhookOptions;
 
 const OPTION_PREFIX: &str = "--";
 const OPTION_PREFIX_LENGTH: usize = OPTION_PREFIX.len();
@@ -10,50 +25,299 @@ const OPTION_PREFIX_LENGTH: usize = OPTION_PREFIX.len();
 const OPTION_VALUE_SEPARATOR: char = '=';
 const OPTION_VALUE_SEPARATOR_LENGTH: usize = 1;
 
-const MAX_FAILED_ATTEMPTS_OPTION: &str = "max-failed-attempts";
-const RESET_AFTER_SECONDS_OPTION: &str = "reset-after-seconds";
+pub const MAX_FAILED_ATTEMPTS_OPTION: &str = "max-failed-attempts";
+pub const WARNING_FAILED_ATTEMPTS_OPTION: &str = "warning-failed-attempts";
+pub const RESET_AFTER_SECONDS_OPTION: &str = "reset-after-seconds";
+pub const CLOCK_SKEW_TOLERANCE_SECONDS_OPTION: &str = "clock-skew-tolerance-seconds";
+pub const CONFIG_OPTION: &str = "config";
+pub const GRACE_PERIOD_SECONDS_OPTION: &str = "grace-period-seconds";
+pub const DRY_RUN_OPTION: &str = "dry-run";
+pub const IGNORE_INHIBITORS_OPTION: &str = "ignore-inhibitors";
+pub const CANCEL_USERS_OPTION: &str = "cancel-users";
+pub const CONTROL_SOCKET_OPTION: &str = "control-socket";
+pub const STATE_DIRECTORY_OPTION: &str = "state-directory";
+pub const LOG_LEVEL_OPTION: &str = "log-level";
+pub const LOG_FORMAT_OPTION: &str = "log-format";
+pub const METRICS_TEXTFILE_OPTION: &str = "metrics-textfile";
+pub const METRICS_ADDRESS_OPTION: &str = "metrics-address";
+pub const WEBHOOK_URL_OPTION: &str = "webhook-url";
+pub const WEBHOOK_TIMEOUT_SECONDS_OPTION: &str = "webhook-timeout-seconds";
+pub const WEBHOOK_RETRIES_OPTION: &str = "webhook-retries";
+pub const WEBHOOK_QUEUE_SIZE_OPTION: &str = "webhook-queue-size";
 
+pub const DEFAULT_CONTROL_SOCKET: &str = "/run/auth-monitor/control.sock";
+pub const DEFAULT_STATE_DIRECTORY: &str = "/var/lib/auth-monitor";
+
+const LIST_VALUE_SEPARATOR: char = ',';
+
+const ENVIRONMENT_VARIABLE_PREFIX: &str = "AUTH_MONITOR_";
+const LOG_FILE_VARIABLE_OPTION: &str = "log-file";
+/// Variables read by earlier versions through the unit's command line.
+const LEGACY_VARIABLES: [&str; 3] = ["LOG_FILE", "MAX_FAILED_ATTEMPTS", "RESET_AFTER_SECONDS"];
+
+#[derive(Clone)]
 pub struct AuthMonitorParams {
     pub filepath: String,
     pub options: AuthMonitorOptions,
+    pub actions: Vec<Action>,
+    pub warning_actions: Vec<Action>,
+    pub dry_run: bool,
+    pub cancel_users: Vec<String>,
+    pub patterns: Vec<AuthFailedMessagePattern>,
+    pub ignore_users: Vec<String>,
+    pub ignore_services: Vec<String>,
+    pub control_socket: String,
+    pub state_directory: String,
+    pub log_level: LogLevel,
+    pub log_format: LogFormat,
+    pub metrics_textfile: Option<String>,
+    pub metrics_address: Option<SocketAddr>,
+    pub webhook: WebhookOptions,
+    pub email: EmailOptions,
+    pub desktop: DesktopOptions,
+    pub power: PowerOptions,
+    pub luks_suspend: LuksSuspendOptions,
+    pub command: CommandOptions,
+    pub tamper: TamperOptions,
 }
 
 impl AuthMonitorParams {
     pub fn from_arguments(arguments: &[String]) -> Result<AuthMonitorParams, Box<dyn Error>> {
+        let environment: Vec<(String, String)> = env::vars().collect();
+        return Self::from_arguments_and_environment(arguments, &environment);
+    }
+
+    pub fn from_arguments_and_environment(
+        arguments: &[String],
+        environment: &[(String, String)],
+    ) -> Result<AuthMonitorParams, Box<dyn Error>> {
+        let environment_arguments = Self::environment_arguments(environment)?;
         let mut params = AuthMonitorParams::default();
-        let arguments_iterator = arguments.iter();
+        let config_path = match Self::find_config_path(arguments)? {
+            Some(config_path) => Some(config_path),
+            None => {
+                let arguments: Vec<String> = environment_arguments
+                    .iter()
+                    .map(|(_, argument)| {
+                        return argument.clone();
+                    })
+                    .collect();
+                Self::find_config_path(&arguments)?
+            }
+        };
+        if let Some(config_path) = config_path {
+            AuthMonitorConfig::load(&config_path, &mut params)?;
+        }
 
-        for argument in arguments_iterator {
-            if !argument.starts_with(OPTION_PREFIX) {
-                if !params.filepath.is_empty() {
-                    Err("File path specified more than once")?;
-                }
-                params.filepath = String::from(argument);
+        for (variable, argument) in environment_arguments {
+            if let Err(error) = params.apply_arguments(&[argument]) {
+                Err(format!(
+                    "Invalid environment variable {}: {}",
+                    variable, error
+                ))?;
+            }
+        }
+        params.apply_arguments(arguments)?;
+        params.validate()?;
+
+        return Ok(params);
+    }
+
+    fn environment_arguments(
+        environment: &[(String, String)],
+    ) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+        let mut arguments = Vec::new();
+        for (variable, value) in Self::with_legacy_variables(environment) {
+            let name = match variable.strip_prefix(ENVIRONMENT_VARIABLE_PREFIX) {
+                Some(name) => name,
+                None => continue,
+            };
+            if value.is_empty() {
                 continue;
             }
-            let (option_name, option_value) = match argument.find(OPTION_VALUE_SEPARATOR) {
-                Some(separator_position) => {
-                    let (name, value) = argument.split_at(separator_position);
-                    (name, Some(&value[OPTION_VALUE_SEPARATOR_LENGTH..]))
+            let option = name.to_lowercase().replace('_', "-");
+            let argument = match option.as_str() {
+                LOG_FILE_VARIABLE_OPTION => value.clone(),
+                DRY_RUN_OPTION | IGNORE_INHIBITORS_OPTION => match value.as_str() {
+                    "true" => format!("{}{}", OPTION_PREFIX, option),
+                    "false" => continue,
+                    _ => Err(format!(
+                        "\"{}\" is not a valid value for {}",
+                        value, variable
+                    ))?,
+                },
+                CONFIG_OPTION
+                | MAX_FAILED_ATTEMPTS_OPTION
+                | WARNING_FAILED_ATTEMPTS_OPTION
+                | RESET_AFTER_SECONDS_OPTION
+                | CLOCK_SKEW_TOLERANCE_SECONDS_OPTION
+                | GRACE_PERIOD_SECONDS_OPTION
+                | CANCEL_USERS_OPTION
+                | CONTROL_SOCKET_OPTION
+                | STATE_DIRECTORY_OPTION
+                | LOG_LEVEL_OPTION
+                | LOG_FORMAT_OPTION
+                | METRICS_TEXTFILE_OPTION
+                | METRICS_ADDRESS_OPTION
+                | WEBHOOK_URL_OPTION
+                | WEBHOOK_TIMEOUT_SECONDS_OPTION
+                | WEBHOOK_RETRIES_OPTION
+                | WEBHOOK_QUEUE_SIZE_OPTION => {
+                    format!(
+                        "{}{}{}{}",
+                        OPTION_PREFIX, option, OPTION_VALUE_SEPARATOR, value
+                    )
                 }
-                None => (argument.as_str(), None),
+                _ => Err(format!("Unknown environment variable {}", variable))?,
             };
+            arguments.push((variable.clone(), argument));
+        }
+        return Ok(arguments);
+    }
+
+    /// Renames legacy variables, placed first so that the current names take
+    /// precedence when both are set.
+    fn with_legacy_variables(environment: &[(String, String)]) -> Vec<(String, String)> {
+        let mut renamed = Vec::new();
+        for (variable, value) in environment {
+            if value.is_empty() || !LEGACY_VARIABLES.contains(&variable.as_str()) {
+                continue;
+            }
+            let replacement = format!("{}{}", ENVIRONMENT_VARIABLE_PREFIX, variable);
+            let message = format!(
+                "Environment variable {} is deprecated, use {} instead",
+                variable, replacement
+            );
+            LogEntry::warning("deprecated_variable", &message).log();
+            renamed.push((replacement, value.clone()));
+        }
+        renamed.extend(environment.iter().cloned());
+        return renamed;
+    }
+
+    fn apply_arguments(&mut self, arguments: &[String]) -> Result<(), Box<dyn Error>> {
+        let mut filepath_specified = false;
+
+        for argument in arguments {
+            if !Self::is_option(argument) {
+                if filepath_specified {
+                    Err("File path specified more than once")?;
+                }
+                self.filepath = String::from(argument);
+                filepath_specified = true;
+                continue;
+            }
+            let (option_name, option_value) = Self::split_option(argument);
             match &option_name[OPTION_PREFIX_LENGTH..] {
-                MAX_FAILED_ATTEMPTS_OPTION => {
-                    params.options.max_failed_attempts =
+                CONFIG_OPTION => {}
+                WARNING_FAILED_ATTEMPTS_OPTION => {
+                    self.options.warning_failed_attempts =
                         Self::parse_option_value(option_name, option_value)?;
                 }
-                RESET_AFTER_SECONDS_OPTION => {
-                    params.options.reset_after_seconds =
+                DRY_RUN_OPTION => {
+                    Self::expect_no_option_value(option_name, option_value)?;
+                    self.dry_run = true;
+                }
+                IGNORE_INHIBITORS_OPTION => {
+                    Self::expect_no_option_value(option_name, option_value)?;
+                    self.power.inhibitors = InhibitorPolicy::Ignore;
+                }
+                CANCEL_USERS_OPTION => {
+                    self.cancel_users = Self::parse_list_option_value(option_name, option_value)?;
+                }
+                CONTROL_SOCKET_OPTION => {
+                    self.control_socket = Self::parse_option_value(option_name, option_value)?;
+                }
+                STATE_DIRECTORY_OPTION => {
+                    self.state_directory = Self::parse_option_value(option_name, option_value)?;
+                }
+                LOG_LEVEL_OPTION => {
+                    self.log_level = Self::parse_option_value(option_name, option_value)?;
+                }
+                LOG_FORMAT_OPTION => {
+                    self.log_format = Self::parse_option_value(option_name, option_value)?;
+                }
+                METRICS_TEXTFILE_OPTION => {
+                    self.metrics_textfile =
+                        Some(Self::parse_option_value(option_name, option_value)?);
+                }
+                METRICS_ADDRESS_OPTION => {
+                    self.metrics_address =
+                        Some(Self::parse_option_value(option_name, option_value)?);
+                }
+                WEBHOOK_URL_OPTION => {
+                    self.webhook.url = Some(Self::parse_option_value(option_name, option_value)?);
+                }
+                WEBHOOK_TIMEOUT_SECONDS_OPTION => {
+                    self.webhook.timeout_seconds =
                         Self::parse_option_value(option_name, option_value)?;
                 }
-                _ => Err(format!("Unknown option {}", argument))?,
+                WEBHOOK_RETRIES_OPTION => {
+                    self.webhook.retries = Self::parse_option_value(option_name, option_value)?;
+                }
+                WEBHOOK_QUEUE_SIZE_OPTION => {
+                    self.webhook.queue_size = Self::parse_option_value(option_name, option_value)?;
+                }
+                _ => Self::parse_option(&mut self.options, argument)?,
             }
         }
+        return Ok(());
+    }
 
-        params.validate()?;
+    pub fn find_config_path(arguments: &[String]) -> Result<Option<String>, Box<dyn Error>> {
+        let mut config_path = None;
+        for argument in arguments {
+            if !Self::is_option(argument) {
+                continue;
+            }
+            let (option_name, option_value) = Self::split_option(argument);
+            if option_name[OPTION_PREFIX_LENGTH..] != *CONFIG_OPTION {
+                continue;
+            }
+            if config_path.is_some() {
+                Err(format!("Option {} specified more than once", option_name))?;
+            }
+            config_path = Some(Self::parse_option_value(option_name, option_value)?);
+        }
+        return Ok(config_path);
+    }
 
-        return Ok(params);
+    pub fn is_option(argument: &str) -> bool {
+        return argument.starts_with(OPTION_PREFIX);
+    }
+
+    pub fn split_option(argument: &str) -> (&str, Option<&str>) {
+        return match argument.find(OPTION_VALUE_SEPARATOR) {
+            Some(separator_position) => {
+                let (name, value) = argument.split_at(separator_position);
+                (name, Some(&value[OPTION_VALUE_SEPARATOR_LENGTH..]))
+            }
+            None => (argument, None),
+        };
+    }
+
+    pub fn parse_option(
+        options: &mut AuthMonitorOptions,
+        argument: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let (option_name, option_value) = Self::split_option(argument);
+        match &option_name[OPTION_PREFIX_LENGTH..] {
+            MAX_FAILED_ATTEMPTS_OPTION => {
+                options.max_failed_attempts = Self::parse_option_value(option_name, option_value)?;
+            }
+            RESET_AFTER_SECONDS_OPTION => {
+                options.reset_after_seconds = Self::parse_option_value(option_name, option_value)?;
+            }
+            CLOCK_SKEW_TOLERANCE_SECONDS_OPTION => {
+                options.clock_skew_tolerance_seconds =
+                    Self::parse_option_value(option_name, option_value)?;
+            }
+            GRACE_PERIOD_SECONDS_OPTION => {
+                options.grace_period_seconds = Self::parse_option_value(option_name, option_value)?;
+            }
+            _ => Err(format!("Unknown option {}", argument))?,
+        }
+        return Ok(());
     }
 
     fn parse_option_value<T: FromStr>(
@@ -76,22 +340,169 @@ impl AuthMonitorParams {
         };
     }
 
+    fn expect_no_option_value(
+        name: &str,
+        optional_value: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        if optional_value.is_some() {
+            Err(format!("Option {} does not take a value", name))?;
+        }
+        return Ok(());
+    }
+
+    fn parse_list_option_value(
+        name: &str,
+        optional_value: Option<&str>,
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        let value: String = Self::parse_option_value(name, optional_value)?;
+        let mut items = Vec::new();
+        for item in value.split(LIST_VALUE_SEPARATOR) {
+            if item.is_empty() {
+                Err(format!(
+                    "\"{}\" is not a valid value for option {}",
+                    value, name
+                ))?;
+            }
+            items.push(String::from(item));
+        }
+        return Ok(items);
+    }
+
     pub fn validate(&self) -> Result<(), Box<dyn Error>> {
         if self.filepath.is_empty() {
             Err("File path not specified")?;
         }
-        if self.options.max_failed_attempts <= 0 {
-            return Err(format!(
-                "{} must be greater than 0",
-                MAX_FAILED_ATTEMPTS_OPTION
+        if let Some(address) = self.metrics_address {
+            if !address.ip().is_loopback() {
+                Err(format!(
+                    "{} {} is not a loopback address",
+                    METRICS_ADDRESS_OPTION, address
+                ))?;
+            }
+        }
+        if !self.warning_actions.is_empty() && self.options.warning_failed_attempts == 0 {
+            Err(format!(
+                "warning actions require {}",
+                WARNING_FAILED_ATTEMPTS_OPTION
             ))?;
         }
-        if self.options.reset_after_seconds <= 0 {
-            return Err(format!(
-                "{} must be greater than 0",
-                RESET_AFTER_SECONDS_OPTION
+        Self::validate_webhook_options(&self.webhook)?;
+        if self.uses_action(Action::Webhook) && self.webhook.url.is_none() {
+            Err(format!(
+                "{} action requires {}",
+                Action::Webhook,
+                WEBHOOK_URL_OPTION
             ))?;
         }
+        if self.uses_action(Action::Email) {
+            if self.email.server.is_none() {
+                Err(format!("{} action requires email.server", Action::Email))?;
+            }
+            if self.email.from.is_none() || self.email.to.is_empty() {
+                Err(format!(
+                    "{} action requires email.from and email.to",
+                    Action::Email
+                ))?;
+            }
+        }
+        Self::validate_email_options(&self.email)?;
+        Self::validate_greater_than_zero("desktop.timeout-seconds", self.desktop.timeout_seconds)?;
+        Self::validate_greater_than_zero("power.timeout-seconds", self.power.timeout_seconds)?;
+        if self.power.inhibitors == InhibitorPolicy::Fallback
+            && self.power.fallback_backend == self.power.backend
+        {
+            Err("power.fallback-backend must differ from power.backend")?;
+        }
+        Self::validate_greater_than_zero(
+            "luks-suspend.timeout-seconds",
+            self.luks_suspend.timeout_seconds,
+        )?;
+        Self::validate_greater_than_zero("command.timeout-seconds", self.command.timeout_seconds)?;
+        Self::validate_greater_than_zero("tamper.absent-seconds", self.tamper.absent_seconds)?;
+        Self::validate_greater_than_zero("tamper.silent-seconds", self.tamper.silent_seconds)?;
+        Self::validate_greater_than_zero("tamper.max-terminations", self.tamper.max_terminations)?;
+        Self::validate_greater_than_zero(
+            "tamper.terminations-window-seconds",
+            self.tamper.terminations_window_seconds,
+        )?;
+        if self.uses_action(Action::Command) && self.command.exec.is_empty() {
+            Err(format!("{} action requires command.exec", Action::Command))?;
+        }
+        if self.uses_action(Action::LuksSuspend)
+            && self.luks_suspend.devices.is_empty()
+            && self.luks_suspend.hook.is_empty()
+        {
+            Err(format!(
+                "{} action requires luks-suspend.devices or luks-suspend.hook",
+                Action::LuksSuspend
+            ))?;
+        }
+        return Self::validate_options(&self.options);
+    }
+
+    pub fn uses_action(&self, action: Action) -> bool {
+        return self.actions.contains(&action)
+            || self.warning_actions.contains(&action)
+            || self.tamper.responses().iter().any(|response| {
+                return response.actions().contains(&action);
+            });
+    }
+
+    pub fn validate_email_options(options: &EmailOptions) -> Result<(), Box<dyn Error>> {
+        if let Some(server) = &options.server {
+            SmtpClient::parse_address(server)?;
+        }
+        if options.username.is_some() != options.password.is_some() {
+            Err("email.username and email.password must be specified together")?;
+        }
+        if options.username.is_some() && options.security == SmtpSecurity::None {
+            Err("email.username and email.password require email.security = \"starttls\"")?;
+        }
+        return Ok(());
+    }
+
+    pub fn validate_webhook_options(options: &WebhookOptions) -> Result<(), Box<dyn Error>> {
+        if let Some(url) = &options.url {
+            url.parse::<HttpUrl>()?;
+        }
+        Self::validate_greater_than_zero(WEBHOOK_TIMEOUT_SECONDS_OPTION, options.timeout_seconds)?;
+        Self::validate_not_negative(WEBHOOK_RETRIES_OPTION, options.retries)?;
+        Self::validate_greater_than_zero(WEBHOOK_QUEUE_SIZE_OPTION, options.queue_size)?;
+        return Ok(());
+    }
+
+    pub fn validate_options(options: &AuthMonitorOptions) -> Result<(), Box<dyn Error>> {
+        Self::validate_greater_than_zero(MAX_FAILED_ATTEMPTS_OPTION, options.max_failed_attempts)?;
+        Self::validate_not_negative(
+            WARNING_FAILED_ATTEMPTS_OPTION,
+            options.warning_failed_attempts,
+        )?;
+        if options.warning_failed_attempts >= options.max_failed_attempts {
+            Err(format!(
+                "{} must be less than {}",
+                WARNING_FAILED_ATTEMPTS_OPTION, MAX_FAILED_ATTEMPTS_OPTION
+            ))?;
+        }
+        Self::validate_greater_than_zero(RESET_AFTER_SECONDS_OPTION, options.reset_after_seconds)?;
+        Self::validate_not_negative(
+            CLOCK_SKEW_TOLERANCE_SECONDS_OPTION,
+            options.clock_skew_tolerance_seconds,
+        )?;
+        Self::validate_not_negative(GRACE_PERIOD_SECONDS_OPTION, options.grace_period_seconds)?;
+        return Ok(());
+    }
+
+    pub fn validate_greater_than_zero(name: &str, value: i32) -> Result<(), Box<dyn Error>> {
+        if value <= 0 {
+            Err(format!("{} must be greater than 0", name))?;
+        }
+        return Ok(());
+    }
+
+    pub fn validate_not_negative(name: &str, value: i32) -> Result<(), Box<dyn Error>> {
+        if value < 0 {
+            Err(format!("{} must be greater than or equal to 0", name))?;
+        }
         return Ok(());
     }
 }
@@ -101,6 +512,26 @@ impl Default for AuthMonitorParams {
         return AuthMonitorParams {
             filepath: String::new(),
             options: AuthMonitorOptions::default(),
+            actions: vec![Action::PowerOff],
+            warning_actions: Vec::new(),
+            dry_run: false,
+            cancel_users: Vec::new(),
+            patterns: Vec::new(),
+            ignore_users: Vec::new(),
+            ignore_services: Vec::new(),
+            control_socket: String::from(DEFAULT_CONTROL_SOCKET),
+            state_directory: String::from(DEFAULT_STATE_DIRECTORY),
+            log_level: LogLevel::Info,
+            log_format: LogFormat::Text,
+            metrics_textfile: None,
+            metrics_address: None,
+            webhook: WebhookOptions::default(),
+            email: EmailOptions::default(),
+            desktop: DesktopOptions::default(),
+            power: PowerOptions::default(),
+            luks_suspend: LuksSuspendOptions::default(),
+            command: CommandOptions::default(),
+            tamper: TamperOptions::default(),
         };
     }
 }
@@ -109,8 +540,41 @@ impl Display for AuthMonitorParams {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
         return write!(
             formatter,
-            "filepath={}, options: {}",
-            self.filepath, self.options
+            "filepath={}, actions={}, warning-actions={}, dry-run={}, cancel-users={}, patterns={}, ignore-users={}, ignore-services={}, control-socket={}, state-directory={}, log-level={}, log-format={}, metrics-textfile={}, metrics-address={}, {}, {}, {}, {}, {}, {}, {}, options: {}",
+            self.filepath,
+            self.actions
+                .iter()
+                .map(Action::to_string)
+                .collect::<Vec<String>>()
+                .join(","),
+            self.warning_actions
+                .iter()
+                .map(Action::to_string)
+                .collect::<Vec<String>>()
+                .join(","),
+            self.dry_run,
+            self.cancel_users.join(","),
+            self.patterns.len(),
+            self.ignore_users.join(","),
+            self.ignore_services.join(","),
+            self.control_socket,
+            self.state_directory,
+            self.log_level,
+            self.log_format,
+            self.metrics_textfile.as_deref().unwrap_or(""),
+            self.metrics_address
+                .map(|address| {
+                    return address.to_string();
+                })
+                .unwrap_or_default(),
+            self.webhook,
+            self.email,
+            self.desktop,
+            self.power,
+            self.luks_suspend,
+            self.command,
+            self.tamper,
+            self.options
         );
     }
 }
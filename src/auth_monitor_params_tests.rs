@@ -1,10 +1,15 @@
+use std::env::temp_dir;
 use std::error::Error;
+use std::fs::{remove_file, write};
 
 use crate::assert_error;
 use crate::auth_monitor_options::AuthMonitorOptions;
 use crate::auth_monitor_params::{
-    AuthMonitorParams, MAX_FAILED_ATTEMPTS_OPTION, RESET_AFTER_SECONDS_OPTION,
+    AuthMonitorParams, CANCEL_USERS_OPTION, CLOCK_SKEW_TOLERANCE_SECONDS_OPTION, CONFIG_OPTION,
+    DRY_RUN_OPTION, GRACE_PERIOD_SECONDS_OPTION, IGNORE_INHIBITORS_OPTION,
+    MAX_FAILED_ATTEMPTS_OPTION, RESET_AFTER_SECONDS_OPTION,
 };
+use crate::power_options::{InhibitorPolicy, PowerBackend, PowerOptions};
 
 const FILEPATH: &str = "/var/log/auth.log";
 const ALL_OPTIONS: [&str; 2] = [MAX_FAILED_ATTEMPTS_OPTION, RESET_AFTER_SECONDS_OPTION];
@@ -77,6 +82,20 @@ fn expect_equals(result: Result<AuthMonitorParams, Box<dyn Error>>, expected: &A
         params.options.reset_after_seconds, expected.options.reset_after_seconds,
         "Reset after seconds does not match"
     );
+    assert_eq!(
+        params.options.clock_skew_tolerance_seconds, expected.options.clock_skew_tolerance_seconds,
+        "Clock skew tolerance seconds does not match"
+    );
+    assert_eq!(params.actions, expected.actions, "Actions do not match");
+    assert_eq!(params.dry_run, expected.dry_run, "Dry run does not match");
+    assert_eq!(
+        params.options.grace_period_seconds, expected.options.grace_period_seconds,
+        "Grace period seconds does not match"
+    );
+    assert_eq!(
+        params.cancel_users, expected.cancel_users,
+        "Cancel users do not match"
+    );
 }
 
 #[test]
@@ -99,7 +118,9 @@ fn when_parsing_filepath_with_one_option_with_correct_value_then_return_params_w
             options: AuthMonitorOptions {
                 max_failed_attempts,
                 reset_after_seconds,
+                ..AuthMonitorOptions::default()
             },
+            ..AuthMonitorParams::default()
         };
         expect_equals(AuthMonitorParams::from_arguments(&arguments), &expected);
     }
@@ -167,7 +188,9 @@ fn when_parsing_filename_and_multiple_options_then_return_params_with_parsed_val
         options: AuthMonitorOptions {
             max_failed_attempts,
             reset_after_seconds,
+            ..AuthMonitorOptions::default()
         },
+        ..AuthMonitorParams::default()
     };
     expect_equals(AuthMonitorParams::from_arguments(&arguments), &expected);
 }
@@ -186,3 +209,477 @@ fn when_parsing_unknown_option_then_return_unknown_option_error() {
         assert_error!(AuthMonitorParams::from_arguments(&arguments), expected)
     }
 }
+
+#[test]
+fn when_parsing_clock_skew_tolerance_then_zero_is_accepted_and_negative_value_is_rejected() {
+    for value in [0, 1, 600] {
+        let arguments = [
+            String::from(FILEPATH),
+            format!("--{}={}", CLOCK_SKEW_TOLERANCE_SECONDS_OPTION, value),
+        ];
+        let expected = AuthMonitorParams {
+            filepath: String::from(FILEPATH),
+            options: AuthMonitorOptions {
+                clock_skew_tolerance_seconds: value,
+                ..AuthMonitorOptions::default()
+            },
+            ..AuthMonitorParams::default()
+        };
+        expect_equals(AuthMonitorParams::from_arguments(&arguments), &expected);
+    }
+    for value in [-1, i32::MIN] {
+        let arguments = [
+            String::from(FILEPATH),
+            format!("--{}={}", CLOCK_SKEW_TOLERANCE_SECONDS_OPTION, value),
+        ];
+        let expected = format!(
+            "{} must be greater than or equal to 0",
+            CLOCK_SKEW_TOLERANCE_SECONDS_OPTION
+        );
+        assert_error!(AuthMonitorParams::from_arguments(&arguments), expected);
+    }
+}
+
+#[test]
+fn when_parsing_dry_run_option_then_return_params_with_dry_run_enabled() {
+    let arguments = [String::from(FILEPATH), format!("--{}", DRY_RUN_OPTION)];
+    let expected = AuthMonitorParams {
+        filepath: String::from(FILEPATH),
+        dry_run: true,
+        ..AuthMonitorParams::default()
+    };
+    expect_equals(AuthMonitorParams::from_arguments(&arguments), &expected);
+}
+
+#[test]
+fn when_parsing_dry_run_option_with_value_then_return_unexpected_value_error() {
+    let arguments = [String::from(FILEPATH), format!("--{}=yes", DRY_RUN_OPTION)];
+    let expected = format!("Option --{} does not take a value", DRY_RUN_OPTION);
+    assert_error!(AuthMonitorParams::from_arguments(&arguments), expected);
+}
+
+#[test]
+fn when_parsing_grace_period_and_cancel_users_then_return_params_with_parsed_values() {
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}=30", GRACE_PERIOD_SECONDS_OPTION),
+        format!("--{}=john,alice", CANCEL_USERS_OPTION),
+    ];
+    let expected = AuthMonitorParams {
+        filepath: String::from(FILEPATH),
+        options: AuthMonitorOptions {
+            grace_period_seconds: 30,
+            ..AuthMonitorOptions::default()
+        },
+        cancel_users: vec![String::from("john"), String::from("alice")],
+        ..AuthMonitorParams::default()
+    };
+    expect_equals(AuthMonitorParams::from_arguments(&arguments), &expected);
+}
+
+#[test]
+fn when_parsing_invalid_grace_period_or_cancel_users_then_return_error() {
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}=-1", GRACE_PERIOD_SECONDS_OPTION),
+    ];
+    let expected = format!(
+        "{} must be greater than or equal to 0",
+        GRACE_PERIOD_SECONDS_OPTION
+    );
+    assert_error!(AuthMonitorParams::from_arguments(&arguments), expected);
+
+    for value in ["john,", ",john", "john,,alice"] {
+        let arguments = [
+            String::from(FILEPATH),
+            format!("--{}={}", CANCEL_USERS_OPTION, value),
+        ];
+        let expected = format!(
+            "\"{}\" is not a valid value for option --{}",
+            value, CANCEL_USERS_OPTION
+        );
+        assert_error!(AuthMonitorParams::from_arguments(&arguments), expected);
+    }
+}
+
+#[test]
+fn when_parsing_config_option_then_options_passed_as_arguments_take_precedence() {
+    let config_path = temp_dir().join("auth-monitor-params-test.toml");
+    let config = "log-file = \"/var/log/secure\"\n\n[thresholds]\nmax-failed-attempts = 3\nreset-after-seconds = 600\n";
+    write(&config_path, config).expect("Error writing test config");
+    let config_option = format!("--{}={}", CONFIG_OPTION, config_path.to_str().unwrap());
+
+    let config_only_result =
+        AuthMonitorParams::from_arguments(std::slice::from_ref(&config_option));
+    let arguments = [
+        config_option,
+        String::from(FILEPATH),
+        format!("--{}=7", MAX_FAILED_ATTEMPTS_OPTION),
+    ];
+    let overridden_result = AuthMonitorParams::from_arguments(&arguments);
+    remove_file(&config_path).expect("Unable to remove test config");
+
+    let expected = AuthMonitorParams {
+        filepath: String::from("/var/log/secure"),
+        options: AuthMonitorOptions {
+            max_failed_attempts: 3,
+            reset_after_seconds: 600,
+            ..AuthMonitorOptions::default()
+        },
+        ..AuthMonitorParams::default()
+    };
+    expect_equals(config_only_result, &expected);
+
+    let expected = AuthMonitorParams {
+        filepath: String::from(FILEPATH),
+        options: AuthMonitorOptions {
+            max_failed_attempts: 7,
+            reset_after_seconds: 600,
+            ..AuthMonitorOptions::default()
+        },
+        ..AuthMonitorParams::default()
+    };
+    expect_equals(overridden_result, &expected);
+}
+
+#[test]
+fn when_parsing_config_option_with_missing_file_then_return_read_error() {
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}=/nonexistent/auth-monitor.toml", CONFIG_OPTION),
+    ];
+    assert_error!(
+        AuthMonitorParams::from_arguments(&arguments),
+        "Error reading configuration file /nonexistent/auth-monitor.toml: No such file or directory (os error 2)"
+    );
+}
+
+#[test]
+fn when_webhook_action_is_configured_without_url_then_return_error() {
+    let config_path = temp_dir().join("auth-monitor-params-webhook-test.toml");
+    let config = "[actions]\nexecute = [\"webhook\", \"poweroff\"]\n";
+    write(&config_path, config).expect("Error writing test config");
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}={}", CONFIG_OPTION, config_path.to_str().unwrap()),
+    ];
+    let result = AuthMonitorParams::from_arguments_and_environment(&arguments, &[]);
+    let environment = environment(&[("AUTH_MONITOR_WEBHOOK_URL", "http://127.0.0.1/hook")]);
+    let result_with_url =
+        AuthMonitorParams::from_arguments_and_environment(&arguments, &environment);
+    remove_file(&config_path).expect("Unable to remove test config");
+
+    assert_error!(result, "webhook action requires webhook-url");
+    assert_eq!(
+        result_with_url.unwrap().webhook.url.as_deref(),
+        Some("http://127.0.0.1/hook")
+    );
+}
+
+#[test]
+fn when_warning_threshold_is_not_below_limit_then_return_error() {
+    let arguments = [
+        String::from(FILEPATH),
+        String::from("--max-failed-attempts=3"),
+        String::from("--warning-failed-attempts=3"),
+    ];
+
+    let result = AuthMonitorParams::from_arguments_and_environment(&arguments, &[]);
+
+    assert_error!(
+        result,
+        "warning-failed-attempts must be less than max-failed-attempts"
+    );
+}
+
+#[test]
+fn when_email_action_is_configured_without_recipients_then_return_error() {
+    let config_path = temp_dir().join("auth-monitor-params-email-test.toml");
+    let config = "[actions]\nexecute = [\"email\"]\n[email]\nserver = \"mail.example.com\"\nfrom = \"auth-monitor@example.com\"\n";
+    write(&config_path, config).expect("Error writing test config");
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}={}", CONFIG_OPTION, config_path.to_str().unwrap()),
+    ];
+    let result = AuthMonitorParams::from_arguments_and_environment(&arguments, &[]);
+    remove_file(&config_path).expect("Unable to remove test config");
+
+    assert_error!(result, "email action requires email.from and email.to");
+}
+
+#[test]
+fn when_email_credentials_are_configured_without_encryption_then_return_error() {
+    let config_path = temp_dir().join("auth-monitor-params-email-security-test.toml");
+    let config = "[email]\nsecurity = \"none\"\nusername = \"alerts\"\npassword = \"secret\"\n";
+    write(&config_path, config).expect("Error writing test config");
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}={}", CONFIG_OPTION, config_path.to_str().unwrap()),
+    ];
+    let result = AuthMonitorParams::from_arguments_and_environment(&arguments, &[]);
+    remove_file(&config_path).expect("Unable to remove test config");
+
+    assert_error!(
+        result,
+        "email.username and email.password require email.security = \"starttls\""
+    );
+}
+
+#[test]
+fn when_luks_suspend_action_is_configured_without_devices_or_hook_then_return_error() {
+    let config_path = temp_dir().join("auth-monitor-params-luks-suspend-test.toml");
+    let config = "[actions]\nexecute = [\"luks-suspend\"]\n[luks-suspend]\ntimeout-seconds = 3\n";
+    write(&config_path, config).expect("Error writing test config");
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}={}", CONFIG_OPTION, config_path.to_str().unwrap()),
+    ];
+    let result = AuthMonitorParams::from_arguments_and_environment(&arguments, &[]);
+    remove_file(&config_path).expect("Unable to remove test config");
+
+    assert_error!(
+        result,
+        "luks-suspend action requires luks-suspend.devices or luks-suspend.hook"
+    );
+}
+
+#[test]
+fn when_tamper_response_uses_command_action_without_exec_then_return_error() {
+    let config_path = temp_dir().join("auth-monitor-params-tamper-test.toml");
+    let config = "[tamper]\nsilent = [\"command\"]\n";
+    write(&config_path, config).expect("Error writing test config");
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}={}", CONFIG_OPTION, config_path.to_str().unwrap()),
+    ];
+    let result = AuthMonitorParams::from_arguments_and_environment(&arguments, &[]);
+    remove_file(&config_path).expect("Unable to remove test config");
+
+    assert_error!(result, "command action requires command.exec");
+}
+
+#[test]
+fn when_ignoring_inhibitors_then_power_inhibitor_policy_is_ignore() {
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}", IGNORE_INHIBITORS_OPTION),
+    ];
+    let params = AuthMonitorParams::from_arguments_and_environment(&arguments, &[]).unwrap();
+    assert_eq!(params.power.inhibitors, InhibitorPolicy::Ignore);
+
+    let environment = environment(&[("AUTH_MONITOR_IGNORE_INHIBITORS", "true")]);
+    let params =
+        AuthMonitorParams::from_arguments_and_environment(&[String::from(FILEPATH)], &environment)
+            .unwrap();
+    assert_eq!(params.power.inhibitors, InhibitorPolicy::Ignore);
+
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}=yes", IGNORE_INHIBITORS_OPTION),
+    ];
+    assert_error!(
+        AuthMonitorParams::from_arguments_and_environment(&arguments, &[]),
+        "Option --ignore-inhibitors does not take a value"
+    );
+}
+
+#[test]
+fn when_fallback_backend_is_the_same_as_backend_then_return_error() {
+    let params = AuthMonitorParams {
+        filepath: String::from(FILEPATH),
+        power: PowerOptions {
+            backend: PowerBackend::Kernel,
+            inhibitors: InhibitorPolicy::Fallback,
+            fallback_backend: PowerBackend::Kernel,
+            ..PowerOptions::default()
+        },
+        ..AuthMonitorParams::default()
+    };
+
+    assert_error!(
+        params.validate(),
+        "power.fallback-backend must differ from power.backend"
+    );
+}
+
+fn environment(variables: &[(&str, &str)]) -> Vec<(String, String)> {
+    return variables
+        .iter()
+        .map(|(name, value)| {
+            return (name.to_string(), value.to_string());
+        })
+        .collect();
+}
+
+#[test]
+fn when_parsing_environment_variables_then_arguments_take_precedence() {
+    let environment = environment(&[
+        ("AUTH_MONITOR_LOG_FILE", "/var/log/secure"),
+        ("AUTH_MONITOR_MAX_FAILED_ATTEMPTS", "3"),
+        ("AUTH_MONITOR_RESET_AFTER_SECONDS", "600"),
+        ("AUTH_MONITOR_DRY_RUN", "true"),
+        ("AUTH_MONITOR_CANCEL_USERS", "john,alice"),
+        ("HOME", "/root"),
+    ]);
+    let environment_only_result =
+        AuthMonitorParams::from_arguments_and_environment(&[], &environment);
+    let arguments = [
+        String::from(FILEPATH),
+        format!("--{}=7", MAX_FAILED_ATTEMPTS_OPTION),
+    ];
+    let overridden_result =
+        AuthMonitorParams::from_arguments_and_environment(&arguments, &environment);
+
+    let expected = AuthMonitorParams {
+        filepath: String::from("/var/log/secure"),
+        options: AuthMonitorOptions {
+            max_failed_attempts: 3,
+            reset_after_seconds: 600,
+            ..AuthMonitorOptions::default()
+        },
+        dry_run: true,
+        cancel_users: vec![String::from("john"), String::from("alice")],
+        ..AuthMonitorParams::default()
+    };
+    expect_equals(environment_only_result, &expected);
+
+    let expected = AuthMonitorParams {
+        filepath: String::from(FILEPATH),
+        options: AuthMonitorOptions {
+            max_failed_attempts: 7,
+            reset_after_seconds: 600,
+            ..AuthMonitorOptions::default()
+        },
+        dry_run: true,
+        cancel_users: vec![String::from("john"), String::from("alice")],
+        ..AuthMonitorParams::default()
+    };
+    expect_equals(overridden_result, &expected);
+}
+
+#[test]
+fn when_legacy_environment_variables_are_set_then_current_names_take_precedence() {
+    let legacy_only = environment(&[
+        ("LOG_FILE", "/var/log/secure"),
+        ("MAX_FAILED_ATTEMPTS", "3"),
+        ("RESET_AFTER_SECONDS", "600"),
+    ]);
+    let both = environment(&[
+        ("AUTH_MONITOR_MAX_FAILED_ATTEMPTS", "4"),
+        ("LOG_FILE", "/var/log/secure"),
+        ("MAX_FAILED_ATTEMPTS", "3"),
+    ]);
+
+    let legacy_only_result = AuthMonitorParams::from_arguments_and_environment(&[], &legacy_only);
+    let both_result = AuthMonitorParams::from_arguments_and_environment(&[], &both);
+
+    let expected = AuthMonitorParams {
+        filepath: String::from("/var/log/secure"),
+        options: AuthMonitorOptions {
+            max_failed_attempts: 3,
+            reset_after_seconds: 600,
+            ..AuthMonitorOptions::default()
+        },
+        ..AuthMonitorParams::default()
+    };
+    expect_equals(legacy_only_result, &expected);
+    let expected = AuthMonitorParams {
+        filepath: String::from("/var/log/secure"),
+        options: AuthMonitorOptions {
+            max_failed_attempts: 4,
+            ..AuthMonitorOptions::default()
+        },
+        ..AuthMonitorParams::default()
+    };
+    expect_equals(both_result, &expected);
+}
+
+#[test]
+fn when_parsing_empty_environment_variables_then_they_are_ignored() {
+    let environment = environment(&[
+        ("AUTH_MONITOR_MAX_FAILED_ATTEMPTS", ""),
+        ("AUTH_MONITOR_RESET_AFTER_SECONDS", ""),
+        ("AUTH_MONITOR_DRY_RUN", "false"),
+    ]);
+    let arguments = [String::from(FILEPATH)];
+    let expected = AuthMonitorParams {
+        filepath: String::from(FILEPATH),
+        ..AuthMonitorParams::default()
+    };
+    expect_equals(
+        AuthMonitorParams::from_arguments_and_environment(&arguments, &environment),
+        &expected,
+    );
+}
+
+#[test]
+fn when_parsing_environment_variables_then_they_take_precedence_over_config() {
+    let config_path = temp_dir().join("auth-monitor-params-environment-test.toml");
+    let config = "log-file = \"/var/log/secure\"\n\n[thresholds]\nmax-failed-attempts = 3\nreset-after-seconds = 600\n";
+    write(&config_path, config).expect("Error writing test config");
+    let environment = environment(&[
+        ("AUTH_MONITOR_CONFIG", config_path.to_str().unwrap()),
+        ("AUTH_MONITOR_MAX_FAILED_ATTEMPTS", "4"),
+    ]);
+    let result = AuthMonitorParams::from_arguments_and_environment(&[], &environment);
+    remove_file(&config_path).expect("Unable to remove test config");
+
+    let expected = AuthMonitorParams {
+        filepath: String::from("/var/log/secure"),
+        options: AuthMonitorOptions {
+            max_failed_attempts: 4,
+            reset_after_seconds: 600,
+            ..AuthMonitorOptions::default()
+        },
+        ..AuthMonitorParams::default()
+    };
+    expect_equals(result, &expected);
+}
+
+#[test]
+fn when_parsing_invalid_environment_variable_then_return_error_with_variable_name() {
+    let variables = [
+        (
+            ("AUTH_MONITOR_MAX_FAILED_ATTEMPTS", "five"),
+            "Invalid environment variable AUTH_MONITOR_MAX_FAILED_ATTEMPTS: \"five\" is not a valid value for option --max-failed-attempts",
+        ),
+        (
+            ("AUTH_MONITOR_CANCEL_USERS", "john,"),
+            "Invalid environment variable AUTH_MONITOR_CANCEL_USERS: \"john,\" is not a valid value for option --cancel-users",
+        ),
+        (
+            ("AUTH_MONITOR_DRY_RUN", "yes"),
+            "\"yes\" is not a valid value for AUTH_MONITOR_DRY_RUN",
+        ),
+        (
+            ("AUTH_MONITOR_LOG_LEVEL", "verbose"),
+            "Invalid environment variable AUTH_MONITOR_LOG_LEVEL: \"verbose\" is not a valid value for option --log-level",
+        ),
+        (
+            ("AUTH_MONITOR_METRICS_ADDRESS", "0.0.0.0:9157"),
+            "metrics-address 0.0.0.0:9157 is not a loopback address",
+        ),
+        (
+            ("AUTH_MONITOR_WEBHOOK_URL", "ftp://example.com"),
+            "ftp://example.com is not an http or https URL",
+        ),
+        (
+            ("AUTH_MONITOR_MAX_ATTEMPTS", "5"),
+            "Unknown environment variable AUTH_MONITOR_MAX_ATTEMPTS",
+        ),
+    ];
+    let arguments = [String::from(FILEPATH)];
+    for (variable, expected) in variables {
+        let environment = environment(&[variable]);
+        assert_error!(
+            AuthMonitorParams::from_arguments_and_environment(&arguments, &environment),
+            expected
+        );
+    }
+
+    let environment = environment(&[("AUTH_MONITOR_MAX_FAILED_ATTEMPTS", "0")]);
+    assert_error!(
+        AuthMonitorParams::from_arguments_and_environment(&arguments, &environment),
+        "max-failed-attempts must be greater than 0"
+    );
+}
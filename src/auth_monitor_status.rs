@@ -0,0 +1,66 @@
+use std::fmt::{Display, Formatter};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
+
+const NONE_VALUE: &str = "none";
+
+pub struct AuthMonitorStatus {
+    pub failed_attempts: i32,
+    pub max_failed_attempts: i32,
+    pub last_event: Option<(SystemTime, String)>,
+    pub disarmed_until: Option<SystemTime>,
+    pub countdown_deadline: Option<SystemTime>,
+    pub watched_files: Vec<String>,
+}
+
+impl AuthMonitorStatus {
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Failed attempts {}/{}",
+            self.failed_attempts, self.max_failed_attempts
+        );
+        if self.disarmed_until.is_some() {
+            summary += ", disarmed";
+        }
+        if let Some(deadline) = self.countdown_deadline {
+            summary += &format!(", actions at {}", Self::format_time(deadline));
+        }
+        return summary;
+    }
+
+    fn format_time(time: SystemTime) -> String {
+        let time: DateTime<Local> = DateTime::from(time);
+        return time.to_rfc3339();
+    }
+}
+
+impl Display for AuthMonitorStatus {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let armed = match self.disarmed_until {
+            Some(time) => format!("no, until {}", Self::format_time(time)),
+            None => String::from("yes"),
+        };
+        let last_event = match &self.last_event {
+            Some((time, description)) => format!("{} {}", Self::format_time(*time), description),
+            None => String::from(NONE_VALUE),
+        };
+        let countdown = match self.countdown_deadline {
+            Some(time) => Self::format_time(time),
+            None => String::from(NONE_VALUE),
+        };
+        writeln!(formatter, "armed: {}", armed)?;
+        writeln!(
+            formatter,
+            "failed-attempts: {}/{}",
+            self.failed_attempts, self.max_failed_attempts
+        )?;
+        writeln!(formatter, "last-event: {}", last_event)?;
+        writeln!(formatter, "countdown: {}", countdown)?;
+        return write!(
+            formatter,
+            "watched-files: {}",
+            self.watched_files.join(", ")
+        );
+    }
+}
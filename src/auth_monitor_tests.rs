@@ -1,10 +1,16 @@
 use std::ops::Range;
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
+use chrono::Local;
+
+use crate::action::Action;
+use crate::assert_error;
 use crate::auth_monitor::AuthMonitor;
 use crate::auth_monitor_options::AuthMonitorOptions;
 use crate::auth_monitor_params::AuthMonitorParams;
+use crate::tamper_options::{TamperOptions, TamperResponse};
+use crate::termination_log::TerminationLog;
 use crate::test_utils::test_file::TestFile;
 
 struct AuthMonitorTest {
@@ -17,20 +23,21 @@ impl AuthMonitorTest {
         let auth_monitor = AuthMonitor::new(AuthMonitorParams {
             filepath: String::from(path),
             options,
+            ..AuthMonitorParams::default()
         })
         .expect("Error creating AuthMonitor");
         return AuthMonitorTest { auth_monitor };
     }
 
     pub fn expect_no_update_callback_call(&mut self) {
-        self.auth_monitor.update(|| {
+        self.auth_monitor.update(|_, _| {
             panic!("Callback call was not expected");
         });
     }
 
     pub fn expect_update_callback_is_called_once(&mut self) {
         let mut call_count = 0;
-        self.auth_monitor.update(|| {
+        self.auth_monitor.update(|_, _| {
             call_count += 1;
         });
         assert_eq!(call_count, 1, "One callback call was expected")
@@ -179,3 +186,450 @@ fn when_file_is_truncated_then_changes_are_still_monitored() {
     file.write_auth_failed_messages(options.max_failed_attempts as usize);
     test.expect_update_callback_is_called_once();
 }
+
+#[test]
+fn when_failed_attempts_are_older_than_reset_time_then_they_are_ignored() {
+    let mut file = TestFile::not_empty();
+    let options = AuthMonitorOptions::default();
+    let mut test = AuthMonitorTest::new(file.path(), options);
+
+    let old_time = Local::now()
+        - chrono::Duration::seconds(
+            (options.reset_after_seconds + options.clock_skew_tolerance_seconds + 60) as i64,
+        );
+    file.write_auth_failed_messages_at(options.max_failed_attempts as usize, old_time);
+    test.expect_no_update_callback_call();
+
+    file.write_auth_failed_messages(options.max_failed_attempts as usize);
+    test.expect_update_callback_is_called_once();
+}
+
+#[test]
+fn when_failed_attempts_have_syslog_timestamps_then_they_are_counted() {
+    let mut file = TestFile::not_empty();
+    let options = AuthMonitorOptions::default();
+    let mut test = AuthMonitorTest::new(file.path(), options);
+
+    let timestamp = Local::now().format("%b %e %H:%M:%S").to_string();
+    for i in 0usize..(options.max_failed_attempts - 1) as usize {
+        file.write_auth_failed_message_at(i, &timestamp);
+        test.expect_no_update_callback_call();
+    }
+
+    file.write_auth_failed_message_at(0, &timestamp);
+    test.expect_update_callback_is_called_once();
+}
+
+#[test]
+fn when_dry_run_is_enabled_then_limit_is_recorded_instead_of_invoking_callback() {
+    let mut file = TestFile::not_empty();
+    let options = AuthMonitorOptions::default();
+    let mut auth_monitor = AuthMonitor::new(AuthMonitorParams {
+        filepath: String::from(file.path()),
+        options,
+        dry_run: true,
+        ..AuthMonitorParams::default()
+    })
+    .expect("Error creating AuthMonitor");
+
+    for _ in 0..2 {
+        file.write_auth_failed_messages(options.max_failed_attempts as usize);
+        auth_monitor.update(|_, _| {
+            panic!("Callback call was not expected in dry run");
+        });
+    }
+
+    assert_eq!(auth_monitor.dry_run_events.len(), 2);
+    for event in &auth_monitor.dry_run_events {
+        assert_eq!(event.failed_attempts, options.max_failed_attempts);
+    }
+}
+
+fn create_auth_monitor_with_grace_period(path: &str) -> AuthMonitor {
+    let mut auth_monitor = AuthMonitor::new(AuthMonitorParams {
+        filepath: String::from(path),
+        options: AuthMonitorOptions {
+            grace_period_seconds: 2,
+            ..AuthMonitorOptions::default()
+        },
+        cancel_users: vec![String::from("john")],
+        ..AuthMonitorParams::default()
+    })
+    .expect("Error creating AuthMonitor");
+    auth_monitor.broadcast = |message| {
+        println!("Broadcast: {}", message);
+    };
+    return auth_monitor;
+}
+
+#[test]
+fn when_grace_period_is_set_then_callback_is_invoked_after_it_has_passed() {
+    let mut file = TestFile::not_empty();
+    let mut auth_monitor = create_auth_monitor_with_grace_period(file.path());
+    let options = AuthMonitorOptions::default();
+
+    file.write_auth_failed_messages(options.max_failed_attempts as usize);
+    auth_monitor.update(|_, _| {
+        panic!("Callback call was not expected during grace period");
+    });
+    assert!(auth_monitor.countdown_deadline.is_some());
+
+    sleep(Duration::from_secs(3));
+    let mut call_count = 0;
+    auth_monitor.update(|_, _| {
+        call_count += 1;
+    });
+    assert_eq!(call_count, 1, "One callback call was expected");
+    assert!(auth_monitor.countdown_deadline.is_none());
+}
+
+#[test]
+fn when_cancel_user_authenticates_during_grace_period_then_callback_is_not_invoked() {
+    let mut file = TestFile::not_empty();
+    let mut auth_monitor = create_auth_monitor_with_grace_period(file.path());
+    let options = AuthMonitorOptions::default();
+
+    file.write_auth_failed_messages(options.max_failed_attempts as usize);
+    file.write_session_opened_message("mallory");
+    auth_monitor.update(|_, _| {
+        panic!("Callback call was not expected during grace period");
+    });
+    assert!(auth_monitor.countdown_deadline.is_some());
+
+    file.write_session_opened_message("john");
+    auth_monitor.update(|_, _| {
+        panic!("Callback call was not expected during grace period");
+    });
+    assert!(auth_monitor.countdown_deadline.is_none());
+    assert_eq!(auth_monitor.counter.failed_attempts(), 0);
+
+    sleep(Duration::from_secs(3));
+    auth_monitor.update(|_, _| {
+        panic!("Callback call was not expected after cancellation");
+    });
+}
+
+#[test]
+fn when_configuration_is_reloaded_then_failed_attempts_are_kept_and_new_limit_is_used() {
+    let mut file = TestFile::not_empty();
+    let options = AuthMonitorOptions::default();
+    let mut test = AuthMonitorTest::new(file.path(), options);
+
+    file.write_auth_failed_messages(2);
+    test.expect_no_update_callback_call();
+
+    let params = AuthMonitorParams {
+        filepath: String::from(file.path()),
+        options: AuthMonitorOptions {
+            max_failed_attempts: 3,
+            ..options
+        },
+        ..AuthMonitorParams::default()
+    };
+    test.auth_monitor.reload(params).expect("Error reloading");
+    assert_eq!(test.auth_monitor.counter.failed_attempts(), 2);
+
+    file.write_auth_failed_message(0);
+    test.expect_update_callback_is_called_once();
+}
+
+#[test]
+fn when_reloaded_configuration_is_invalid_then_previous_one_is_kept() {
+    let mut file = TestFile::not_empty();
+    let options = AuthMonitorOptions::default();
+    let mut test = AuthMonitorTest::new(file.path(), options);
+
+    file.write_auth_failed_messages(2);
+    test.expect_no_update_callback_call();
+
+    let invalid_params = [
+        AuthMonitorParams {
+            filepath: String::from(file.path()),
+            options: AuthMonitorOptions {
+                max_failed_attempts: 0,
+                ..options
+            },
+            ..AuthMonitorParams::default()
+        },
+        AuthMonitorParams {
+            filepath: String::from("/nonexistent/auth.log"),
+            options: AuthMonitorOptions {
+                max_failed_attempts: 3,
+                ..options
+            },
+            ..AuthMonitorParams::default()
+        },
+    ];
+    for params in invalid_params {
+        assert!(test.auth_monitor.reload(params).is_err());
+    }
+    assert_eq!(test.auth_monitor.counter.failed_attempts(), 2);
+
+    file.write_auth_failed_message(0);
+    test.expect_no_update_callback_call();
+    file.write_auth_failed_messages((options.max_failed_attempts - 3) as usize);
+    test.expect_update_callback_is_called_once();
+}
+
+#[test]
+fn when_disarmed_then_failed_attempts_are_counted_but_callback_is_not_invoked() {
+    let mut file = TestFile::not_empty();
+    let options = AuthMonitorOptions::default();
+    let state_directory = std::env::temp_dir().join("auth-monitor-disarm-test");
+    std::fs::create_dir_all(&state_directory).unwrap();
+    let mut test = AuthMonitorTest {
+        auth_monitor: AuthMonitor::new(AuthMonitorParams {
+            filepath: String::from(file.path()),
+            state_directory: String::from(state_directory.to_str().unwrap()),
+            ..AuthMonitorParams::default()
+        })
+        .unwrap(),
+    };
+    test.auth_monitor.disarm(Duration::from_secs(2)).unwrap();
+
+    file.write_auth_failed_messages(2);
+    test.expect_no_update_callback_call();
+    assert_eq!(test.auth_monitor.status().failed_attempts, 2);
+    assert!(test.auth_monitor.status().disarmed_until.is_some());
+
+    file.write_auth_failed_messages((options.max_failed_attempts - 2) as usize);
+    test.expect_no_update_callback_call();
+
+    sleep(Duration::from_secs(3));
+    test.expect_no_update_callback_call();
+    assert!(test.auth_monitor.status().disarmed_until.is_none());
+    file.write_auth_failed_messages(options.max_failed_attempts as usize);
+    test.expect_update_callback_is_called_once();
+}
+
+#[test]
+fn when_reset_is_requested_then_failed_attempts_and_countdown_are_cleared() {
+    let mut file = TestFile::not_empty();
+    let mut auth_monitor = create_auth_monitor_with_grace_period(file.path());
+    let options = AuthMonitorOptions::default();
+
+    file.write_auth_failed_messages(options.max_failed_attempts as usize);
+    auth_monitor.update(|_, _| {
+        panic!("Callback call was not expected during grace period");
+    });
+    let status = auth_monitor.status();
+    assert_eq!(status.failed_attempts, options.max_failed_attempts);
+    assert!(status.countdown_deadline.is_some());
+    assert!(status.last_event.is_some());
+
+    auth_monitor.reset();
+    let status = auth_monitor.status();
+    assert_eq!(status.failed_attempts, 0);
+    assert!(status.countdown_deadline.is_none());
+}
+
+#[test]
+fn when_auth_monitor_is_restarted_while_disarmed_then_it_stays_disarmed() {
+    let file = TestFile::not_empty();
+    let state_directory = std::env::temp_dir().join("auth-monitor-disarm-state-test");
+    std::fs::create_dir_all(&state_directory).unwrap();
+    let params = || {
+        return AuthMonitorParams {
+            filepath: String::from(file.path()),
+            state_directory: String::from(state_directory.to_str().unwrap()),
+            ..AuthMonitorParams::default()
+        };
+    };
+
+    let mut auth_monitor = AuthMonitor::new(params()).unwrap();
+    auth_monitor.disarm(Duration::from_secs(3600)).unwrap();
+    let disarmed_until = auth_monitor.status().disarmed_until;
+    drop(auth_monitor);
+
+    let mut auth_monitor = AuthMonitor::new(params()).unwrap();
+    let restored_disarmed_until = auth_monitor.status().disarmed_until.unwrap();
+    let difference = disarmed_until
+        .unwrap()
+        .duration_since(restored_disarmed_until)
+        .unwrap();
+    assert!(difference < Duration::from_secs(1));
+
+    auth_monitor.arm();
+    drop(auth_monitor);
+    let auth_monitor = AuthMonitor::new(params()).unwrap();
+    assert!(auth_monitor.status().disarmed_until.is_none());
+    std::fs::remove_dir_all(&state_directory).unwrap();
+}
+
+#[test]
+fn when_disarm_state_is_out_of_range_then_auth_monitor_stays_armed() {
+    let file = TestFile::not_empty();
+    let state_directory = std::env::temp_dir().join("auth-monitor-disarm-range-test");
+    std::fs::create_dir_all(&state_directory).unwrap();
+    let params = || {
+        return AuthMonitorParams {
+            filepath: String::from(file.path()),
+            state_directory: String::from(state_directory.to_str().unwrap()),
+            ..AuthMonitorParams::default()
+        };
+    };
+
+    let mut auth_monitor = AuthMonitor::new(params()).unwrap();
+    let result = auth_monitor.disarm(Duration::from_secs(u64::MAX));
+    let disarmed_until = auth_monitor.status().disarmed_until;
+    drop(auth_monitor);
+    let mut restarted = Vec::new();
+    for state in ["18446744073709551615", "99999999999"] {
+        std::fs::write(state_directory.join("disarmed-until"), state).unwrap();
+        restarted.push(AuthMonitor::new(params()).unwrap().status().disarmed_until);
+    }
+    std::fs::remove_dir_all(&state_directory).unwrap();
+
+    assert_error!(result, "Disarm duration is longer than 31536000 second(s)");
+    assert!(disarmed_until.is_none());
+    assert_eq!(restarted, [None, None]);
+}
+
+#[test]
+fn when_warning_threshold_is_crossed_then_warning_actions_are_executed_once() {
+    let mut file = TestFile::not_empty();
+    let mut auth_monitor = AuthMonitor::new(AuthMonitorParams {
+        filepath: String::from(file.path()),
+        options: AuthMonitorOptions {
+            max_failed_attempts: 4,
+            warning_failed_attempts: 2,
+            ..AuthMonitorOptions::default()
+        },
+        warning_actions: vec![Action::Desktop],
+        ..AuthMonitorParams::default()
+    })
+    .unwrap();
+    let update = |auth_monitor: &mut AuthMonitor| {
+        let mut calls = Vec::new();
+        auth_monitor.update(|actions, context| {
+            calls.push((actions.to_vec(), context.summary()));
+        });
+        return calls;
+    };
+    assert!(update(&mut auth_monitor).is_empty());
+
+    file.write_auth_failed_message(0);
+    assert!(update(&mut auth_monitor).is_empty());
+    file.write_auth_failed_message(1);
+    assert_eq!(
+        update(&mut auth_monitor),
+        [(
+            vec![Action::Desktop],
+            String::from("2 failed authentication attempt(s); the machine will power off after 4")
+        )]
+    );
+    file.write_auth_failed_message(2);
+    assert!(update(&mut auth_monitor).is_empty());
+    file.write_auth_failed_message(3);
+    assert_eq!(
+        update(&mut auth_monitor),
+        [(
+            vec![Action::PowerOff],
+            String::from("4 failed authentication attempt(s); the limit of 4 has been reached")
+        )]
+    );
+}
+
+#[test]
+fn when_deletion_is_counted_then_it_adds_to_failed_attempts() {
+    let mut file = TestFile::not_empty();
+    let mut test = AuthMonitorTest {
+        auth_monitor: AuthMonitor::new(AuthMonitorParams {
+            filepath: String::from(file.path()),
+            options: AuthMonitorOptions {
+                max_failed_attempts: 2,
+                ..AuthMonitorOptions::default()
+            },
+            tamper: TamperOptions {
+                deleted: TamperResponse::Count,
+                ..TamperOptions::default()
+            },
+            ..AuthMonitorParams::default()
+        })
+        .unwrap(),
+    };
+    file.remove();
+    test.expect_no_update_callback_call();
+    assert_eq!(test.auth_monitor.status().failed_attempts, 1);
+
+    file.create();
+    file.write_auth_failed_message(0);
+    test.expect_update_callback_is_called_once();
+}
+
+#[test]
+fn when_truncation_has_actions_then_they_are_executed_without_counting() {
+    let mut file = TestFile::not_empty();
+    file.write_other_messages(5);
+    let mut auth_monitor = AuthMonitor::new(AuthMonitorParams {
+        filepath: String::from(file.path()),
+        tamper: TamperOptions {
+            truncated: TamperResponse::Actions(vec![Action::Wall, Action::Desktop]),
+            ..TamperOptions::default()
+        },
+        ..AuthMonitorParams::default()
+    })
+    .unwrap();
+
+    file.truncate();
+    let mut calls = Vec::new();
+    auth_monitor.update(|actions, _| {
+        calls.push(actions.to_vec());
+    });
+
+    assert_eq!(calls, [vec![Action::Wall, Action::Desktop]]);
+    assert_eq!(auth_monitor.status().failed_attempts, 0);
+}
+
+#[test]
+fn when_daemon_was_terminated_too_often_then_tamper_actions_are_executed_once() {
+    let file = TestFile::not_empty();
+    let state_directory = std::env::temp_dir().join("auth-monitor-terminations-test");
+    std::fs::create_dir_all(&state_directory).unwrap();
+    let state_directory = String::from(state_directory.to_str().unwrap());
+    let termination_log = TerminationLog::new(&state_directory);
+    termination_log
+        .record(
+            SystemTime::now(),
+            "signal (killed KILL)",
+            Duration::from_secs(60),
+        )
+        .unwrap();
+    termination_log
+        .record(
+            SystemTime::now(),
+            "signal (killed KILL)",
+            Duration::from_secs(60),
+        )
+        .unwrap();
+    let params = || {
+        return AuthMonitorParams {
+            filepath: String::from(file.path()),
+            state_directory: state_directory.clone(),
+            tamper: TamperOptions {
+                terminated: TamperResponse::Actions(vec![Action::Wall]),
+                max_terminations: 2,
+                ..TamperOptions::default()
+            },
+            ..AuthMonitorParams::default()
+        };
+    };
+    let update = |auth_monitor: &mut AuthMonitor| {
+        let mut calls = Vec::new();
+        auth_monitor.update(|actions, _| {
+            calls.push(actions.to_vec());
+        });
+        return calls;
+    };
+
+    let mut auth_monitor = AuthMonitor::new(params()).unwrap();
+    let first_calls = update(&mut auth_monitor);
+    let second_calls = update(&mut auth_monitor);
+    let mut restarted_auth_monitor = AuthMonitor::new(params()).unwrap();
+    let restarted_calls = update(&mut restarted_auth_monitor);
+    std::fs::remove_dir_all(&state_directory).unwrap();
+
+    assert_eq!(first_calls, [vec![Action::Wall]]);
+    assert!(second_calls.is_empty());
+    assert!(restarted_calls.is_empty());
+}
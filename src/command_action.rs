@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::action_context::ActionContext;
+use crate::command_options::CommandOptions;
+use crate::command_runner::CommandRunner;
+use crate::logger::LogEntry;
+
+/// PATH of the command, the environment of the daemon is not passed on.
+const COMMAND_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+const MAX_LOGGED_OUTPUT_LENGTH: usize = 4096;
+
+/// Runs a user supplied program with the event details in its environment:
+/// AUTH_MONITOR_HOST, AUTH_MONITOR_USER, AUTH_MONITOR_SERVICE,
+/// AUTH_MONITOR_COUNT, AUTH_MONITOR_MAX_FAILED_ATTEMPTS and
+/// AUTH_MONITOR_LINES, with one matched log line per line.
+pub struct CommandAction {}
+
+impl CommandAction {
+    pub fn execute(
+        options: &CommandOptions,
+        context: &ActionContext,
+    ) -> Result<(), Box<dyn Error>> {
+        let (program, arguments) = match options.exec.split_first() {
+            Some(exec) => exec,
+            None => Err("Command not specified")?,
+        };
+        let mut command = Command::new(program);
+        command
+            .args(arguments)
+            .current_dir(&options.working_directory)
+            .env_clear()
+            .env("PATH", COMMAND_PATH)
+            .envs(Self::environment(context));
+        let timeout = Duration::from_secs(options.timeout_seconds as u64);
+        let output = CommandRunner::run(&mut command, timeout)?;
+        Self::log_output("stdout", &output.stdout);
+        Self::log_output("stderr", &output.stderr);
+        if !output.status.success() {
+            Err(format!("{} failed with {}", program, output.status))?;
+        }
+        return Ok(());
+    }
+
+    pub fn environment(context: &ActionContext) -> Vec<(&'static str, String)> {
+        return vec![
+            ("AUTH_MONITOR_HOST", context.host.clone()),
+            (
+                "AUTH_MONITOR_USER",
+                context.user.clone().unwrap_or_default(),
+            ),
+            (
+                "AUTH_MONITOR_SERVICE",
+                context.service.clone().unwrap_or_default(),
+            ),
+            ("AUTH_MONITOR_COUNT", context.counter.to_string()),
+            (
+                "AUTH_MONITOR_MAX_FAILED_ATTEMPTS",
+                context.max_failed_attempts.to_string(),
+            ),
+            ("AUTH_MONITOR_LINES", context.lines().join("\n")),
+        ];
+    }
+
+    fn log_output(stream: &str, data: &[u8]) {
+        let output = String::from_utf8_lossy(data);
+        let output = output.trim_end();
+        if output.is_empty() {
+            return;
+        }
+        let mut end = output.len().min(MAX_LOGGED_OUTPUT_LENGTH);
+        while !output.is_char_boundary(end) {
+            end -= 1;
+        }
+        let message = format!("Command {}: {}", stream, &output[..end]);
+        LogEntry::info("command_output", &message).log();
+    }
+}
+
+#[cfg(test)]
+#[path = "./command_action_tests.rs"]
+mod tests;
@@ -0,0 +1,112 @@
+use std::env::temp_dir;
+use std::fs::{read_to_string, remove_file};
+use std::time::SystemTime;
+
+use crate::action::Action;
+use crate::action_context::{ActionContext, FailedAttempt};
+use crate::assert_error;
+use crate::command_action::CommandAction;
+use crate::command_options::CommandOptions;
+
+fn context() -> ActionContext {
+    return ActionContext {
+        host: String::from("workstation"),
+        user: Some(String::from("john")),
+        service: None,
+        counter: 2,
+        max_failed_attempts: 5,
+        limit_actions: vec![Action::PowerOff],
+        attempts: vec![
+            FailedAttempt {
+                time: SystemTime::now(),
+                counter: 1,
+                line: String::from("sshd[1]: Failed password for john"),
+            },
+            FailedAttempt {
+                time: SystemTime::now(),
+                counter: 2,
+                line: String::from("sshd[2]: Failed password for john"),
+            },
+        ],
+    };
+}
+
+fn options(exec: &[&str]) -> CommandOptions {
+    return CommandOptions {
+        exec: exec
+            .iter()
+            .map(|argument| {
+                return argument.to_string();
+            })
+            .collect(),
+        working_directory: String::from("/"),
+        timeout_seconds: 5,
+    };
+}
+
+#[test]
+fn when_command_is_executed_then_event_is_passed_in_environment() {
+    let output_path = temp_dir().join(format!("auth-monitor-command-{}", std::process::id()));
+    let script = format!(
+        "printf '%s|%s|%s|%s|%s|%s|%s|%s' \"$AUTH_MONITOR_HOST\" \"$AUTH_MONITOR_USER\" \"$AUTH_MONITOR_SERVICE\" \"$AUTH_MONITOR_COUNT\" \"$AUTH_MONITOR_MAX_FAILED_ATTEMPTS\" \"$AUTH_MONITOR_LINES\" \"$PWD\" \"${{HOME:-unset}}\" > {}",
+        output_path.to_str().unwrap()
+    );
+    let mut options = options(&["sh", "-c", &script]);
+    options.working_directory = String::from(temp_dir().to_str().unwrap());
+
+    let result = CommandAction::execute(&options, &context());
+
+    let output = read_to_string(&output_path).unwrap_or_default();
+    let _ = remove_file(&output_path);
+    assert!(result.is_ok(), "{:?}", result.err());
+    assert_eq!(
+        output,
+        format!(
+            "workstation|john||2|5|sshd[1]: Failed password for john\nsshd[2]: Failed password for john|{}|unset",
+            temp_dir().to_str().unwrap().trim_end_matches('/')
+        )
+    );
+}
+
+#[test]
+fn when_arguments_contain_shell_syntax_then_they_are_passed_literally() {
+    let result = CommandAction::execute(
+        &options(&["test", "$AUTH_MONITOR_USER;", "=", "$AUTH_MONITOR_USER;"]),
+        &context(),
+    );
+
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn when_command_fails_then_return_error_with_status() {
+    assert_error!(
+        CommandAction::execute(
+            &options(&["sh", "-c", "echo failing >&2; exit 4"]),
+            &context()
+        ),
+        "sh failed with exit status: 4"
+    );
+}
+
+#[test]
+fn when_command_does_not_finish_in_time_then_return_error() {
+    let mut options = options(&["sleep", "30"]);
+    options.timeout_seconds = 1;
+
+    assert_error!(
+        CommandAction::execute(&options, &context()),
+        "sleep timed out after 1 seconds"
+    );
+}
+
+#[test]
+fn when_working_directory_does_not_exist_then_return_error() {
+    let mut options = options(&["true"]);
+    options.working_directory = String::from("/nonexistent/directory");
+
+    assert_error!(
+        CommandAction::execute(&options, &context()),
+        "Unable to run true: No such file or directory (os error 2)"
+    );
+}
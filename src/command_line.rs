@@ -0,0 +1,538 @@
+use std::error::Error;
+use std::path::Path;
+
+use crate::auth_monitor_params::{
+    CANCEL_USERS_OPTION, CLOCK_SKEW_TOLERANCE_SECONDS_OPTION, CONFIG_OPTION, CONTROL_SOCKET_OPTION,
+    DRY_RUN_OPTION, GRACE_PERIOD_SECONDS_OPTION, IGNORE_INHIBITORS_OPTION, LOG_FORMAT_OPTION,
+    LOG_LEVEL_OPTION, MAX_FAILED_ATTEMPTS_OPTION, METRICS_ADDRESS_OPTION, METRICS_TEXTFILE_OPTION,
+    RESET_AFTER_SECONDS_OPTION, STATE_DIRECTORY_OPTION, WARNING_FAILED_ATTEMPTS_OPTION,
+    WEBHOOK_QUEUE_SIZE_OPTION, WEBHOOK_RETRIES_OPTION, WEBHOOK_TIMEOUT_SECONDS_OPTION,
+    WEBHOOK_URL_OPTION,
+};
+
+pub const PROGRAM_NAME: &str = env!("CARGO_PKG_NAME");
+pub const PROGRAM_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const RUN_COMMAND: &str = "run";
+pub const REPLAY_COMMAND: &str = "replay";
+pub const CHECK_CONFIG_COMMAND: &str = "check-config";
+pub const STATUS_COMMAND: &str = "status";
+pub const RESET_COMMAND: &str = "reset";
+pub const DISARM_COMMAND: &str = "disarm";
+pub const ARM_COMMAND: &str = "arm";
+
+pub const FOR_OPTION: &str = "for";
+pub const TEST_PATTERN_COMMAND: &str = "test-pattern";
+pub const RECORD_TERMINATION_COMMAND: &str = "record-termination";
+
+const HELP_OPTION: &str = "help";
+const VERSION_OPTION: &str = "version";
+
+const LONG_OPTION_PREFIX: &str = "--";
+const SHORT_OPTION_PREFIX: &str = "-";
+const OPTION_VALUE_SEPARATOR: char = '=';
+
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+pub struct OptionSpec {
+    pub name: &'static str,
+    pub short_name: Option<char>,
+    pub value_name: Option<&'static str>,
+    pub description: &'static str,
+}
+
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub arguments: &'static str,
+    pub description: &'static str,
+    pub options: &'static [OptionSpec],
+}
+
+const HELP_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: HELP_OPTION,
+    short_name: Some('h'),
+    value_name: None,
+    description: "Print help",
+};
+
+const VERSION_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: VERSION_OPTION,
+    short_name: Some('V'),
+    value_name: None,
+    description: "Print version",
+};
+
+const CONFIG_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: CONFIG_OPTION,
+    short_name: Some('c'),
+    value_name: Some("FILE"),
+    description: "Load settings from a TOML configuration file",
+};
+
+const MAX_FAILED_ATTEMPTS_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: MAX_FAILED_ATTEMPTS_OPTION,
+    short_name: Some('m'),
+    value_name: Some("COUNT"),
+    description: "Failed attempts before the actions are executed [default: 5]",
+};
+
+const WARNING_FAILED_ATTEMPTS_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: WARNING_FAILED_ATTEMPTS_OPTION,
+    short_name: None,
+    value_name: Some("COUNT"),
+    description: "Failed attempts before the warning actions are executed [default: 0, disabled]",
+};
+
+const RESET_AFTER_SECONDS_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: RESET_AFTER_SECONDS_OPTION,
+    short_name: Some('r'),
+    value_name: Some("SECONDS"),
+    description: "Reset the failed attempts counter after this time [default: 1800]",
+};
+
+const CLOCK_SKEW_TOLERANCE_SECONDS_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: CLOCK_SKEW_TOLERANCE_SECONDS_OPTION,
+    short_name: Some('s'),
+    value_name: Some("SECONDS"),
+    description: "Allowed difference between log timestamps and the clock [default: 60]",
+};
+
+const GRACE_PERIOD_SECONDS_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: GRACE_PERIOD_SECONDS_OPTION,
+    short_name: Some('g'),
+    value_name: Some("SECONDS"),
+    description: "Delay before executing the actions [default: 0]",
+};
+
+const DRY_RUN_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: DRY_RUN_OPTION,
+    short_name: Some('n'),
+    value_name: None,
+    description: "Only log the actions that would be executed",
+};
+
+const IGNORE_INHIBITORS_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: IGNORE_INHIBITORS_OPTION,
+    short_name: Some('i'),
+    value_name: None,
+    description: "Power off even when logind inhibitor locks are held, logging their holders",
+};
+
+const CANCEL_USERS_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: CANCEL_USERS_OPTION,
+    short_name: Some('u'),
+    value_name: Some("USERS"),
+    description: "Comma separated users whose login cancels pending actions",
+};
+
+const CONTROL_SOCKET_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: CONTROL_SOCKET_OPTION,
+    short_name: None,
+    value_name: Some("PATH"),
+    description: "Path of the control socket [default: /run/auth-monitor/control.sock]",
+};
+
+const STATE_DIRECTORY_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: STATE_DIRECTORY_OPTION,
+    short_name: None,
+    value_name: Some("DIRECTORY"),
+    description: "Directory for state kept across restarts [default: /var/lib/auth-monitor]",
+};
+
+const LOG_LEVEL_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: LOG_LEVEL_OPTION,
+    short_name: Some('l'),
+    value_name: Some("LEVEL"),
+    description: "Log verbosity: error, warning, info or debug [default: info]",
+};
+
+const LOG_FORMAT_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: LOG_FORMAT_OPTION,
+    short_name: None,
+    value_name: Some("FORMAT"),
+    description: "Log output: text or json [default: text]",
+};
+
+const METRICS_TEXTFILE_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: METRICS_TEXTFILE_OPTION,
+    short_name: None,
+    value_name: Some("PATH"),
+    description: "Write Prometheus metrics to a node_exporter textfile",
+};
+
+const METRICS_ADDRESS_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: METRICS_ADDRESS_OPTION,
+    short_name: None,
+    value_name: Some("ADDRESS"),
+    description:
+        "Serve Prometheus metrics on http://ADDRESS/metrics, e.g. 127.0.0.1:9157 (loopback only)",
+};
+
+const WEBHOOK_URL_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: WEBHOOK_URL_OPTION,
+    short_name: None,
+    value_name: Some("URL"),
+    description: "http or https URL the webhook action posts to",
+};
+
+const WEBHOOK_TIMEOUT_SECONDS_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: WEBHOOK_TIMEOUT_SECONDS_OPTION,
+    short_name: None,
+    value_name: Some("SECONDS"),
+    description: "Timeout of a single webhook request [default: 5]",
+};
+
+const WEBHOOK_RETRIES_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: WEBHOOK_RETRIES_OPTION,
+    short_name: None,
+    value_name: Some("COUNT"),
+    description: "Retries of a failed webhook request [default: 3]",
+};
+
+const WEBHOOK_QUEUE_SIZE_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: WEBHOOK_QUEUE_SIZE_OPTION,
+    short_name: None,
+    value_name: Some("COUNT"),
+    description:
+        "Webhook notifications waiting to be sent before new ones are dropped [default: 16]",
+};
+
+const FOR_OPTION_SPEC: OptionSpec = OptionSpec {
+    name: FOR_OPTION,
+    short_name: Some('f'),
+    value_name: Some("DURATION"),
+    description: "How long to stay disarmed, for example 90s, 30m, 2h or 1h30m, at most 365d",
+};
+
+const MONITOR_OPTIONS: &[OptionSpec] = &[
+    HELP_OPTION_SPEC,
+    CONFIG_OPTION_SPEC,
+    MAX_FAILED_ATTEMPTS_OPTION_SPEC,
+    WARNING_FAILED_ATTEMPTS_OPTION_SPEC,
+    RESET_AFTER_SECONDS_OPTION_SPEC,
+    CLOCK_SKEW_TOLERANCE_SECONDS_OPTION_SPEC,
+    GRACE_PERIOD_SECONDS_OPTION_SPEC,
+    DRY_RUN_OPTION_SPEC,
+    IGNORE_INHIBITORS_OPTION_SPEC,
+    CANCEL_USERS_OPTION_SPEC,
+    CONTROL_SOCKET_OPTION_SPEC,
+    STATE_DIRECTORY_OPTION_SPEC,
+    LOG_LEVEL_OPTION_SPEC,
+    LOG_FORMAT_OPTION_SPEC,
+    METRICS_TEXTFILE_OPTION_SPEC,
+    METRICS_ADDRESS_OPTION_SPEC,
+    WEBHOOK_URL_OPTION_SPEC,
+    WEBHOOK_TIMEOUT_SECONDS_OPTION_SPEC,
+    WEBHOOK_RETRIES_OPTION_SPEC,
+    WEBHOOK_QUEUE_SIZE_OPTION_SPEC,
+];
+
+const REPLAY_OPTIONS: &[OptionSpec] = &[
+    HELP_OPTION_SPEC,
+    CONFIG_OPTION_SPEC,
+    MAX_FAILED_ATTEMPTS_OPTION_SPEC,
+    RESET_AFTER_SECONDS_OPTION_SPEC,
+    CLOCK_SKEW_TOLERANCE_SECONDS_OPTION_SPEC,
+];
+
+const CONTROL_OPTIONS: &[OptionSpec] = &[HELP_OPTION_SPEC, CONTROL_SOCKET_OPTION_SPEC];
+
+const DISARM_OPTIONS: &[OptionSpec] = &[
+    HELP_OPTION_SPEC,
+    CONTROL_SOCKET_OPTION_SPEC,
+    FOR_OPTION_SPEC,
+];
+
+const TEST_PATTERN_OPTIONS: &[OptionSpec] = &[HELP_OPTION_SPEC, CONFIG_OPTION_SPEC];
+
+const GLOBAL_OPTIONS: &[OptionSpec] = &[HELP_OPTION_SPEC, VERSION_OPTION_SPEC];
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: RUN_COMMAND,
+        arguments: "[FILE]",
+        description: "Monitor the log file and execute actions (default)",
+        options: MONITOR_OPTIONS,
+    },
+    CommandSpec {
+        name: REPLAY_COMMAND,
+        arguments: "FILE...",
+        description: "Replay log files and report when actions would have been executed",
+        options: REPLAY_OPTIONS,
+    },
+    CommandSpec {
+        name: CHECK_CONFIG_COMMAND,
+        arguments: "[FILE]",
+        description: "Validate the configuration, the log file and the actions",
+        options: MONITOR_OPTIONS,
+    },
+    CommandSpec {
+        name: STATUS_COMMAND,
+        arguments: "",
+        description: "Show the state of the running daemon",
+        options: CONTROL_OPTIONS,
+    },
+    CommandSpec {
+        name: RESET_COMMAND,
+        arguments: "",
+        description: "Reset the failed attempts counter of the running daemon",
+        options: CONTROL_OPTIONS,
+    },
+    CommandSpec {
+        name: DISARM_COMMAND,
+        arguments: "[DURATION]",
+        description: "Suppress the actions of the running daemon for the given time",
+        options: DISARM_OPTIONS,
+    },
+    CommandSpec {
+        name: ARM_COMMAND,
+        arguments: "",
+        description: "Enable the actions of the running daemon again",
+        options: CONTROL_OPTIONS,
+    },
+    CommandSpec {
+        name: TEST_PATTERN_COMMAND,
+        arguments: "[LINE...]",
+        description: "Show how log lines are classified, read from stdin if none given",
+        options: TEST_PATTERN_OPTIONS,
+    },
+    CommandSpec {
+        name: RECORD_TERMINATION_COMMAND,
+        arguments: "[FILE]",
+        description: "Record an unexpected termination of the daemon, run by ExecStopPost",
+        options: MONITOR_OPTIONS,
+    },
+];
+
+#[derive(PartialEq, Debug)]
+pub enum CommandLine {
+    Help(String),
+    Version,
+    Command(&'static str, Vec<String>),
+}
+
+impl CommandLine {
+    pub fn parse(arguments: &[String]) -> Result<CommandLine, Box<dyn Error>> {
+        let (command, command_arguments) = match arguments.first() {
+            Some(argument) if argument.starts_with(SHORT_OPTION_PREFIX) => {
+                match Self::find_option(GLOBAL_OPTIONS, argument) {
+                    Some(option) if option.name == HELP_OPTION => {
+                        return Ok(CommandLine::Help(Self::usage()));
+                    }
+                    Some(option) if option.name == VERSION_OPTION => {
+                        return Ok(CommandLine::Version);
+                    }
+                    _ => (Self::find_command(RUN_COMMAND), arguments),
+                }
+            }
+            Some(argument) => match COMMANDS.iter().find(|command| {
+                return command.name == argument;
+            }) {
+                Some(command) => (command, &arguments[1..]),
+                None => {
+                    Self::check_not_misspelled_command(argument)?;
+                    (Self::find_command(RUN_COMMAND), arguments)
+                }
+            },
+            None => (Self::find_command(RUN_COMMAND), arguments),
+        };
+        return Self::parse_command_arguments(command, command_arguments);
+    }
+
+    fn parse_command_arguments(
+        command: &'static CommandSpec,
+        arguments: &[String],
+    ) -> Result<CommandLine, Box<dyn Error>> {
+        let mut normalized_arguments = Vec::new();
+        let mut arguments_iterator = arguments.iter();
+        while let Some(argument) = arguments_iterator.next() {
+            if !argument.starts_with(SHORT_OPTION_PREFIX) || argument == SHORT_OPTION_PREFIX {
+                normalized_arguments.push(argument.clone());
+                continue;
+            }
+            let option = match Self::find_option(command.options, argument) {
+                Some(option) => option,
+                None => Err(Self::unknown_option_error(command.options, argument))?,
+            };
+            if option.name == HELP_OPTION {
+                return Ok(CommandLine::Help(Self::command_usage(command)));
+            }
+            let long_name = format!("{}{}", LONG_OPTION_PREFIX, option.name);
+            if argument.contains(OPTION_VALUE_SEPARATOR) || option.value_name.is_none() {
+                let (_, value) = Self::split_option(argument);
+                normalized_arguments.push(match value {
+                    Some(value) => format!("{}{}{}", long_name, OPTION_VALUE_SEPARATOR, value),
+                    None => long_name,
+                });
+                continue;
+            }
+            let value = match arguments_iterator.next() {
+                Some(value) => value,
+                None => Err(format!("Missing value for option {}", long_name))?,
+            };
+            normalized_arguments.push(format!("{}{}{}", long_name, OPTION_VALUE_SEPARATOR, value));
+        }
+        return Ok(CommandLine::Command(command.name, normalized_arguments));
+    }
+
+    fn find_command(name: &str) -> &'static CommandSpec {
+        return COMMANDS
+            .iter()
+            .find(|command| {
+                return command.name == name;
+            })
+            .expect("Command is not defined");
+    }
+
+    fn find_option(options: &'static [OptionSpec], argument: &str) -> Option<&'static OptionSpec> {
+        let (name, _) = Self::split_option(argument);
+        return options.iter().find(|option| {
+            return match name.strip_prefix(LONG_OPTION_PREFIX) {
+                Some(long_name) => option.name == long_name,
+                None => {
+                    let short_name = &name[SHORT_OPTION_PREFIX.len()..];
+                    let mut characters = short_name.chars();
+                    match (characters.next(), characters.next()) {
+                        (Some(character), None) => option.short_name == Some(character),
+                        _ => false,
+                    }
+                }
+            };
+        });
+    }
+
+    fn split_option(argument: &str) -> (&str, Option<&str>) {
+        return match argument.split_once(OPTION_VALUE_SEPARATOR) {
+            Some((name, value)) => (name, Some(value)),
+            None => (argument, None),
+        };
+    }
+
+    fn check_not_misspelled_command(argument: &str) -> Result<(), Box<dyn Error>> {
+        if Path::new(argument).exists() {
+            return Ok(());
+        }
+        let names = COMMANDS.iter().map(|command| {
+            return command.name;
+        });
+        if let Some(name) = Self::find_closest(names, argument) {
+            Err(format!(
+                "Unknown command {}, did you mean {}?",
+                argument, name
+            ))?;
+        }
+        return Ok(());
+    }
+
+    fn unknown_option_error(options: &[OptionSpec], argument: &str) -> String {
+        let (name, _) = Self::split_option(argument);
+        let names = options.iter().map(|option| {
+            return option.name;
+        });
+        let trimmed_name = name.trim_start_matches(SHORT_OPTION_PREFIX);
+        return match Self::find_closest(names, trimmed_name) {
+            Some(closest_name) => format!(
+                "Unknown option {}, did you mean {}{}?",
+                name, LONG_OPTION_PREFIX, closest_name
+            ),
+            None => format!("Unknown option {}", name),
+        };
+    }
+
+    fn find_closest<'a>(names: impl Iterator<Item = &'a str>, text: &str) -> Option<&'a str> {
+        return names
+            .map(|name| {
+                return (Self::edit_distance(name, text), name);
+            })
+            .filter(|(distance, name)| {
+                return *distance <= MAX_SUGGESTION_DISTANCE && *distance < name.len();
+            })
+            .min_by_key(|(distance, _)| {
+                return *distance;
+            })
+            .map(|(_, name)| {
+                return name;
+            });
+    }
+
+    fn edit_distance(first: &str, second: &str) -> usize {
+        let second_characters: Vec<char> = second.chars().collect();
+        let mut previous_row: Vec<usize> = (0..=second_characters.len()).collect();
+        for (i, first_character) in first.chars().enumerate() {
+            let mut current_row = vec![i + 1];
+            for (j, second_character) in second_characters.iter().enumerate() {
+                let substitution_cost = usize::from(first_character != *second_character);
+                let distance = (previous_row[j] + substitution_cost)
+                    .min(previous_row[j + 1] + 1)
+                    .min(current_row[j] + 1);
+                current_row.push(distance);
+            }
+            previous_row = current_row;
+        }
+        return previous_row[second_characters.len()];
+    }
+
+    pub fn usage() -> String {
+        let mut usage = format!(
+            "Usage: {} [COMMAND] [OPTIONS]\n\n\
+             Powers off the computer after too many failed login attempts.\n\nCommands:\n",
+            PROGRAM_NAME
+        );
+        let width = COMMANDS
+            .iter()
+            .map(|command| {
+                return command.name.len();
+            })
+            .max()
+            .unwrap_or(0);
+        for command in COMMANDS {
+            usage += &format!("  {:width$}  {}\n", command.name, command.description);
+        }
+        usage += "\nOptions:\n";
+        usage += &Self::options_usage(GLOBAL_OPTIONS);
+        usage += &format!(
+            "\nRun \"{} COMMAND --help\" for the options of a command.",
+            PROGRAM_NAME
+        );
+        return usage;
+    }
+
+    pub fn command_usage(command: &CommandSpec) -> String {
+        let mut usage = format!("Usage: {} {} [OPTIONS]", PROGRAM_NAME, command.name);
+        if !command.arguments.is_empty() {
+            usage += &format!(" {}", command.arguments);
+        }
+        usage += &format!("\n\n{}.\n\nOptions:\n", command.description);
+        usage += &Self::options_usage(command.options);
+        return usage.trim_end().to_string();
+    }
+
+    fn options_usage(options: &[OptionSpec]) -> String {
+        let labels: Vec<String> = options
+            .iter()
+            .map(|option| {
+                let short_name = match option.short_name {
+                    Some(short_name) => format!("{}{}, ", SHORT_OPTION_PREFIX, short_name),
+                    None => String::from("    "),
+                };
+                return match option.value_name {
+                    Some(value_name) => format!(
+                        "{}{}{} <{}>",
+                        short_name, LONG_OPTION_PREFIX, option.name, value_name
+                    ),
+                    None => format!("{}{}{}", short_name, LONG_OPTION_PREFIX, option.name),
+                };
+            })
+            .collect();
+        let width = labels.iter().map(String::len).max().unwrap_or(0);
+        let mut usage = String::new();
+        for (label, option) in labels.iter().zip(options) {
+            usage += &format!("  {:width$}  {}\n", label, option.description);
+        }
+        return usage;
+    }
+}
+
+#[cfg(test)]
+#[path = "./command_line_tests.rs"]
+mod tests;
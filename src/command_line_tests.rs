@@ -0,0 +1,179 @@
+use crate::assert_error;
+use crate::command_line::{
+    CommandLine, COMMANDS, REPLAY_COMMAND, RUN_COMMAND, STATUS_COMMAND, TEST_PATTERN_COMMAND,
+};
+
+fn parse(arguments: &[&str]) -> Result<CommandLine, Box<dyn std::error::Error>> {
+    let arguments: Vec<String> = arguments
+        .iter()
+        .map(|argument| {
+            return argument.to_string();
+        })
+        .collect();
+    return CommandLine::parse(&arguments);
+}
+
+fn command(name: &'static str, arguments: &[&str]) -> CommandLine {
+    return CommandLine::Command(
+        name,
+        arguments
+            .iter()
+            .map(|argument| {
+                return argument.to_string();
+            })
+            .collect(),
+    );
+}
+
+#[test]
+fn when_no_command_is_given_then_run_command_is_used() {
+    assert_eq!(parse(&[]).unwrap(), command(RUN_COMMAND, &[]));
+    assert_eq!(
+        parse(&["/var/log/auth.log", "--dry-run"]).unwrap(),
+        command(RUN_COMMAND, &["/var/log/auth.log", "--dry-run"])
+    );
+    assert_eq!(
+        parse(&["--max-failed-attempts=3", "/var/log/auth.log"]).unwrap(),
+        command(
+            RUN_COMMAND,
+            &["--max-failed-attempts=3", "/var/log/auth.log"]
+        )
+    );
+}
+
+#[test]
+fn when_command_is_given_then_command_arguments_follow_it() {
+    assert_eq!(
+        parse(&["replay", "a.log", "b.log.gz"]).unwrap(),
+        command(REPLAY_COMMAND, &["a.log", "b.log.gz"])
+    );
+    assert_eq!(parse(&["status"]).unwrap(), command(STATUS_COMMAND, &[]));
+}
+
+#[test]
+fn when_option_value_is_separated_by_space_then_it_is_joined_with_option() {
+    assert_eq!(
+        parse(&[
+            "run",
+            "--max-failed-attempts",
+            "5",
+            "--cancel-users",
+            "john,alice",
+            "auth.log"
+        ])
+        .unwrap(),
+        command(
+            RUN_COMMAND,
+            &[
+                "--max-failed-attempts=5",
+                "--cancel-users=john,alice",
+                "auth.log"
+            ]
+        )
+    );
+    assert_eq!(
+        parse(&["--clock-skew-tolerance-seconds", "-1"]).unwrap(),
+        command(RUN_COMMAND, &["--clock-skew-tolerance-seconds=-1"])
+    );
+}
+
+#[test]
+fn when_short_options_are_used_then_they_are_expanded_to_long_options() {
+    assert_eq!(
+        parse(&[
+            "-c",
+            "/etc/auth-monitor.toml",
+            "-m",
+            "3",
+            "-n",
+            "-i",
+            "-g=30",
+            "auth.log"
+        ])
+        .unwrap(),
+        command(
+            RUN_COMMAND,
+            &[
+                "--config=/etc/auth-monitor.toml",
+                "--max-failed-attempts=3",
+                "--dry-run",
+                "--ignore-inhibitors",
+                "--grace-period-seconds=30",
+                "auth.log"
+            ]
+        )
+    );
+    assert_eq!(
+        parse(&["test-pattern", "-c", "auth-monitor.toml", "some line"]).unwrap(),
+        command(
+            TEST_PATTERN_COMMAND,
+            &["--config=auth-monitor.toml", "some line"]
+        )
+    );
+}
+
+#[test]
+fn when_option_value_is_missing_then_return_error() {
+    assert_error!(
+        parse(&["auth.log", "-m"]),
+        "Missing value for option --max-failed-attempts"
+    );
+}
+
+#[test]
+fn when_option_is_unknown_then_return_error_with_closest_option() {
+    assert_error!(
+        parse(&["--max-faild-attempts=3"]),
+        "Unknown option --max-faild-attempts, did you mean --max-failed-attempts?"
+    );
+    assert_error!(
+        parse(&["replay", "--dryrun", "auth.log"]),
+        "Unknown option --dryrun"
+    );
+    assert_error!(
+        parse(&["replay", "--grace-period-seconds=30", "auth.log"]),
+        "Unknown option --grace-period-seconds"
+    );
+    assert_error!(parse(&["-x"]), "Unknown option -x");
+    assert_error!(
+        parse(&["status", "--config=auth-monitor.toml"]),
+        "Unknown option --config"
+    );
+}
+
+#[test]
+fn when_command_is_misspelled_then_return_error_with_closest_command() {
+    assert_error!(
+        parse(&["replya", "auth.log"]),
+        "Unknown command replya, did you mean replay?"
+    );
+}
+
+#[test]
+fn when_help_is_requested_then_return_usage() {
+    let usage = CommandLine::usage();
+    assert_eq!(
+        parse(&["--help"]).unwrap(),
+        CommandLine::Help(usage.clone())
+    );
+    assert_eq!(parse(&["-h"]).unwrap(), CommandLine::Help(usage.clone()));
+    for command in COMMANDS {
+        assert!(usage.contains(command.name));
+    }
+}
+
+#[test]
+fn when_help_is_requested_for_command_then_return_command_usage() {
+    let CommandLine::Help(usage) = parse(&["replay", "a.log", "--help"]).unwrap() else {
+        panic!("Help was expected");
+    };
+    assert!(usage.starts_with("Usage: auth-monitor replay [OPTIONS] FILE..."));
+    assert!(usage.contains("-m, --max-failed-attempts <COUNT>"));
+    assert!(!usage.contains("--dry-run"));
+}
+
+#[test]
+fn when_version_is_requested_then_return_version() {
+    assert_eq!(parse(&["--version"]).unwrap(), CommandLine::Version);
+    assert_eq!(parse(&["-V"]).unwrap(), CommandLine::Version);
+}
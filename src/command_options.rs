@@ -0,0 +1,31 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct CommandOptions {
+    /// Program and its arguments, executed without a shell.
+    pub exec: Vec<String>,
+    pub working_directory: String,
+    pub timeout_seconds: i32,
+}
+
+impl Default for CommandOptions {
+    fn default() -> Self {
+        return CommandOptions {
+            exec: Vec::new(),
+            working_directory: String::from("/"),
+            timeout_seconds: 30,
+        };
+    }
+}
+
+impl Display for CommandOptions {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            formatter,
+            "command-exec={}, command-working-directory={}, command-timeout-seconds={}",
+            self.exec.join(" "),
+            self.working_directory,
+            self.timeout_seconds
+        );
+    }
+}
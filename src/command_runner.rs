@@ -0,0 +1,95 @@
+use std::error::Error;
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Runs commands without a shell and kills them when they do not finish in
+/// time, so that a hanging command cannot block the actions following it.
+pub struct CommandRunner {}
+
+impl CommandRunner {
+    /// Runs the command with its output captured. On timeout, the whole
+    /// process group is killed, including processes the command started.
+    pub fn run(command: &mut Command, timeout: Duration) -> Result<Output, Box<dyn Error>> {
+        let program = command.get_program().to_string_lossy().into_owned();
+        let mut child = match command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(error) => Err(format!("Unable to run {}: {}", program, error))?,
+        };
+        let stdout = Self::read_in_background(child.stdout.take());
+        let stderr = Self::read_in_background(child.stderr.take());
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                unsafe {
+                    libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+                }
+                let _ = child.wait();
+                Err(format!(
+                    "{} timed out after {} seconds",
+                    program,
+                    timeout.as_secs()
+                ))?;
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+        // A process left running in the background may keep the output open.
+        let remaining = deadline.saturating_duration_since(Instant::now()) + POLL_INTERVAL;
+        return Ok(Output {
+            status,
+            stdout: stdout.recv_timeout(remaining).unwrap_or_default(),
+            stderr: stderr.recv_timeout(remaining).unwrap_or_default(),
+        });
+    }
+
+    /// Runs a function in a separate thread and gives up waiting for it after
+    /// the timeout, for steps such as system calls that may block.
+    pub fn run_function(
+        name: &str,
+        timeout: Duration,
+        function: impl FnOnce() -> Result<(), String> + Send + 'static,
+    ) -> Result<(), Box<dyn Error>> {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(function());
+        });
+        return match receiver.recv_timeout(timeout) {
+            Ok(result) => Ok(result?),
+            Err(_) => Err(format!(
+                "{} timed out after {} seconds",
+                name,
+                timeout.as_secs()
+            ))?,
+        };
+    }
+
+    fn read_in_background(source: Option<impl Read + Send + 'static>) -> mpsc::Receiver<Vec<u8>> {
+        let (sender, receiver) = mpsc::channel();
+        if let Some(mut source) = source {
+            thread::spawn(move || {
+                let mut data = Vec::new();
+                let _ = source.read_to_end(&mut data);
+                let _ = sender.send(data);
+            });
+        }
+        return receiver;
+    }
+}
+
+#[cfg(test)]
+#[path = "./command_runner_tests.rs"]
+mod tests;
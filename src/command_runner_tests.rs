@@ -0,0 +1,61 @@
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::assert_error;
+use crate::command_runner::CommandRunner;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[test]
+fn when_command_exits_then_output_and_status_are_returned() {
+    let output = CommandRunner::run(
+        Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]),
+        TIMEOUT,
+    )
+    .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(output.stdout, b"out\n");
+    assert_eq!(output.stderr, b"err\n");
+}
+
+#[test]
+fn when_command_does_not_finish_in_time_then_it_is_killed() {
+    let start = Instant::now();
+
+    let result = CommandRunner::run(
+        Command::new("sh").args(["-c", "sleep 30 & sleep 30"]),
+        Duration::from_secs(1),
+    );
+
+    assert_error!(result, "sh timed out after 1 seconds");
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn when_command_does_not_exist_then_return_error() {
+    assert_error!(
+        CommandRunner::run(&mut Command::new("/nonexistent/command"), TIMEOUT),
+        "Unable to run /nonexistent/command: No such file or directory (os error 2)"
+    );
+}
+
+#[test]
+fn when_function_blocks_then_run_function_gives_up_after_timeout() {
+    let result = CommandRunner::run_function("Step", Duration::from_secs(1), || {
+        thread::sleep(Duration::from_secs(5));
+        return Ok(());
+    });
+
+    assert_error!(result, "Step timed out after 1 seconds");
+}
+
+#[test]
+fn when_function_fails_then_its_error_is_returned() {
+    let result = CommandRunner::run_function("Step", TIMEOUT, || {
+        return Err(String::from("Step failed"));
+    });
+
+    assert_error!(result, "Step failed");
+}
@@ -0,0 +1,110 @@
+use std::error::Error;
+use std::fs::File;
+
+use crate::action::Action;
+use crate::auth_message_parser::AuthMessageParser;
+use crate::auth_monitor_params::AuthMonitorParams;
+use crate::file_path::FilePath;
+use crate::power_options::PowerOptions;
+
+pub struct ConfigCheck {
+    results: Vec<ConfigCheckResult>,
+}
+
+struct ConfigCheckResult {
+    name: String,
+    error: Option<String>,
+}
+
+impl ConfigCheck {
+    pub fn run(arguments: &[String], check_actions: bool) -> ConfigCheck {
+        let mut config_check = ConfigCheck {
+            results: Vec::new(),
+        };
+        let params = match AuthMonitorParams::from_arguments(arguments) {
+            Ok(params) => {
+                config_check.add(&format!("Parameters: {}", params), Ok(()));
+                params
+            }
+            Err(error) => {
+                config_check.add("Parameters", Err(error));
+                return config_check;
+            }
+        };
+        config_check.check_patterns(&params);
+        config_check.check_log_file(&params.filepath);
+        if check_actions {
+            config_check.check_actions(&params.actions, &params.power);
+            config_check.check_actions(&params.warning_actions, &params.power);
+            for response in params.tamper.responses() {
+                config_check.check_actions(response.actions(), &params.power);
+            }
+        }
+        return config_check;
+    }
+
+    fn check_patterns(&mut self, params: &AuthMonitorParams) {
+        let parser = AuthMessageParser::with_rules(
+            &params.patterns,
+            &params.ignore_users,
+            &params.ignore_services,
+        );
+        let name = format!(
+            "Patterns: {} in total, {} configured",
+            parser.pattern_count(),
+            params.patterns.len()
+        );
+        self.add(&name, Ok(()));
+    }
+
+    fn check_log_file(&mut self, filepath: &str) {
+        let name = format!("Log file {} is readable", filepath);
+        let result = match FilePath::from(filepath) {
+            Ok(_) => match File::open(filepath) {
+                Ok(_) => Ok(()),
+                Err(error) => Err(Box::from(error)),
+            },
+            Err(error) => Err(error),
+        };
+        self.add(&name, result);
+    }
+
+    fn check_actions(&mut self, actions: &[Action], options: &PowerOptions) {
+        for action in actions {
+            let name = format!("Action {} is executable", action);
+            self.add(&name, action.check(options));
+        }
+    }
+
+    fn add(&mut self, name: &str, result: Result<(), Box<dyn Error>>) {
+        self.results.push(ConfigCheckResult {
+            name: String::from(name),
+            error: result.err().map(|error| {
+                return error.to_string();
+            }),
+        });
+    }
+
+    pub fn is_successful(&self) -> bool {
+        return self.results.iter().all(|result| {
+            return result.error.is_none();
+        });
+    }
+
+    pub fn print_report(&self) {
+        for result in &self.results {
+            match &result.error {
+                None => println!("[ OK ] {}", result.name),
+                Some(error) => println!("[FAIL] {}: {}", result.name, error),
+            }
+        }
+        match self.is_successful() {
+            true => println!("Configuration is valid"),
+            false => println!("Configuration has problems"),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./config_check_tests.rs"]
+mod tests;
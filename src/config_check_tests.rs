@@ -0,0 +1,35 @@
+use crate::config_check::ConfigCheck;
+use crate::test_utils::test_file::TestFile;
+
+#[test]
+fn when_parameters_and_log_file_are_valid_then_check_is_successful() {
+    let file = TestFile::empty();
+    let arguments = [
+        String::from(file.path()),
+        String::from("--max-failed-attempts=3"),
+    ];
+    let config_check = ConfigCheck::run(&arguments, false);
+    assert!(config_check.is_successful());
+    assert_eq!(config_check.results.len(), 3);
+}
+
+#[test]
+fn when_log_file_does_not_exist_then_check_fails() {
+    let arguments = [String::from("/tmp/auth-monitor-nonexistent.log")];
+    let config_check = ConfigCheck::run(&arguments, false);
+    assert!(!config_check.is_successful());
+    let error = config_check.results[2].error.as_deref();
+    assert_eq!(error, Some("No such file or directory (os error 2)"));
+}
+
+#[test]
+fn when_parameters_are_invalid_then_check_fails_without_further_checks() {
+    let arguments = [String::from("--max-failed-attempts=0")];
+    let config_check = ConfigCheck::run(&arguments, false);
+    assert!(!config_check.is_successful());
+    assert_eq!(config_check.results.len(), 1);
+    assert_eq!(
+        config_check.results[0].error.as_deref(),
+        Some("File path not specified")
+    );
+}
@@ -0,0 +1,40 @@
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+use crate::control_socket::{ControlRequest, ERROR_RESPONSE, OK_RESPONSE};
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct ControlClient {}
+
+impl ControlClient {
+    pub fn send(path: &str, request: &ControlRequest) -> Result<String, Box<dyn Error>> {
+        let mut stream = match UnixStream::connect(path) {
+            Ok(stream) => stream,
+            Err(error) => Err(format!(
+                "Unable to connect to control socket {}: {}",
+                path, error
+            ))?,
+        };
+        stream.set_read_timeout(Some(RESPONSE_TIMEOUT))?;
+        stream.write_all(format!("{}\n", request).as_bytes())?;
+        stream.shutdown(Shutdown::Write)?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        return Self::parse_response(&response);
+    }
+
+    fn parse_response(response: &str) -> Result<String, Box<dyn Error>> {
+        let (status, body) = response.split_once('\n').unwrap_or((response, ""));
+        if status == OK_RESPONSE {
+            return Ok(String::from(body.trim_end()));
+        }
+        return match status.strip_prefix(ERROR_RESPONSE) {
+            Some(error) => Err(error.trim())?,
+            None => Err(format!("Invalid response \"{}\"", status))?,
+        };
+    }
+}
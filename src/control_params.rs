@@ -0,0 +1,64 @@
+use std::env;
+use std::error::Error;
+
+use crate::auth_monitor_params::{
+    AuthMonitorParams, CONTROL_SOCKET_OPTION, DEFAULT_CONTROL_SOCKET,
+};
+use crate::command_line::{DISARM_COMMAND, FOR_OPTION};
+use crate::control_socket::ControlRequest;
+use crate::duration_parser::DurationParser;
+
+const CONTROL_SOCKET_VARIABLE: &str = "AUTH_MONITOR_CONTROL_SOCKET";
+
+pub struct ControlParams {
+    pub control_socket: String,
+    pub request: ControlRequest,
+}
+
+impl ControlParams {
+    pub fn from_arguments(
+        command: &str,
+        arguments: &[String],
+    ) -> Result<ControlParams, Box<dyn Error>> {
+        let mut control_socket = match env::var(CONTROL_SOCKET_VARIABLE) {
+            Ok(path) if !path.is_empty() => path,
+            _ => String::from(DEFAULT_CONTROL_SOCKET),
+        };
+        let mut values = Vec::new();
+        for argument in arguments {
+            if !AuthMonitorParams::is_option(argument) {
+                values.push(argument.as_str());
+                continue;
+            }
+            let (option_name, option_value) = AuthMonitorParams::split_option(argument);
+            let value = match option_value {
+                Some(value) if !value.is_empty() => value,
+                _ => Err(format!("Missing value for option {}", option_name))?,
+            };
+            match option_name.trim_start_matches('-') {
+                CONTROL_SOCKET_OPTION => control_socket = String::from(value),
+                FOR_OPTION => values.push(value),
+                _ => Err(format!("Unknown option {}", argument))?,
+            }
+        }
+        let request = match (command, values.as_slice()) {
+            (DISARM_COMMAND, [duration]) => {
+                ControlRequest::Disarm(DurationParser::parse(duration)?)
+            }
+            (DISARM_COMMAND, []) => Err("Missing disarm duration")?,
+            _ => {
+                let mut words = vec![command];
+                words.extend(values);
+                words.join(" ").parse()?
+            }
+        };
+        return Ok(ControlParams {
+            control_socket,
+            request,
+        });
+    }
+}
+
+#[cfg(test)]
+#[path = "./control_params_tests.rs"]
+mod tests;
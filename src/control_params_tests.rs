@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use crate::assert_error;
+use crate::control_params::ControlParams;
+use crate::control_socket::ControlRequest;
+
+#[test]
+fn when_parsing_control_command_then_return_matching_request() {
+    let params = ControlParams::from_arguments("status", &[]).unwrap();
+    assert_eq!(params.request, ControlRequest::Status);
+
+    let arguments = [
+        String::from("--control-socket=/tmp/auth-monitor.sock"),
+        String::from("600"),
+    ];
+    let params = ControlParams::from_arguments("disarm", &arguments).unwrap();
+    assert_eq!(params.control_socket, "/tmp/auth-monitor.sock");
+    assert_eq!(
+        params.request,
+        ControlRequest::Disarm(Duration::from_secs(600))
+    );
+
+    let params = ControlParams::from_arguments("disarm", &[String::from("--for=2h")]).unwrap();
+    assert_eq!(
+        params.request,
+        ControlRequest::Disarm(Duration::from_secs(7200))
+    );
+}
+
+#[test]
+fn when_parsing_invalid_control_arguments_then_return_error() {
+    assert_error!(
+        ControlParams::from_arguments("disarm", &[String::from("0")]),
+        "\"0\" is not a valid duration"
+    );
+    assert_error!(
+        ControlParams::from_arguments("disarm", &[]),
+        "Missing disarm duration"
+    );
+    assert_error!(
+        ControlParams::from_arguments("reset", &[String::from("now")]),
+        "Unknown request \"reset now\""
+    );
+    assert_error!(
+        ControlParams::from_arguments("status", &[String::from("--control-socket=")]),
+        "Missing value for option --control-socket"
+    );
+}
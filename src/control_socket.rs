@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::mem;
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::duration_parser::MAX_DURATION;
+use crate::logger::LogEntry;
+
+const SOCKET_PERMISSIONS: u32 = 0o600;
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(1);
+const ROOT_UID: u32 = 0;
+
+const STATUS_REQUEST: &str = "status";
+const RESET_REQUEST: &str = "reset";
+const DISARM_REQUEST: &str = "disarm";
+const ARM_REQUEST: &str = "arm";
+
+pub const OK_RESPONSE: &str = "OK";
+pub const ERROR_RESPONSE: &str = "ERROR";
+
+#[derive(PartialEq, Debug)]
+pub enum ControlRequest {
+    Status,
+    Reset,
+    Disarm(Duration),
+    Arm,
+}
+
+impl Display for ControlRequest {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            ControlRequest::Status => write!(formatter, "{}", STATUS_REQUEST),
+            ControlRequest::Reset => write!(formatter, "{}", RESET_REQUEST),
+            ControlRequest::Disarm(duration) => {
+                write!(formatter, "{} {}", DISARM_REQUEST, duration.as_secs())
+            }
+            ControlRequest::Arm => write!(formatter, "{}", ARM_REQUEST),
+        };
+    }
+}
+
+impl FromStr for ControlRequest {
+    type Err = Box<dyn Error>;
+
+    fn from_str(request: &str) -> Result<Self, Self::Err> {
+        let mut words = request.split_whitespace();
+        let control_request = match (words.next(), words.next()) {
+            (Some(STATUS_REQUEST), None) => ControlRequest::Status,
+            (Some(RESET_REQUEST), None) => ControlRequest::Reset,
+            (Some(ARM_REQUEST), None) => ControlRequest::Arm,
+            (Some(DISARM_REQUEST), Some(seconds)) => match seconds.parse::<u64>() {
+                Ok(seconds) if seconds > 0 && seconds <= MAX_DURATION.as_secs() => {
+                    ControlRequest::Disarm(Duration::from_secs(seconds))
+                }
+                _ => Err(format!("\"{}\" is not a valid disarm duration", seconds))?,
+            },
+            _ => Err(format!("Unknown request \"{}\"", request.trim()))?,
+        };
+        if words.next().is_some() {
+            Err(format!("Unknown request \"{}\"", request.trim()))?;
+        }
+        return Ok(control_request);
+    }
+}
+
+pub struct ControlSocket {
+    path: String,
+    listener: UnixListener,
+    owner_uid: u32,
+}
+
+impl ControlSocket {
+    pub fn new(path: &str) -> Result<ControlSocket, Box<dyn Error>> {
+        Self::remove_stale_socket(path)?;
+        let listener = UnixListener::bind(path)?;
+        listener.set_nonblocking(true)?;
+        fs::set_permissions(path, fs::Permissions::from_mode(SOCKET_PERMISSIONS))?;
+        return Ok(ControlSocket {
+            path: String::from(path),
+            listener,
+            owner_uid: unsafe { libc::geteuid() },
+        });
+    }
+
+    fn remove_stale_socket(path: &str) -> Result<(), Box<dyn Error>> {
+        match fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_socket() => fs::remove_file(path)?,
+            Ok(_) => Err(format!("{} exists and is not a socket", path))?,
+            Err(_) => {}
+        }
+        return Ok(());
+    }
+
+    pub fn update(
+        &mut self,
+        mut handle_request: impl FnMut(ControlRequest) -> Result<String, String>,
+    ) {
+        loop {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return,
+                Err(error) => {
+                    let message = format!("Error accepting control connection: {}", error);
+                    LogEntry::error("control_error", &message).log();
+                    return;
+                }
+            };
+            if let Err(error) = self.handle_connection(stream, &mut handle_request) {
+                let message = format!("Error handling control connection: {}", error);
+                LogEntry::error("control_error", &message).log();
+            }
+        }
+    }
+
+    fn handle_connection(
+        &self,
+        mut stream: UnixStream,
+        handle_request: &mut impl FnMut(ControlRequest) -> Result<String, String>,
+    ) -> Result<(), Box<dyn Error>> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+        let peer_uid = Self::get_peer_uid(&stream)?;
+        let response = match Self::is_peer_allowed(peer_uid, self.owner_uid) {
+            true => {
+                let mut line = String::new();
+                BufReader::new(&stream).read_line(&mut line)?;
+                match line.parse::<ControlRequest>() {
+                    Ok(request) => {
+                        let message =
+                            format!("Control request \"{}\" from uid {}", request, peer_uid);
+                        LogEntry::info("control_request", &message).log();
+                        handle_request(request)
+                    }
+                    Err(error) => Err(error.to_string()),
+                }
+            }
+            false => {
+                let message = format!("Control request from uid {} rejected", peer_uid);
+                LogEntry::warning("control_rejected", &message).log();
+                Err(String::from("Permission denied"))
+            }
+        };
+        let response = match response {
+            Ok(body) if body.is_empty() => format!("{}\n", OK_RESPONSE),
+            Ok(body) => format!("{}\n{}\n", OK_RESPONSE, body),
+            Err(error) => format!("{} {}\n", ERROR_RESPONSE, error),
+        };
+        stream.write_all(response.as_bytes())?;
+        return Ok(());
+    }
+
+    fn get_peer_uid(stream: &UnixStream) -> Result<u32, Box<dyn Error>> {
+        let mut credentials = libc::ucred {
+            pid: 0,
+            uid: 0,
+            gid: 0,
+        };
+        let mut length = mem::size_of::<libc::ucred>() as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                stream.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                &mut credentials as *mut libc::ucred as *mut libc::c_void,
+                &mut length,
+            )
+        };
+        if result != 0 {
+            Err(format!(
+                "Unable to get peer credentials: {}",
+                std::io::Error::last_os_error()
+            ))?;
+        }
+        return Ok(credentials.uid);
+    }
+
+    fn is_peer_allowed(peer_uid: u32, owner_uid: u32) -> bool {
+        return peer_uid == ROOT_UID || peer_uid == owner_uid;
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        if let Err(error) = fs::remove_file(&self.path) {
+            let message = format!("Error removing control socket {}: {}", self.path, error);
+            LogEntry::error("control_error", &message).log();
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./control_socket_tests.rs"]
+mod tests;
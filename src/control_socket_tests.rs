@@ -0,0 +1,93 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::assert_error;
+use crate::control_client::ControlClient;
+use crate::control_socket::{ControlRequest, ControlSocket};
+
+fn send_request(path: &str, request: ControlRequest) -> Result<String, String> {
+    let mut control_socket = ControlSocket::new(path).expect("Error creating control socket");
+    let client_path = String::from(path);
+    let client = thread::spawn(move || {
+        return ControlClient::send(&client_path, &request).map_err(|error| {
+            return error.to_string();
+        });
+    });
+    while !client.is_finished() {
+        control_socket.update(|request| {
+            return match request {
+                ControlRequest::Status => Ok(String::from("armed: yes\nfailed-attempts: 1/5")),
+                ControlRequest::Disarm(duration) => Err(format!(
+                    "Disarm for {} second(s) refused",
+                    duration.as_secs()
+                )),
+                _ => Ok(String::new()),
+            };
+        });
+        thread::sleep(Duration::from_millis(10));
+    }
+    return client.join().unwrap();
+}
+
+#[test]
+fn when_client_sends_request_then_it_receives_handler_response() {
+    let path = "/tmp/auth-monitor-control-test-status.sock";
+    assert_eq!(
+        send_request(path, ControlRequest::Status),
+        Ok(String::from("armed: yes\nfailed-attempts: 1/5"))
+    );
+    assert_eq!(send_request(path, ControlRequest::Reset), Ok(String::new()));
+}
+
+#[test]
+fn when_handler_returns_error_then_client_receives_error() {
+    let path = "/tmp/auth-monitor-control-test-error.sock";
+    let request = ControlRequest::Disarm(Duration::from_secs(60));
+    assert_eq!(
+        send_request(path, request),
+        Err(String::from("Disarm for 60 second(s) refused"))
+    );
+}
+
+#[test]
+fn when_control_socket_is_dropped_then_socket_file_is_removed() {
+    let path = "/tmp/auth-monitor-control-test-drop.sock";
+    let control_socket = ControlSocket::new(path).unwrap();
+    assert!(std::path::Path::new(path).exists());
+    drop(control_socket);
+    assert!(!std::path::Path::new(path).exists());
+}
+
+#[test]
+fn when_checking_peer_then_only_root_and_owner_are_allowed() {
+    assert!(ControlSocket::is_peer_allowed(0, 1000));
+    assert!(ControlSocket::is_peer_allowed(1000, 1000));
+    assert!(!ControlSocket::is_peer_allowed(1001, 1000));
+}
+
+#[test]
+fn when_parsing_request_then_it_matches_its_text_form() {
+    let requests = [
+        ControlRequest::Status,
+        ControlRequest::Reset,
+        ControlRequest::Disarm(Duration::from_secs(7200)),
+        ControlRequest::Arm,
+    ];
+    for request in requests {
+        assert_eq!(
+            request.to_string().parse::<ControlRequest>().unwrap(),
+            request
+        );
+    }
+}
+
+#[test]
+fn when_disarm_duration_is_out_of_range_then_request_is_invalid() {
+    for seconds in ["0", "31536001", "18446744073709551615"] {
+        let request = format!("disarm {}", seconds);
+        assert_error!(
+            request.parse::<ControlRequest>(),
+            format!("\"{}\" is not a valid disarm duration", seconds)
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::error::Error;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::time::{Duration, Instant};
+
+use crate::dbus_message::{DBusMessage, DBusValue, ERROR, METHOD_RETURN, SIGNAL};
+
+const UNIX_TRANSPORT_PREFIX: &str = "unix:";
+const PATH_KEY: &str = "path";
+const AUTH_OK_PREFIX: &str = "OK ";
+
+const BUS_NAME: &str = "org.freedesktop.DBus";
+const BUS_PATH: &str = "/org/freedesktop/DBus";
+const HELLO_METHOD: &str = "Hello";
+
+/// A connection to a message bus, authenticated with the credentials of the
+/// process. Method calls are blocking and give up after the timeout.
+pub struct DBusConnection {
+    reader: BufReader<UnixStream>,
+    timeout: Duration,
+    serial: u32,
+}
+
+impl DBusConnection {
+    pub fn open(address: &str, timeout: Duration) -> Result<DBusConnection, Box<dyn Error>> {
+        let path = Self::parse_address(address)?;
+        let stream = match UnixStream::connect(&path) {
+            Ok(stream) => stream,
+            Err(error) => Err(format!("Unable to connect to D-Bus {}: {}", path, error))?,
+        };
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        let mut connection = DBusConnection {
+            reader: BufReader::new(stream),
+            timeout,
+            serial: 0,
+        };
+        connection.authenticate()?;
+        connection.call(BUS_NAME, BUS_PATH, BUS_NAME, HELLO_METHOD, Vec::new())?;
+        return Ok(connection);
+    }
+
+    /// Returns the socket path of a unix:path=... address, the only kind the
+    /// system and session buses use.
+    pub fn parse_address(address: &str) -> Result<String, Box<dyn Error>> {
+        for entry in address.split(';') {
+            let parameters = match entry.strip_prefix(UNIX_TRANSPORT_PREFIX) {
+                Some(parameters) => parameters,
+                None => continue,
+            };
+            for parameter in parameters.split(',') {
+                if let Some((PATH_KEY, path)) = parameter.split_once('=') {
+                    if !path.is_empty() {
+                        return Ok(String::from(path));
+                    }
+                }
+            }
+        }
+        return Err(format!("Unsupported D-Bus address {}", address))?;
+    }
+
+    fn authenticate(&mut self) -> Result<(), Box<dyn Error>> {
+        let uid = unsafe { libc::geteuid() };
+        let hex_uid: String = uid
+            .to_string()
+            .bytes()
+            .map(|byte| {
+                return format!("{:02x}", byte);
+            })
+            .collect();
+        let stream = self.reader.get_mut();
+        stream.write_all(b"\0")?;
+        stream.write_all(format!("AUTH EXTERNAL {}\r\n", hex_uid).as_bytes())?;
+        let mut response = String::new();
+        self.reader.read_line(&mut response)?;
+        if !response.starts_with(AUTH_OK_PREFIX) {
+            Err(format!(
+                "D-Bus authentication failed: {}",
+                response.trim_end()
+            ))?;
+        }
+        self.reader.get_mut().write_all(b"BEGIN\r\n")?;
+        return Ok(());
+    }
+
+    pub fn call(
+        &mut self,
+        destination: &str,
+        path: &str,
+        interface: &str,
+        member: &str,
+        body: Vec<DBusValue>,
+    ) -> Result<Vec<DBusValue>, Box<dyn Error>> {
+        self.serial += 1;
+        let mut message = DBusMessage::method_call(destination, path, interface, member, body);
+        message.serial = self.serial;
+        self.reader.get_mut().write_all(&message.to_bytes())?;
+        let deadline = Instant::now() + self.timeout;
+        // Signals, such as NameAcquired after Hello, may arrive before the reply.
+        while Instant::now() < deadline {
+            let reply = DBusMessage::read_from(&mut self.reader)?;
+            if reply.message_type == SIGNAL || reply.reply_serial != Some(self.serial) {
+                continue;
+            }
+            return match reply.message_type {
+                METHOD_RETURN => Ok(reply.body),
+                ERROR => Err(format!(
+                    "{}.{} failed: {} {}",
+                    interface,
+                    member,
+                    reply.error_name.unwrap_or_default(),
+                    reply.body.first().and_then(DBusValue::as_str).unwrap_or("")
+                ))?,
+                _ => continue,
+            };
+        }
+        return Err(format!("{}.{} timed out", interface, member))?;
+    }
+}
+
+#[cfg(test)]
+#[path = "./dbus_connection_tests.rs"]
+mod tests;
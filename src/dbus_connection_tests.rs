@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use crate::assert_error;
+use crate::dbus_connection::DBusConnection;
+use crate::dbus_message::DBusValue;
+use crate::test_utils::dbus_stand_in::DBusStandIn;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[test]
+fn when_calling_method_then_return_reply_body() {
+    let bus = DBusStandIn::start(|call| {
+        return match call.member.as_deref() {
+            Some("Echo") => Ok(call.body.clone()),
+            _ => Err(String::from("org.freedesktop.DBus.Error.UnknownMethod")),
+        };
+    });
+    let mut connection = DBusConnection::open(&bus.address(), TIMEOUT).unwrap();
+
+    let reply = connection.call(
+        "org.example",
+        "/org/example",
+        "org.example.Test",
+        "Echo",
+        vec![DBusValue::string("hello"), DBusValue::UInt32(3)],
+    );
+    let error = connection.call(
+        "org.example",
+        "/",
+        "org.example.Test",
+        "Missing",
+        Vec::new(),
+    );
+
+    assert_eq!(
+        reply.unwrap(),
+        [DBusValue::string("hello"), DBusValue::UInt32(3)]
+    );
+    assert_error!(
+        error,
+        "org.example.Test.Missing failed: org.freedesktop.DBus.Error.UnknownMethod Refused by test bus"
+    );
+    let calls = bus.calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].destination.as_deref(), Some("org.example"));
+    assert_eq!(calls[0].path.as_deref(), Some("/org/example"));
+}
+
+#[test]
+fn when_parsing_address_then_return_socket_path() {
+    assert_eq!(
+        DBusConnection::parse_address("unix:path=/run/dbus/system_bus_socket").unwrap(),
+        "/run/dbus/system_bus_socket"
+    );
+    assert_eq!(
+        DBusConnection::parse_address("tcp:host=localhost;unix:guid=1,path=/run/user/1000/bus")
+            .unwrap(),
+        "/run/user/1000/bus"
+    );
+    assert_error!(
+        DBusConnection::parse_address("unix:abstract=/tmp/dbus-1"),
+        "Unsupported D-Bus address unix:abstract=/tmp/dbus-1"
+    );
+}
@@ -0,0 +1,520 @@
+use std::error::Error;
+use std::io::Read;
+
+const LITTLE_ENDIAN: u8 = b'l';
+const BIG_ENDIAN: u8 = b'B';
+const PROTOCOL_VERSION: u8 = 1;
+const FIXED_HEADER_LENGTH: usize = 16;
+const MAX_MESSAGE_LENGTH: usize = 4 * 1024 * 1024;
+const MAX_NESTING_DEPTH: usize = 32;
+
+pub const METHOD_CALL: u8 = 1;
+pub const METHOD_RETURN: u8 = 2;
+pub const ERROR: u8 = 3;
+pub const SIGNAL: u8 = 4;
+
+const PATH_FIELD: u8 = 1;
+const INTERFACE_FIELD: u8 = 2;
+const MEMBER_FIELD: u8 = 3;
+const ERROR_NAME_FIELD: u8 = 4;
+const REPLY_SERIAL_FIELD: u8 = 5;
+const DESTINATION_FIELD: u8 = 6;
+const SENDER_FIELD: u8 = 7;
+const SIGNATURE_FIELD: u8 = 8;
+
+/// A D-Bus value with its type. Arrays carry the signature of their
+/// elements, so that empty arrays can be marshalled too.
+#[derive(Clone, PartialEq, Debug)]
+pub enum DBusValue {
+    Byte(u8),
+    Boolean(bool),
+    Int16(i16),
+    UInt16(u16),
+    Int32(i32),
+    UInt32(u32),
+    Int64(i64),
+    UInt64(u64),
+    Double(f64),
+    String(String),
+    ObjectPath(String),
+    Signature(String),
+    Array(String, Vec<DBusValue>),
+    Struct(Vec<DBusValue>),
+    DictEntry(Box<DBusValue>, Box<DBusValue>),
+    Variant(Box<DBusValue>),
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct DBusMessage {
+    pub message_type: u8,
+    pub serial: u32,
+    pub path: Option<String>,
+    pub interface: Option<String>,
+    pub member: Option<String>,
+    pub error_name: Option<String>,
+    pub reply_serial: Option<u32>,
+    pub destination: Option<String>,
+    pub sender: Option<String>,
+    pub body: Vec<DBusValue>,
+}
+
+struct DBusWriter {
+    data: Vec<u8>,
+}
+
+struct DBusReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    big_endian: bool,
+}
+
+impl DBusValue {
+    pub fn string(value: &str) -> DBusValue {
+        return DBusValue::String(String::from(value));
+    }
+
+    pub fn signature(&self) -> String {
+        return match self {
+            DBusValue::Byte(_) => String::from("y"),
+            DBusValue::Boolean(_) => String::from("b"),
+            DBusValue::Int16(_) => String::from("n"),
+            DBusValue::UInt16(_) => String::from("q"),
+            DBusValue::Int32(_) => String::from("i"),
+            DBusValue::UInt32(_) => String::from("u"),
+            DBusValue::Int64(_) => String::from("x"),
+            DBusValue::UInt64(_) => String::from("t"),
+            DBusValue::Double(_) => String::from("d"),
+            DBusValue::String(_) => String::from("s"),
+            DBusValue::ObjectPath(_) => String::from("o"),
+            DBusValue::Signature(_) => String::from("g"),
+            DBusValue::Array(element_signature, _) => format!("a{}", element_signature),
+            DBusValue::Struct(fields) => format!("({})", Self::signature_of(fields)),
+            DBusValue::DictEntry(key, value) => {
+                format!("{{{}{}}}", key.signature(), value.signature())
+            }
+            DBusValue::Variant(_) => String::from("v"),
+        };
+    }
+
+    pub fn signature_of(values: &[DBusValue]) -> String {
+        return values.iter().map(DBusValue::signature).collect();
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        return match self {
+            DBusValue::String(value) | DBusValue::ObjectPath(value) => Some(value),
+            DBusValue::Signature(value) => Some(value),
+            DBusValue::Variant(value) => value.as_str(),
+            _ => None,
+        };
+    }
+
+    pub fn as_u32(&self) -> Option<u32> {
+        return match self {
+            DBusValue::UInt32(value) => Some(*value),
+            DBusValue::Variant(value) => value.as_u32(),
+            _ => None,
+        };
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        return match self {
+            DBusValue::Boolean(value) => Some(*value),
+            DBusValue::Variant(value) => value.as_bool(),
+            _ => None,
+        };
+    }
+
+    /// Elements of an array or fields of a struct.
+    pub fn items(&self) -> &[DBusValue] {
+        return match self {
+            DBusValue::Array(_, items) | DBusValue::Struct(items) => items,
+            DBusValue::Variant(value) => value.items(),
+            _ => &[],
+        };
+    }
+
+    /// Looks up a key in a dictionary such as a{sv}, unwrapping variant values.
+    pub fn get(&self, key: &str) -> Option<&DBusValue> {
+        for item in self.items() {
+            if let DBusValue::DictEntry(entry_key, value) = item {
+                if entry_key.as_str() == Some(key) {
+                    return match value.as_ref() {
+                        DBusValue::Variant(value) => Some(value),
+                        value => Some(value),
+                    };
+                }
+            }
+        }
+        return None;
+    }
+
+    fn alignment(signature: &str) -> usize {
+        return match signature.as_bytes().first() {
+            Some(b'n' | b'q') => 2,
+            Some(b'b' | b'i' | b'u' | b's' | b'o' | b'a') => 4,
+            Some(b'x' | b't' | b'd' | b'(' | b'{') => 8,
+            _ => 1,
+        };
+    }
+
+    /// Splits a signature into complete types, such as "sa{sv}i" into "s",
+    /// "a{sv}" and "i".
+    pub fn split_signature(signature: &str) -> Result<Vec<&str>, Box<dyn Error>> {
+        let mut types = Vec::new();
+        let mut rest = signature;
+        while !rest.is_empty() {
+            let length = Self::complete_type_length(rest)?;
+            types.push(&rest[..length]);
+            rest = &rest[length..];
+        }
+        return Ok(types);
+    }
+
+    fn complete_type_length(signature: &str) -> Result<usize, Box<dyn Error>> {
+        let bytes = signature.as_bytes();
+        return match bytes.first() {
+            Some(b'a') => Ok(1 + Self::complete_type_length(&signature[1..])?),
+            Some(&open @ (b'(' | b'{')) => {
+                let close = match open {
+                    b'(' => b')',
+                    _ => b'}',
+                };
+                let mut length = 1;
+                let mut count = 0;
+                while bytes.get(length) != Some(&close) {
+                    if length >= bytes.len() || matches!(bytes[length], b')' | b'}') {
+                        Err(format!("Invalid D-Bus signature {}", signature))?;
+                    }
+                    length += Self::complete_type_length(&signature[length..])?;
+                    count += 1;
+                }
+                // Structs cannot be empty and dict entries hold a key and a value.
+                let is_valid = match open {
+                    b'(' => count > 0,
+                    _ => count == 2,
+                };
+                if !is_valid {
+                    Err(format!("Invalid D-Bus signature {}", signature))?;
+                }
+                Ok(length + 1)
+            }
+            Some(b'y' | b'b' | b'n' | b'q' | b'i' | b'u' | b'x' | b't' | b'd' | b's' | b'o')
+            | Some(b'g' | b'v') => Ok(1),
+            _ => Err(format!("Invalid D-Bus signature {}", signature))?,
+        };
+    }
+}
+
+impl DBusMessage {
+    pub fn method_call(
+        destination: &str,
+        path: &str,
+        interface: &str,
+        member: &str,
+        body: Vec<DBusValue>,
+    ) -> DBusMessage {
+        return DBusMessage {
+            message_type: METHOD_CALL,
+            path: Some(String::from(path)),
+            interface: Some(String::from(interface)),
+            member: Some(String::from(member)),
+            destination: Some(String::from(destination)),
+            body,
+            ..DBusMessage::default()
+        };
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut body = DBusWriter { data: Vec::new() };
+        for value in &self.body {
+            body.write_value(value);
+        }
+        let mut fields = Vec::new();
+        let string_fields = [
+            (PATH_FIELD, &self.path),
+            (INTERFACE_FIELD, &self.interface),
+            (MEMBER_FIELD, &self.member),
+            (ERROR_NAME_FIELD, &self.error_name),
+            (DESTINATION_FIELD, &self.destination),
+            (SENDER_FIELD, &self.sender),
+        ];
+        for (code, value) in string_fields {
+            if let Some(value) = value {
+                let value = match code {
+                    PATH_FIELD => DBusValue::ObjectPath(value.clone()),
+                    _ => DBusValue::String(value.clone()),
+                };
+                fields.push(Self::header_field(code, value));
+            }
+        }
+        if let Some(reply_serial) = self.reply_serial {
+            fields.push(Self::header_field(
+                REPLY_SERIAL_FIELD,
+                DBusValue::UInt32(reply_serial),
+            ));
+        }
+        if !self.body.is_empty() {
+            let signature = DBusValue::Signature(DBusValue::signature_of(&self.body));
+            fields.push(Self::header_field(SIGNATURE_FIELD, signature));
+        }
+        let mut message = DBusWriter { data: Vec::new() };
+        message
+            .data
+            .extend([LITTLE_ENDIAN, self.message_type, 0, PROTOCOL_VERSION]);
+        message.write_value(&DBusValue::UInt32(body.data.len() as u32));
+        message.write_value(&DBusValue::UInt32(self.serial));
+        message.write_value(&DBusValue::Array(String::from("(yv)"), fields));
+        message.align(8);
+        message.data.extend(body.data);
+        return message.data;
+    }
+
+    fn header_field(code: u8, value: DBusValue) -> DBusValue {
+        return DBusValue::Struct(vec![
+            DBusValue::Byte(code),
+            DBusValue::Variant(Box::new(value)),
+        ]);
+    }
+
+    pub fn read_from(reader: &mut impl Read) -> Result<DBusMessage, Box<dyn Error>> {
+        let mut fixed_header = [0u8; FIXED_HEADER_LENGTH];
+        reader.read_exact(&mut fixed_header)?;
+        let big_endian = match fixed_header[0] {
+            LITTLE_ENDIAN => false,
+            BIG_ENDIAN => true,
+            _ => Err("Invalid D-Bus message byte order")?,
+        };
+        let mut header = DBusReader {
+            data: &fixed_header,
+            position: 4,
+            big_endian,
+        };
+        let body_length = header.read_u32()? as usize;
+        let serial = header.read_u32()?;
+        let fields_length = header.read_u32()? as usize;
+        let header_length = (FIXED_HEADER_LENGTH + fields_length).next_multiple_of(8);
+        if header_length + body_length > MAX_MESSAGE_LENGTH {
+            Err("D-Bus message is too long")?;
+        }
+        let mut data = vec![0u8; header_length + body_length];
+        data[..FIXED_HEADER_LENGTH].copy_from_slice(&fixed_header);
+        reader.read_exact(&mut data[FIXED_HEADER_LENGTH..])?;
+
+        let mut message = DBusMessage {
+            message_type: fixed_header[1],
+            serial,
+            ..DBusMessage::default()
+        };
+        let mut header = DBusReader {
+            data: &data[..FIXED_HEADER_LENGTH + fields_length],
+            position: 12,
+            big_endian,
+        };
+        let mut signature = String::new();
+        for field in header.read_value("a(yv)", 0)?.items() {
+            let (code, value) = match field.items() {
+                [DBusValue::Byte(code), value] => (*code, value),
+                _ => Err("Invalid D-Bus header field")?,
+            };
+            let string = value.as_str().map(String::from);
+            match code {
+                PATH_FIELD => message.path = string,
+                INTERFACE_FIELD => message.interface = string,
+                MEMBER_FIELD => message.member = string,
+                ERROR_NAME_FIELD => message.error_name = string,
+                REPLY_SERIAL_FIELD => message.reply_serial = value.as_u32(),
+                DESTINATION_FIELD => message.destination = string,
+                SENDER_FIELD => message.sender = string,
+                SIGNATURE_FIELD => signature = string.unwrap_or_default(),
+                _ => {}
+            }
+        }
+        let mut body = DBusReader {
+            data: &data[header_length..],
+            position: 0,
+            big_endian,
+        };
+        for value_signature in DBusValue::split_signature(&signature)? {
+            message.body.push(body.read_value(value_signature, 0)?);
+        }
+        return Ok(message);
+    }
+}
+
+impl DBusWriter {
+    fn align(&mut self, alignment: usize) {
+        let length = self.data.len().next_multiple_of(alignment);
+        self.data.resize(length, 0);
+    }
+
+    fn write_value(&mut self, value: &DBusValue) {
+        self.align(DBusValue::alignment(&value.signature()));
+        match value {
+            DBusValue::Byte(value) => self.data.push(*value),
+            DBusValue::Boolean(value) => self.data.extend((*value as u32).to_le_bytes()),
+            DBusValue::Int16(value) => self.data.extend(value.to_le_bytes()),
+            DBusValue::UInt16(value) => self.data.extend(value.to_le_bytes()),
+            DBusValue::Int32(value) => self.data.extend(value.to_le_bytes()),
+            DBusValue::UInt32(value) => self.data.extend(value.to_le_bytes()),
+            DBusValue::Int64(value) => self.data.extend(value.to_le_bytes()),
+            DBusValue::UInt64(value) => self.data.extend(value.to_le_bytes()),
+            DBusValue::Double(value) => self.data.extend(value.to_le_bytes()),
+            DBusValue::String(value) | DBusValue::ObjectPath(value) => {
+                self.data.extend((value.len() as u32).to_le_bytes());
+                self.data.extend(value.as_bytes());
+                self.data.push(0);
+            }
+            DBusValue::Signature(value) => {
+                self.data.push(value.len() as u8);
+                self.data.extend(value.as_bytes());
+                self.data.push(0);
+            }
+            DBusValue::Array(element_signature, items) => {
+                let length_position = self.data.len();
+                self.data.extend([0u8; 4]);
+                // Padding before the first element is not part of the length.
+                self.align(DBusValue::alignment(element_signature));
+                let start = self.data.len();
+                for item in items {
+                    self.write_value(item);
+                }
+                let length = (self.data.len() - start) as u32;
+                self.data[length_position..length_position + 4]
+                    .copy_from_slice(&length.to_le_bytes());
+            }
+            DBusValue::Struct(fields) => {
+                for field in fields {
+                    self.write_value(field);
+                }
+            }
+            DBusValue::DictEntry(key, value) => {
+                self.write_value(key);
+                self.write_value(value);
+            }
+            DBusValue::Variant(value) => {
+                self.write_value(&DBusValue::Signature(value.signature()));
+                self.write_value(value);
+            }
+        }
+    }
+}
+
+impl DBusReader<'_> {
+    fn align(&mut self, alignment: usize) -> Result<(), Box<dyn Error>> {
+        self.position = self.position.next_multiple_of(alignment);
+        if self.position > self.data.len() {
+            Err("Truncated D-Bus message")?;
+        }
+        return Ok(());
+    }
+
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], Box<dyn Error>> {
+        self.align(N)?;
+        let bytes = match self.data.get(self.position..self.position + N) {
+            Some(bytes) => bytes,
+            None => Err("Truncated D-Bus message")?,
+        };
+        self.position += N;
+        let mut array = [0u8; N];
+        array.copy_from_slice(bytes);
+        if self.big_endian {
+            array.reverse();
+        }
+        return Ok(array);
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Box<dyn Error>> {
+        return Ok(u32::from_le_bytes(self.read_bytes()?));
+    }
+
+    fn read_text(&mut self, length: usize) -> Result<String, Box<dyn Error>> {
+        let bytes = match self.data.get(self.position..self.position + length + 1) {
+            Some(bytes) => bytes,
+            None => Err("Truncated D-Bus message")?,
+        };
+        self.position += length + 1;
+        return Ok(String::from_utf8(bytes[..length].to_vec())?);
+    }
+
+    fn read_value(&mut self, signature: &str, depth: usize) -> Result<DBusValue, Box<dyn Error>> {
+        if depth > MAX_NESTING_DEPTH {
+            Err("D-Bus value is nested too deeply")?;
+        }
+        let value = match signature.as_bytes()[0] {
+            b'y' => DBusValue::Byte(self.read_bytes::<1>()?[0]),
+            b'b' => DBusValue::Boolean(self.read_u32()? != 0),
+            b'n' => DBusValue::Int16(i16::from_le_bytes(self.read_bytes()?)),
+            b'q' => DBusValue::UInt16(u16::from_le_bytes(self.read_bytes()?)),
+            b'i' => DBusValue::Int32(i32::from_le_bytes(self.read_bytes()?)),
+            b'u' => DBusValue::UInt32(self.read_u32()?),
+            b'x' => DBusValue::Int64(i64::from_le_bytes(self.read_bytes()?)),
+            b't' => DBusValue::UInt64(u64::from_le_bytes(self.read_bytes()?)),
+            b'd' => DBusValue::Double(f64::from_le_bytes(self.read_bytes()?)),
+            b's' => {
+                let length = self.read_u32()? as usize;
+                DBusValue::String(self.read_text(length)?)
+            }
+            b'o' => {
+                let length = self.read_u32()? as usize;
+                DBusValue::ObjectPath(self.read_text(length)?)
+            }
+            b'g' => {
+                let length = self.read_bytes::<1>()?[0] as usize;
+                DBusValue::Signature(self.read_text(length)?)
+            }
+            b'a' => {
+                let length = self.read_u32()? as usize;
+                let element_signature = &signature[1..];
+                self.align(DBusValue::alignment(element_signature))?;
+                let end = self.position + length;
+                if end > self.data.len() {
+                    Err("Truncated D-Bus message")?;
+                }
+                let mut items = Vec::new();
+                while self.position < end {
+                    let start = self.position;
+                    items.push(self.read_value(element_signature, depth + 1)?);
+                    if self.position == start {
+                        Err("Invalid D-Bus array element of zero length")?;
+                    }
+                }
+                DBusValue::Array(String::from(element_signature), items)
+            }
+            b'(' | b'{' => {
+                self.align(8)?;
+                let inner_signature = &signature[1..signature.len() - 1];
+                let mut fields = Vec::new();
+                for field_signature in DBusValue::split_signature(inner_signature)? {
+                    fields.push(self.read_value(field_signature, depth + 1)?);
+                }
+                match (signature.as_bytes()[0], fields.as_slice()) {
+                    (b'{', [key, value]) => {
+                        DBusValue::DictEntry(Box::new(key.clone()), Box::new(value.clone()))
+                    }
+                    (b'{', _) => Err(format!("Invalid D-Bus signature {}", signature))?,
+                    _ => DBusValue::Struct(fields),
+                }
+            }
+            b'v' => {
+                let length = self.read_bytes::<1>()?[0] as usize;
+                let value_signature = self.read_text(length)?;
+                match DBusValue::split_signature(&value_signature)?.as_slice() {
+                    [value_signature] => {
+                        DBusValue::Variant(Box::new(self.read_value(value_signature, depth + 1)?))
+                    }
+                    _ => Err(format!(
+                        "Invalid D-Bus variant signature {}",
+                        value_signature
+                    ))?,
+                }
+            }
+            _ => Err(format!("Invalid D-Bus signature {}", signature))?,
+        };
+        return Ok(value);
+    }
+}
+
+#[cfg(test)]
+#[path = "./dbus_message_tests.rs"]
+mod tests;
@@ -0,0 +1,151 @@
+use crate::assert_error;
+use crate::dbus_message::{DBusMessage, DBusValue, METHOD_CALL};
+
+fn dict_entry(key: &str, value: DBusValue) -> DBusValue {
+    return DBusValue::DictEntry(
+        Box::new(DBusValue::string(key)),
+        Box::new(DBusValue::Variant(Box::new(value))),
+    );
+}
+
+#[test]
+fn when_marshalling_body_then_values_are_aligned_and_arrays_have_byte_length() {
+    let message = DBusMessage::method_call(
+        "org.example",
+        "/org/example",
+        "org.example.Test",
+        "Test",
+        vec![
+            DBusValue::string("ab"),
+            DBusValue::Array(
+                String::from("{sv}"),
+                vec![dict_entry("k", DBusValue::Byte(2))],
+            ),
+        ],
+    );
+
+    let bytes = message.to_bytes();
+
+    let expected_body = [
+        2, 0, 0, 0, b'a', b'b', 0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, b'k', 0, 1, b'y', 0, 2,
+    ];
+    assert!(bytes.ends_with(&expected_body), "{:?}", bytes);
+    assert_eq!(&bytes[..4], &[b'l', METHOD_CALL, 0, 1]);
+    assert_eq!(&bytes[4..8], &(expected_body.len() as u32).to_le_bytes());
+}
+
+#[test]
+fn when_reading_marshalled_message_then_return_same_message() {
+    let mut message = DBusMessage::method_call(
+        "org.freedesktop.Notifications",
+        "/org/freedesktop/Notifications",
+        "org.freedesktop.Notifications",
+        "Notify",
+        vec![
+            DBusValue::string("auth-monitor"),
+            DBusValue::UInt32(0),
+            DBusValue::Array(String::from("s"), Vec::new()),
+            DBusValue::Array(
+                String::from("{sv}"),
+                vec![
+                    dict_entry("urgency", DBusValue::Byte(2)),
+                    dict_entry("resident", DBusValue::Boolean(true)),
+                ],
+            ),
+            DBusValue::Struct(vec![
+                DBusValue::Int64(-5),
+                DBusValue::Double(1.5),
+                DBusValue::ObjectPath(String::from("/org/example")),
+            ]),
+            DBusValue::Int32(-1),
+        ],
+    );
+    message.serial = 7;
+
+    let read_message = DBusMessage::read_from(&mut message.to_bytes().as_slice()).unwrap();
+
+    assert_eq!(read_message, message);
+    assert_eq!(
+        read_message.body[3].get("urgency"),
+        Some(&DBusValue::Byte(2))
+    );
+    assert_eq!(read_message.body[3].get("missing"), None);
+}
+
+#[test]
+fn when_reading_truncated_message_then_return_error() {
+    let message = DBusMessage::method_call("a.b", "/a", "a.b", "C", vec![DBusValue::string("x")]);
+    let bytes = message.to_bytes();
+
+    let result = DBusMessage::read_from(&mut &bytes[..bytes.len() - 1]);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn when_splitting_signature_then_return_complete_types() {
+    assert_eq!(
+        DBusValue::split_signature("susssasa{sv}i").unwrap(),
+        ["s", "u", "s", "s", "s", "as", "a{sv}", "i"]
+    );
+    assert_eq!(
+        DBusValue::split_signature("a(susso)").unwrap(),
+        ["a(susso)"]
+    );
+    assert_error!(
+        DBusValue::split_signature("a(su"),
+        "Invalid D-Bus signature (su"
+    );
+    assert_error!(DBusValue::split_signature("z"), "Invalid D-Bus signature z");
+}
+
+#[test]
+fn when_array_elements_are_empty_structs_then_return_error() {
+    let message = DBusMessage::method_call(
+        "a.b",
+        "/a",
+        "a.b",
+        "C",
+        vec![
+            DBusValue::Array(String::from("t"), vec![DBusValue::UInt64(0)]),
+            DBusValue::Byte(0),
+        ],
+    );
+    let mut bytes = message.to_bytes();
+    // Turns the "aty" body signature into "a()", whose elements use no bytes.
+    let position = bytes
+        .windows(4)
+        .position(|window| {
+            return window == b"aty\0";
+        })
+        .unwrap();
+    bytes[position..position + 3].copy_from_slice(b"a()");
+
+    let result = DBusMessage::read_from(&mut &bytes[..]);
+
+    assert_error!(result, "Invalid D-Bus signature ()");
+}
+
+#[test]
+fn when_signature_has_empty_struct_or_invalid_dict_entry_then_return_error() {
+    assert_error!(
+        DBusValue::split_signature("a()"),
+        "Invalid D-Bus signature ()"
+    );
+    assert_error!(
+        DBusValue::split_signature("a{}"),
+        "Invalid D-Bus signature {}"
+    );
+    assert_error!(
+        DBusValue::split_signature("a{s}"),
+        "Invalid D-Bus signature {s}"
+    );
+    assert_error!(
+        DBusValue::split_signature("a{sss}"),
+        "Invalid D-Bus signature {sss}"
+    );
+    assert_error!(
+        DBusValue::split_signature("(s}"),
+        "Invalid D-Bus signature (s}"
+    );
+}
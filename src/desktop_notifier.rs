@@ -0,0 +1,146 @@
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::time::Duration;
+
+use crate::action_context::ActionContext;
+use crate::command_line::PROGRAM_NAME;
+use crate::command_runner::CommandRunner;
+use crate::dbus_connection::DBusConnection;
+use crate::dbus_message::DBusValue;
+use crate::desktop_options::DesktopOptions;
+use crate::logger::LogEntry;
+use crate::logind::{Logind, LogindSession};
+
+const UID_PLACEHOLDER: &str = "{uid}";
+
+const NOTIFICATIONS_NAME: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+const NOTIFY_METHOD: &str = "Notify";
+const NOTIFICATION_ICON: &str = "dialog-warning";
+const NOTIFICATION_SUMMARY: &str = "Failed authentication attempts";
+const CRITICAL_URGENCY: u8 = 2;
+const DEFAULT_EXPIRE_TIMEOUT: i32 = -1;
+
+/// Shows a freedesktop notification in every active graphical session.
+pub struct DesktopNotifier {}
+
+impl DesktopNotifier {
+    pub fn send(options: &DesktopOptions, context: &ActionContext) -> Result<(), Box<dyn Error>> {
+        let timeout = Duration::from_secs(options.timeout_seconds as u64);
+        let mut system_bus = DBusConnection::open(&options.system_bus, timeout)?;
+        let mut sessions: Vec<LogindSession> = Logind::sessions(&mut system_bus)?
+            .into_iter()
+            .filter(LogindSession::is_active_graphical)
+            .collect();
+        // Sessions of the same user share a session bus.
+        sessions.sort_by_key(|session| {
+            return session.uid;
+        });
+        sessions.dedup_by_key(|session| {
+            return session.uid;
+        });
+        if sessions.is_empty() {
+            Err("No active graphical session")?;
+        }
+        let body = context.summary();
+        let mut errors = Vec::new();
+        for session in sessions {
+            let address = options
+                .session_bus
+                .replace(UID_PLACEHOLDER, &session.uid.to_string());
+            match Self::notify_as_user(&address, session.uid, timeout, &body) {
+                Ok(_) => {
+                    let message = format!("Desktop notification shown in session {}", session.id);
+                    LogEntry::debug("desktop_notified", &message)
+                        .user(Some(&session.user))
+                        .log();
+                }
+                Err(error) => errors.push(format!("user {}: {}", session.user, error)),
+            }
+        }
+        if !errors.is_empty() {
+            Err(errors.join(", "))?;
+        }
+        return Ok(());
+    }
+
+    /// Session buses only accept connections from their owner, so the
+    /// notification is sent from a thread running with the credentials of
+    /// the session user.
+    fn notify_as_user(
+        address: &str,
+        uid: u32,
+        timeout: Duration,
+        body: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let socket = fs::metadata(DBusConnection::parse_address(address)?)?;
+        if socket.uid() != uid {
+            Err(format!("{} is not owned by user {}", address, uid))?;
+        }
+        let (address, body) = (String::from(address), String::from(body));
+        let gid = socket.gid();
+        // The session bus belongs to the user, so a stalled exchange must not
+        // hold up the actions following this one.
+        return CommandRunner::run_function("Desktop notification", timeout, move || {
+            if unsafe { libc::geteuid() } != uid {
+                if let Err(error) = Self::switch_thread_credentials(uid, gid) {
+                    return Err(format!("Unable to switch to user {}: {}", uid, error));
+                }
+            }
+            return Self::notify(&address, timeout, &body).map_err(|error| {
+                return error.to_string();
+            });
+        });
+    }
+
+    /// Unlike the libc wrappers, which change all threads of the process, the
+    /// raw system calls only change the credentials of the calling thread.
+    fn switch_thread_credentials(uid: u32, gid: u32) -> io::Result<()> {
+        let results = unsafe {
+            [
+                libc::syscall(libc::SYS_setgroups, 0, std::ptr::null::<libc::gid_t>()),
+                libc::syscall(libc::SYS_setresgid, gid, gid, gid),
+                libc::syscall(libc::SYS_setresuid, uid, uid, uid),
+            ]
+        };
+        if results.iter().any(|result| {
+            return *result != 0;
+        }) {
+            return Err(io::Error::last_os_error());
+        }
+        return Ok(());
+    }
+
+    fn notify(address: &str, timeout: Duration, body: &str) -> Result<(), Box<dyn Error>> {
+        let mut session_bus = DBusConnection::open(address, timeout)?;
+        let hints = vec![DBusValue::DictEntry(
+            Box::new(DBusValue::string("urgency")),
+            Box::new(DBusValue::Variant(Box::new(DBusValue::Byte(
+                CRITICAL_URGENCY,
+            )))),
+        )];
+        session_bus.call(
+            NOTIFICATIONS_NAME,
+            NOTIFICATIONS_PATH,
+            NOTIFICATIONS_NAME,
+            NOTIFY_METHOD,
+            vec![
+                DBusValue::string(PROGRAM_NAME),
+                DBusValue::UInt32(0),
+                DBusValue::string(NOTIFICATION_ICON),
+                DBusValue::string(NOTIFICATION_SUMMARY),
+                DBusValue::string(body),
+                DBusValue::Array(String::from("s"), Vec::new()),
+                DBusValue::Array(String::from("{sv}"), hints),
+                DBusValue::Int32(DEFAULT_EXPIRE_TIMEOUT),
+            ],
+        )?;
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+#[path = "./desktop_notifier_tests.rs"]
+mod tests;
@@ -0,0 +1,129 @@
+use std::time::SystemTime;
+
+use crate::action::Action;
+use crate::action_context::{ActionContext, FailedAttempt};
+use crate::assert_error;
+use crate::dbus_message::{DBusMessage, DBusValue};
+use crate::desktop_notifier::DesktopNotifier;
+use crate::desktop_options::DesktopOptions;
+use crate::test_utils::dbus_stand_in::DBusStandIn;
+
+fn context() -> ActionContext {
+    return ActionContext {
+        host: String::from("workstation"),
+        user: Some(String::from("john")),
+        service: Some(String::from("kde")),
+        counter: 3,
+        max_failed_attempts: 5,
+        limit_actions: vec![Action::PowerOff],
+        attempts: vec![FailedAttempt {
+            time: SystemTime::now(),
+            counter: 3,
+            line: String::from("kscreenlocker_greet: authentication failure"),
+        }],
+    };
+}
+
+fn session(id: &str, uid: u32) -> DBusValue {
+    return DBusValue::Struct(vec![
+        DBusValue::string(id),
+        DBusValue::UInt32(uid),
+        DBusValue::string("john"),
+        DBusValue::string("seat0"),
+        DBusValue::ObjectPath(format!("/org/freedesktop/login1/session/_{}", id)),
+    ]);
+}
+
+fn property(name: &str, value: DBusValue) -> DBusValue {
+    return DBusValue::DictEntry(
+        Box::new(DBusValue::string(name)),
+        Box::new(DBusValue::Variant(Box::new(value))),
+    );
+}
+
+/// Stands in for logind and the notification server of the test user. The
+/// graphical session is active only when `active` is true, the second
+/// session is always a text console.
+fn start_bus(active: bool) -> DBusStandIn {
+    let uid = unsafe { libc::geteuid() };
+    return DBusStandIn::start(move |call| {
+        return match call.member.as_deref() {
+            Some("ListSessions") => Ok(vec![DBusValue::Array(
+                String::from("(susso)"),
+                vec![session("2", uid), session("3", uid)],
+            )]),
+            Some("GetAll") => {
+                let session_type = match call.path.as_deref() {
+                    Some("/org/freedesktop/login1/session/_2") => "wayland",
+                    _ => "tty",
+                };
+                Ok(vec![DBusValue::Array(
+                    String::from("{sv}"),
+                    vec![
+                        property("Type", DBusValue::string(session_type)),
+                        property("Class", DBusValue::string("user")),
+                        property("Active", DBusValue::Boolean(active)),
+                    ],
+                )])
+            }
+            Some("Notify") => Ok(vec![DBusValue::UInt32(1)]),
+            _ => Err(String::from("org.freedesktop.DBus.Error.UnknownMethod")),
+        };
+    });
+}
+
+fn options(bus: &DBusStandIn) -> DesktopOptions {
+    return DesktopOptions {
+        system_bus: bus.address(),
+        session_bus: bus.address(),
+        timeout_seconds: 5,
+    };
+}
+
+fn members(calls: &[DBusMessage]) -> Vec<&str> {
+    return calls
+        .iter()
+        .map(|call| {
+            return call.member.as_deref().unwrap_or_default();
+        })
+        .collect();
+}
+
+#[test]
+fn when_graphical_session_is_active_then_notification_is_sent_to_its_bus() {
+    let bus = start_bus(true);
+
+    let result = DesktopNotifier::send(&options(&bus), &context());
+
+    assert!(result.is_ok(), "{:?}", result.err());
+    let calls = bus.calls();
+    assert_eq!(
+        members(&calls),
+        ["ListSessions", "GetAll", "GetAll", "Notify"]
+    );
+    let notify = &calls[3];
+    assert_eq!(
+        notify.destination.as_deref(),
+        Some("org.freedesktop.Notifications")
+    );
+    assert_eq!(notify.body[0], DBusValue::string("auth-monitor"));
+    assert_eq!(
+        notify.body[3],
+        DBusValue::string("Failed authentication attempts")
+    );
+    assert_eq!(
+        notify.body[4],
+        DBusValue::string("3 failed authentication attempt(s); the machine will power off after 5")
+    );
+    assert_eq!(notify.body[6].get("urgency"), Some(&DBusValue::Byte(2)));
+}
+
+#[test]
+fn when_no_graphical_session_is_active_then_return_error() {
+    let bus = start_bus(false);
+
+    let result = DesktopNotifier::send(&options(&bus), &context());
+
+    assert_error!(result, "No active graphical session");
+    assert_eq!(members(&bus.calls()), ["ListSessions", "GetAll", "GetAll"]);
+}
@@ -0,0 +1,32 @@
+use std::fmt::{Display, Formatter};
+
+pub const SYSTEM_BUS_ADDRESS: &str = "unix:path=/run/dbus/system_bus_socket";
+/// Session bus of a user, {uid} is replaced by the user ID.
+pub const SESSION_BUS_ADDRESS: &str = "unix:path=/run/user/{uid}/bus";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DesktopOptions {
+    pub system_bus: String,
+    pub session_bus: String,
+    pub timeout_seconds: i32,
+}
+
+impl Default for DesktopOptions {
+    fn default() -> Self {
+        return DesktopOptions {
+            system_bus: String::from(SYSTEM_BUS_ADDRESS),
+            session_bus: String::from(SESSION_BUS_ADDRESS),
+            timeout_seconds: 5,
+        };
+    }
+}
+
+impl Display for DesktopOptions {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            formatter,
+            "desktop-system-bus={}, desktop-session-bus={}, desktop-timeout-seconds={}",
+            self.system_bus, self.session_bus, self.timeout_seconds
+        );
+    }
+}
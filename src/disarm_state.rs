@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const DISARM_STATE_FILENAME: &str = "disarmed-until";
+
+/// Keeps the disarm deadline in the state directory, so restarting the daemon
+/// does not re-arm it before the deadline.
+pub struct DisarmState {
+    path: PathBuf,
+}
+
+impl DisarmState {
+    pub fn new(state_directory: &str) -> DisarmState {
+        return DisarmState {
+            path: Path::new(state_directory).join(DISARM_STATE_FILENAME),
+        };
+    }
+
+    pub fn load(&self) -> Result<Option<SystemTime>, Box<dyn Error>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => Err(format!(
+                "Error reading disarm state {}: {}",
+                self.path.display(),
+                error
+            ))?,
+        };
+        let deadline = content.trim().parse::<u64>().ok().and_then(|seconds| {
+            return SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(seconds));
+        });
+        return match deadline {
+            Some(deadline) => Ok(Some(deadline)),
+            None => Err(format!("Invalid disarm state {}", self.path.display()))?,
+        };
+    }
+
+    pub fn save(&self, deadline: SystemTime) -> Result<(), Box<dyn Error>> {
+        let seconds = deadline
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+        // Replaces the file in one step, so a crash cannot leave it truncated.
+        let temporary_path = self.path.with_extension("tmp");
+        let result = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&temporary_path)
+            .and_then(|mut file| {
+                file.write_all(format!("{}\n", seconds).as_bytes())?;
+                return file.sync_all();
+            })
+            .and_then(|_| {
+                return fs::rename(&temporary_path, &self.path);
+            });
+        if let Err(error) = result {
+            Err(format!(
+                "Error saving disarm state {}: {}",
+                self.path.display(),
+                error
+            ))?;
+        }
+        return Ok(());
+    }
+
+    pub fn clear(&self) -> Result<(), Box<dyn Error>> {
+        return match fs::remove_file(&self.path) {
+            Ok(_) => Ok(()),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(()),
+            Err(error) => Err(format!(
+                "Error removing disarm state {}: {}",
+                self.path.display(),
+                error
+            ))?,
+        };
+    }
+}
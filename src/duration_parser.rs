@@ -0,0 +1,61 @@
+use std::error::Error;
+use std::time::Duration;
+
+const SECONDS_PER_MINUTE: u64 = 60;
+const SECONDS_PER_HOUR: u64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+const MAX_DAYS: u64 = 365;
+
+/// Longest accepted duration, which keeps deadlines far from the limits of
+/// the time types.
+pub const MAX_DURATION: Duration = Duration::from_secs(MAX_DAYS * SECONDS_PER_DAY);
+
+pub struct DurationParser {}
+
+impl DurationParser {
+    /// Parses durations such as "90", "45s", "30m", "2h", "1d" or "1h30m".
+    /// A number without a unit is a number of seconds.
+    pub fn parse(text: &str) -> Result<Duration, Box<dyn Error>> {
+        let seconds = match text.parse::<u64>() {
+            Ok(seconds) => Some(seconds),
+            Err(_) => Self::parse_with_units(text),
+        };
+        let duration = match seconds {
+            Some(seconds) if seconds > 0 => Duration::from_secs(seconds),
+            _ => Err(format!("\"{}\" is not a valid duration", text))?,
+        };
+        if duration > MAX_DURATION {
+            Err(format!("\"{}\" is longer than {} days", text, MAX_DAYS))?;
+        }
+        return Ok(duration);
+    }
+
+    fn parse_with_units(text: &str) -> Option<u64> {
+        let mut seconds: u64 = 0;
+        let mut number = String::new();
+        for character in text.chars() {
+            if character.is_ascii_digit() {
+                number.push(character);
+                continue;
+            }
+            let unit_seconds = match character {
+                's' => 1,
+                'm' => SECONDS_PER_MINUTE,
+                'h' => SECONDS_PER_HOUR,
+                'd' => SECONDS_PER_DAY,
+                _ => return None,
+            };
+            let value = number.parse::<u64>().ok()?;
+            seconds = seconds.checked_add(value.checked_mul(unit_seconds)?)?;
+            number.clear();
+        }
+        if !number.is_empty() {
+            return None;
+        }
+        return Some(seconds);
+    }
+}
+
+#[cfg(test)]
+#[path = "./duration_parser_tests.rs"]
+mod tests;
@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use crate::assert_error;
+use crate::duration_parser::DurationParser;
+
+#[test]
+fn when_parsing_duration_with_units_then_return_total_seconds() {
+    let durations = [
+        ("90", 90),
+        ("45s", 45),
+        ("30m", 1800),
+        ("2h", 7200),
+        ("1d", 86400),
+        ("1h30m", 5400),
+        ("1d2h3m4s", 93784),
+    ];
+    for (text, seconds) in durations {
+        assert_eq!(
+            DurationParser::parse(text).unwrap(),
+            Duration::from_secs(seconds)
+        );
+    }
+}
+
+#[test]
+fn when_parsing_invalid_duration_then_return_error() {
+    for text in ["", "0", "0h", "h", "2x", "2h30", "-5m", "1.5h"] {
+        assert_error!(
+            DurationParser::parse(text),
+            format!("\"{}\" is not a valid duration", text)
+        );
+    }
+}
+
+#[test]
+fn when_duration_is_longer_than_maximum_then_return_error() {
+    assert_eq!(
+        DurationParser::parse("365d").unwrap(),
+        Duration::from_secs(365 * 86400)
+    );
+    for text in ["366d", "31536001", "18446744073709551615"] {
+        assert_error!(
+            DurationParser::parse(text),
+            format!("\"{}\" is longer than 365 days", text)
+        );
+    }
+}
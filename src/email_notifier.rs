@@ -0,0 +1,158 @@
+use std::error::Error;
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::Local;
+
+use crate::action_context::ActionContext;
+use crate::command_line::PROGRAM_NAME;
+use crate::email_options::EmailOptions;
+use crate::notification_queue::NotificationQueue;
+use crate::smtp_client::{SmtpClient, SmtpServer};
+
+const QUEUE_NAME: &str = "Email";
+const UNKNOWN_VALUE: &str = "unknown";
+
+pub struct EmailNotifier {
+    options: EmailOptions,
+    queue: NotificationQueue,
+}
+
+impl EmailNotifier {
+    pub fn new(options: &EmailOptions) -> Result<EmailNotifier, Box<dyn Error>> {
+        let (host, port) = match &options.server {
+            Some(server) => SmtpClient::parse_address(server)?,
+            None => Err("Email server not specified")?,
+        };
+        let from = match &options.from {
+            Some(from) => from.clone(),
+            None => Err("Email sender not specified")?,
+        };
+        let credentials = match (&options.username, &options.password) {
+            (Some(username), Some(password)) => Some((username.clone(), password.clone())),
+            (None, None) => None,
+            _ => Err("Email username and password must be specified together")?,
+        };
+        let timeout = Duration::from_secs(options.timeout_seconds as u64);
+        let server = SmtpServer {
+            host,
+            port,
+            security: options.security,
+            credentials,
+            timeout,
+        };
+        let recipients = options.to.clone();
+        let hello_name = ActionContext::hostname();
+        let queue = NotificationQueue::new(
+            QUEUE_NAME,
+            options.queue_size as usize,
+            options.retries as u32,
+            timeout,
+            move |message| {
+                return SmtpClient::send(&server, &hello_name, &from, &recipients, message);
+            },
+        )?;
+        return Ok(EmailNotifier {
+            options: options.clone(),
+            queue,
+        });
+    }
+
+    pub fn options(&self) -> &EmailOptions {
+        return &self.options;
+    }
+
+    pub fn send(&self, context: &ActionContext) -> Result<(), Box<dyn Error>> {
+        return self.queue.send(Self::compose(&self.options, context));
+    }
+
+    pub fn wait_until_idle(&self, deadline: Instant) {
+        self.queue.wait_until_idle(deadline);
+    }
+
+    pub fn compose(options: &EmailOptions, context: &ActionContext) -> String {
+        let host = Self::header_value(&context.host);
+        let message_id = format!(
+            "<{}.{}@{}>",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos(),
+            process::id(),
+            host
+        );
+        let mut message = vec![
+            format!(
+                "From: {}",
+                Self::header_value(options.from.as_deref().unwrap_or(""))
+            ),
+            format!("To: {}", Self::header_value(&options.to.join(", "))),
+            format!("Subject: {}", Self::subject(context, &host)),
+            format!("Date: {}", Local::now().to_rfc2822()),
+            format!("Message-ID: {}", message_id),
+            String::from("MIME-Version: 1.0"),
+            String::from("Content-Type: text/plain; charset=utf-8"),
+            String::from("Content-Transfer-Encoding: 8bit"),
+            String::new(),
+            Self::introduction(context),
+            String::new(),
+            format!("User: {}", context.user.as_deref().unwrap_or(UNKNOWN_VALUE)),
+            format!(
+                "Service: {}",
+                context.service.as_deref().unwrap_or(UNKNOWN_VALUE)
+            ),
+            String::new(),
+            String::from("Counter history:"),
+        ];
+        for attempt in &context.attempts {
+            message.push(format!(
+                "  {}  {}",
+                ActionContext::format_time(attempt.time),
+                attempt.counter
+            ));
+        }
+        message.push(String::new());
+        message.push(String::from("Matched log lines:"));
+        for attempt in &context.attempts {
+            message.push(format!("  {}", attempt.line));
+        }
+        return message.join("\r\n");
+    }
+
+    fn subject(context: &ActionContext, host: &str) -> String {
+        if context.is_warning() {
+            return format!(
+                "{}: warning, {} of {} failed authentication attempt(s) on {}",
+                PROGRAM_NAME, context.counter, context.max_failed_attempts, host
+            );
+        }
+        return format!(
+            "{}: {} failed authentication attempt(s) on {}, limit reached",
+            PROGRAM_NAME, context.counter, host
+        );
+    }
+
+    fn introduction(context: &ActionContext) -> String {
+        if context.is_warning() {
+            return format!(
+                "Authentication failed {} time(s) on {}, {} after {} failed attempts.",
+                context.counter,
+                context.host,
+                context.consequence(),
+                context.max_failed_attempts
+            );
+        }
+        return format!(
+            "Authentication failed {} time(s) on {}, the limit of {} failed attempts has been reached.",
+            context.counter, context.host, context.max_failed_attempts
+        );
+    }
+
+    fn header_value(value: &str) -> String {
+        return value.replace(['\r', '\n'], " ");
+    }
+}
+
+#[cfg(test)]
+#[path = "./email_notifier_tests.rs"]
+mod tests;
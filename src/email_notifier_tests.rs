@@ -0,0 +1,135 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::action::Action;
+use crate::action_context::{ActionContext, FailedAttempt};
+use crate::assert_error;
+use crate::email_notifier::EmailNotifier;
+use crate::email_options::EmailOptions;
+
+fn options() -> EmailOptions {
+    return EmailOptions {
+        server: Some(String::from("mail.example.com")),
+        from: Some(String::from("auth-monitor@example.com")),
+        to: vec![
+            String::from("admin@example.com"),
+            String::from("ops@example.com"),
+        ],
+        ..EmailOptions::default()
+    };
+}
+
+fn attempt(seconds: u64, counter: i32, line: &str) -> FailedAttempt {
+    return FailedAttempt {
+        time: UNIX_EPOCH + Duration::from_secs(seconds),
+        counter,
+        line: String::from(line),
+    };
+}
+
+#[test]
+fn when_composing_message_then_it_contains_headers_history_and_lines() {
+    let context = ActionContext {
+        host: String::from("workstation"),
+        user: Some(String::from("john")),
+        service: None,
+        counter: 2,
+        max_failed_attempts: 5,
+        limit_actions: vec![Action::PowerOff],
+        attempts: vec![
+            attempt(1_700_000_000, 1, "sudo: authentication failure"),
+            attempt(1_700_000_060, 2, "sshd: Failed password for john"),
+        ],
+    };
+
+    let message = EmailNotifier::compose(&options(), &context);
+
+    let (headers, body) = message.split_once("\r\n\r\n").unwrap();
+    let headers: Vec<&str> = headers.split("\r\n").collect();
+    assert_eq!(headers[0], "From: auth-monitor@example.com");
+    assert_eq!(headers[1], "To: admin@example.com, ops@example.com");
+    assert_eq!(
+        headers[2],
+        "Subject: auth-monitor: warning, 2 of 5 failed authentication attempt(s) on workstation"
+    );
+    assert!(headers.iter().any(|header| {
+        return header.starts_with("Date: ");
+    }));
+    assert!(headers.contains(&"Content-Type: text/plain; charset=utf-8"));
+    assert!(
+        body.contains("User: john\r\nService: unknown\r\n"),
+        "{}",
+        body
+    );
+    let history = format!(
+        "Counter history:\r\n  {}  1\r\n  {}  2\r\n",
+        ActionContext::format_time(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        ActionContext::format_time(UNIX_EPOCH + Duration::from_secs(1_700_000_060))
+    );
+    assert!(body.contains(&history), "{}", body);
+    assert!(
+        body.ends_with(
+            "Matched log lines:\r\n  sudo: authentication failure\r\n  sshd: Failed password for john"
+        ),
+        "{}",
+        body
+    );
+}
+
+#[test]
+fn when_composing_warning_or_limit_message_then_text_matches_counter() {
+    let warning = ActionContext {
+        host: String::from("workstation"),
+        user: None,
+        service: None,
+        counter: 3,
+        max_failed_attempts: 5,
+        limit_actions: vec![Action::PowerOff],
+        attempts: Vec::new(),
+    };
+    let limit = ActionContext {
+        counter: 5,
+        ..warning.clone()
+    };
+
+    let warning_message = EmailNotifier::compose(&options(), &warning);
+    let limit_message = EmailNotifier::compose(&options(), &limit);
+
+    assert!(
+        warning_message.contains("\r\n\r\nAuthentication failed 3 time(s) on workstation, the machine will power off after 5 failed attempts.\r\n"),
+        "{}",
+        warning_message
+    );
+    assert!(
+        limit_message.contains(
+            "Subject: auth-monitor: 5 failed authentication attempt(s) on workstation, limit reached\r\n"
+        ),
+        "{}",
+        limit_message
+    );
+    assert!(
+        limit_message.contains("\r\n\r\nAuthentication failed 5 time(s) on workstation, the limit of 5 failed attempts has been reached.\r\n"),
+        "{}",
+        limit_message
+    );
+}
+
+#[test]
+fn when_creating_notifier_without_sender_or_with_partial_credentials_then_return_error() {
+    let without_sender = EmailOptions {
+        from: None,
+        ..options()
+    };
+    let without_password = EmailOptions {
+        username: Some(String::from("alerts")),
+        ..options()
+    };
+
+    assert_error!(
+        EmailNotifier::new(&without_sender),
+        "Email sender not specified"
+    );
+    assert_error!(
+        EmailNotifier::new(&without_password),
+        "Email username and password must be specified together"
+    );
+}
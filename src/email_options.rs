@@ -0,0 +1,46 @@
+use std::fmt::{Display, Formatter};
+
+use crate::smtp_client::SmtpSecurity;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct EmailOptions {
+    pub server: Option<String>,
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: Option<String>,
+    pub to: Vec<String>,
+    pub timeout_seconds: i32,
+    pub retries: i32,
+    pub queue_size: i32,
+}
+
+impl Default for EmailOptions {
+    fn default() -> Self {
+        return EmailOptions {
+            server: None,
+            security: SmtpSecurity::StartTls,
+            username: None,
+            password: None,
+            from: None,
+            to: Vec::new(),
+            timeout_seconds: 10,
+            retries: 3,
+            queue_size: 16,
+        };
+    }
+}
+
+impl Display for EmailOptions {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            formatter,
+            "email-server={}, email-security={}, email-username={}, email-from={}, email-to={}",
+            self.server.as_deref().unwrap_or(""),
+            self.security,
+            self.username.as_deref().unwrap_or(""),
+            self.from.as_deref().unwrap_or(""),
+            self.to.join(",")
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::{Duration, SystemTime};
+
+use crate::auth_monitor_options::AuthMonitorOptions;
+
+pub struct FailedAttemptsCounter {
+    failed_attempts: i32,
+    options: AuthMonitorOptions,
+    last_failed_auth: SystemTime,
+}
+
+impl FailedAttemptsCounter {
+    pub fn new(options: AuthMonitorOptions) -> FailedAttemptsCounter {
+        return FailedAttemptsCounter {
+            failed_attempts: 0,
+            options,
+            last_failed_auth: SystemTime::UNIX_EPOCH,
+        };
+    }
+
+    pub fn set_options(&mut self, options: AuthMonitorOptions) {
+        self.options = options;
+    }
+
+    pub fn failed_attempts(&self) -> i32 {
+        return self.failed_attempts;
+    }
+
+    pub fn is_limit_reached(&self) -> bool {
+        return self.failed_attempts >= self.options.max_failed_attempts;
+    }
+
+    pub fn should_reset(&self, now: SystemTime) -> bool {
+        if self.failed_attempts <= 0 || self.is_limit_reached() {
+            return false;
+        }
+        let seconds_from_last_error = now
+            .duration_since(self.last_failed_auth)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+        return seconds_from_last_error > self.options.reset_after_seconds as u64;
+    }
+
+    pub fn is_stale(&self, event_time: SystemTime, now: SystemTime) -> bool {
+        let event_age = now.duration_since(event_time).unwrap_or(Duration::ZERO);
+        let max_event_age = self.options.reset_after_seconds as u64
+            + self.options.clock_skew_tolerance_seconds as u64;
+        return event_age.as_secs() > max_event_age;
+    }
+
+    pub fn is_in_future(&self, event_time: SystemTime, now: SystemTime) -> bool {
+        let event_lead = event_time.duration_since(now).unwrap_or(Duration::ZERO);
+        return event_lead.as_secs() > self.options.clock_skew_tolerance_seconds as u64;
+    }
+
+    pub fn reset(&mut self, now: SystemTime) {
+        self.failed_attempts = 0;
+        self.last_failed_auth = now;
+    }
+
+    pub fn increase(&mut self, failed_attempts: i32, now: SystemTime) {
+        self.last_failed_auth = now;
+        self.failed_attempts += failed_attempts;
+    }
+}
+
+#[cfg(test)]
+#[path = "./failed_attempts_counter_tests.rs"]
+mod tests;
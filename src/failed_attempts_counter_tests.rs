@@ -0,0 +1,73 @@
+use std::time::{Duration, SystemTime};
+
+use crate::auth_monitor_options::AuthMonitorOptions;
+use crate::failed_attempts_counter::FailedAttemptsCounter;
+
+const OPTIONS: AuthMonitorOptions = AuthMonitorOptions {
+    max_failed_attempts: 3,
+    warning_failed_attempts: 0,
+    reset_after_seconds: 60,
+    clock_skew_tolerance_seconds: 60,
+    grace_period_seconds: 0,
+};
+
+fn time(seconds: u64) -> SystemTime {
+    return SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+}
+
+#[test]
+fn when_failed_attempts_reach_max_then_limit_is_reached() {
+    let mut counter = FailedAttemptsCounter::new(OPTIONS);
+    assert!(!counter.is_limit_reached());
+
+    counter.increase(2, time(100));
+    assert_eq!(counter.failed_attempts(), 2);
+    assert!(!counter.is_limit_reached());
+
+    counter.increase(1, time(101));
+    assert_eq!(counter.failed_attempts(), 3);
+    assert!(counter.is_limit_reached());
+}
+
+#[test]
+fn when_reset_time_has_passed_since_last_failure_then_counter_should_be_reset() {
+    let mut counter = FailedAttemptsCounter::new(OPTIONS);
+    assert!(!counter.should_reset(time(1000)));
+
+    counter.increase(1, time(100));
+    assert!(!counter.should_reset(time(160)));
+    assert!(counter.should_reset(time(161)));
+
+    counter.reset(time(161));
+    assert_eq!(counter.failed_attempts(), 0);
+    assert!(!counter.should_reset(time(1000)));
+}
+
+#[test]
+fn when_limit_is_reached_then_counter_is_never_reset_by_time() {
+    let mut counter = FailedAttemptsCounter::new(OPTIONS);
+    counter.increase(OPTIONS.max_failed_attempts, time(100));
+    assert!(!counter.should_reset(time(100_000)));
+}
+
+#[test]
+fn when_event_is_older_than_reset_time_and_tolerance_then_it_is_stale() {
+    let counter = FailedAttemptsCounter::new(OPTIONS);
+    let now = time(1000);
+    let max_age = (OPTIONS.reset_after_seconds + OPTIONS.clock_skew_tolerance_seconds) as u64;
+    assert!(!counter.is_stale(time(1000), now));
+    assert!(!counter.is_stale(time(1000 - max_age), now));
+    assert!(counter.is_stale(time(1000 - max_age - 1), now));
+    assert!(!counter.is_stale(time(2000), now));
+}
+
+#[test]
+fn when_event_is_later_than_now_and_tolerance_then_it_is_in_future() {
+    let counter = FailedAttemptsCounter::new(OPTIONS);
+    let now = time(1000);
+    let tolerance = OPTIONS.clock_skew_tolerance_seconds as u64;
+    assert!(!counter.is_in_future(time(1000), now));
+    assert!(!counter.is_in_future(time(1000 + tolerance), now));
+    assert!(counter.is_in_future(time(1000 + tolerance + 1), now));
+    assert!(!counter.is_in_future(time(0), now));
+}
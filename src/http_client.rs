@@ -0,0 +1,169 @@
+use std::error::Error;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::time::Duration;
+
+use native_tls::TlsConnector;
+
+use crate::command_line::{PROGRAM_NAME, PROGRAM_VERSION};
+
+const HTTP_SCHEME: &str = "http://";
+const HTTPS_SCHEME: &str = "https://";
+const HTTP_PORT: u16 = 80;
+const HTTPS_PORT: u16 = 443;
+const DEFAULT_PATH: &str = "/";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct HttpUrl {
+    pub secure: bool,
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl FromStr for HttpUrl {
+    type Err = Box<dyn Error>;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        let (secure, rest) = if let Some(rest) = url.strip_prefix(HTTP_SCHEME) {
+            (false, rest)
+        } else if let Some(rest) = url.strip_prefix(HTTPS_SCHEME) {
+            (true, rest)
+        } else {
+            Err(format!("{} is not an http or https URL", url))?
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(position) => rest.split_at(position),
+            None => (rest, DEFAULT_PATH),
+        };
+        let default_port = Self::default_port(secure);
+        let (host, port) = match Self::split_authority(authority) {
+            (host, Some(port)) => match port.parse::<u16>() {
+                Ok(port) => (host, port),
+                Err(_) => Err(format!("{} has an invalid port", url))?,
+            },
+            (host, None) => (host, default_port),
+        };
+        if host.is_empty() || host.contains(['@', '[', ']']) {
+            Err(format!("{} has an invalid host", url))?;
+        }
+        return Ok(HttpUrl {
+            secure,
+            host: String::from(host),
+            port,
+            path: String::from(path),
+        });
+    }
+}
+
+impl HttpUrl {
+    /// The URL without its path and query, which often carry a secret token,
+    /// for logs and reports.
+    pub fn redacted(&self) -> String {
+        let scheme = match self.secure {
+            true => HTTPS_SCHEME,
+            false => HTTP_SCHEME,
+        };
+        return format!("{}{}/...", scheme, self.host_header());
+    }
+
+    fn host_header(&self) -> String {
+        let host = match self.host.contains(':') {
+            true => format!("[{}]", self.host),
+            false => self.host.clone(),
+        };
+        return match self.port == Self::default_port(self.secure) {
+            true => host,
+            false => format!("{}:{}", host, self.port),
+        };
+    }
+
+    fn default_port(secure: bool) -> u16 {
+        return match secure {
+            true => HTTPS_PORT,
+            false => HTTP_PORT,
+        };
+    }
+
+    fn split_authority(authority: &str) -> (&str, Option<&str>) {
+        if let Some((host, rest)) = authority.strip_prefix('[').and_then(|authority| {
+            return authority.split_once(']');
+        }) {
+            return (host, rest.strip_prefix(':'));
+        }
+        return match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        };
+    }
+}
+
+pub struct HttpClient {}
+
+impl HttpClient {
+    pub fn post_json(url: &HttpUrl, body: &str, timeout: Duration) -> Result<(), Box<dyn Error>> {
+        let stream = Self::connect(url, timeout)?;
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}/{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            url.path,
+            url.host_header(),
+            PROGRAM_NAME,
+            PROGRAM_VERSION,
+            body.len(),
+            body
+        );
+        let status_line = match url.secure {
+            true => {
+                let connector = TlsConnector::new()?;
+                let stream = connector.connect(&url.host, stream)?;
+                Self::send_request(stream, &request)?
+            }
+            false => Self::send_request(stream, &request)?,
+        };
+        let status = status_line.split_whitespace().nth(1).unwrap_or("");
+        if !status.starts_with('2') {
+            Err(format!("Unexpected response \"{}\"", status_line.trim()))?;
+        }
+        return Ok(());
+    }
+
+    fn connect(url: &HttpUrl, timeout: Duration) -> Result<TcpStream, Box<dyn Error>> {
+        let mut last_error = None;
+        for address in (url.host.as_str(), url.port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(timeout))?;
+                    stream.set_write_timeout(Some(timeout))?;
+                    return Ok(stream);
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+        return match last_error {
+            Some(error) => Err(format!(
+                "Unable to connect to {}:{}: {}",
+                url.host, url.port, error
+            ))?,
+            None => Err(format!("Unable to resolve {}", url.host))?,
+        };
+    }
+
+    fn send_request(
+        mut stream: impl Read + Write,
+        request: &str,
+    ) -> Result<String, Box<dyn Error>> {
+        stream.write_all(request.as_bytes())?;
+        stream.flush()?;
+        let mut status_line = String::new();
+        BufReader::new(stream).read_line(&mut status_line)?;
+        if status_line.is_empty() {
+            Err("Connection closed without response")?;
+        }
+        return Ok(status_line);
+    }
+}
+
+#[cfg(test)]
+#[path = "./http_client_tests.rs"]
+mod tests;
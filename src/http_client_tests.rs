@@ -0,0 +1,66 @@
+use crate::assert_error;
+use crate::http_client::HttpUrl;
+
+fn url(secure: bool, host: &str, port: u16, path: &str) -> HttpUrl {
+    return HttpUrl {
+        secure,
+        host: String::from(host),
+        port,
+        path: String::from(path),
+    };
+}
+
+#[test]
+fn when_parsing_url_then_scheme_host_port_and_path_are_returned() {
+    let urls = [
+        ("http://localhost", url(false, "localhost", 80, "/")),
+        (
+            "https://hooks.example.com/alert?x=1",
+            url(true, "hooks.example.com", 443, "/alert?x=1"),
+        ),
+        (
+            "http://127.0.0.1:8080/hook",
+            url(false, "127.0.0.1", 8080, "/hook"),
+        ),
+        ("http://[::1]:8080/hook", url(false, "::1", 8080, "/hook")),
+        ("https://[::1]", url(true, "::1", 443, "/")),
+    ];
+    for (text, expected) in urls {
+        assert_eq!(text.parse::<HttpUrl>().unwrap(), expected, "{}", text);
+    }
+}
+
+#[test]
+fn when_parsing_invalid_url_then_return_error() {
+    let urls = [
+        (
+            "ftp://example.com",
+            "ftp://example.com is not an http or https URL",
+        ),
+        (
+            "http://example.com:http/",
+            "http://example.com:http/ has an invalid port",
+        ),
+        ("http:///hook", "http:///hook has an invalid host"),
+        (
+            "http://user@example.com/",
+            "http://user@example.com/ has an invalid host",
+        ),
+    ];
+    for (text, expected) in urls {
+        assert_error!(text.parse::<HttpUrl>(), expected);
+    }
+}
+
+#[test]
+fn when_redacting_url_then_only_scheme_and_host_are_kept() {
+    let slack = "https://hooks.slack.com/services/T000/B000/secret"
+        .parse::<HttpUrl>()
+        .unwrap();
+    let local = "http://127.0.0.1:8080/hook?token=secret"
+        .parse::<HttpUrl>()
+        .unwrap();
+
+    assert_eq!(slack.redacted(), "https://hooks.slack.com/...");
+    assert_eq!(local.redacted(), "http://127.0.0.1:8080/...");
+}
@@ -0,0 +1,49 @@
+pub struct Json {}
+
+impl Json {
+    pub fn string(value: &str) -> String {
+        let mut json = String::from("\"");
+        for character in value.chars() {
+            match character {
+                '"' => json.push_str("\\\""),
+                '\\' => json.push_str("\\\\"),
+                '\n' => json.push_str("\\n"),
+                '\r' => json.push_str("\\r"),
+                '\t' => json.push_str("\\t"),
+                character if character.is_control() => {
+                    json.push_str(&format!("\\u{:04x}", character as u32));
+                }
+                character => json.push(character),
+            }
+        }
+        json.push('"');
+        return json;
+    }
+
+    pub fn optional_string(value: Option<&str>) -> String {
+        return match value {
+            Some(value) => Self::string(value),
+            None => String::from("null"),
+        };
+    }
+
+    pub fn string_array(values: &[String]) -> String {
+        let items: Vec<String> = values
+            .iter()
+            .map(|value| {
+                return Self::string(value);
+            })
+            .collect();
+        return format!("[{}]", items.join(","));
+    }
+
+    pub fn object(fields: &[(&str, String)]) -> String {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| {
+                return format!("{}:{}", Self::string(name), value);
+            })
+            .collect();
+        return format!("{{{}}}", fields.join(","));
+    }
+}
@@ -0,0 +1,68 @@
+use std::error::Error;
+use std::fs;
+use std::io;
+
+pub const PROCESS_STATUS_PATH: &str = "/proc/self/status";
+const EFFECTIVE_CAPABILITIES_KEY: &str = "CapEff:";
+/// Capability number of CAP_SYS_BOOT in linux/capability.h.
+const CAP_SYS_BOOT: u32 = 22;
+
+/// Powers off with the reboot(2) system call, without systemd, sudo or the
+/// D-Bus system bus. Requires the CAP_SYS_BOOT capability, which root has and
+/// which can be given to the daemon with AmbientCapabilities or setcap.
+pub struct KernelPower {}
+
+impl KernelPower {
+    pub fn check(status_path: &str) -> Result<(), Box<dyn Error>> {
+        let status = match fs::read_to_string(status_path) {
+            Ok(status) => status,
+            Err(error) => Err(format!("Unable to read {}: {}", status_path, error))?,
+        };
+        if !Self::has_capability(&status, CAP_SYS_BOOT)? {
+            Err("CAP_SYS_BOOT capability is missing")?;
+        }
+        return Ok(());
+    }
+
+    /// Checks the capability first, so that a missing one is reported
+    /// instead of EPERM, then writes the file system buffers to disk, since
+    /// reboot(2) does not.
+    pub fn power_off(status_path: &str) -> Result<(), Box<dyn Error>> {
+        Self::check(status_path)?;
+        unsafe { libc::sync() };
+        return Self::reboot();
+    }
+
+    /// Powers off without writing the buffers, for when disks may no longer
+    /// accept writes and syncing would block forever.
+    pub fn power_off_without_sync(status_path: &str) -> Result<(), Box<dyn Error>> {
+        Self::check(status_path)?;
+        return Self::reboot();
+    }
+
+    fn reboot() -> Result<(), Box<dyn Error>> {
+        if unsafe { libc::reboot(libc::RB_POWER_OFF) } != 0 {
+            Err(format!("reboot failed: {}", io::Error::last_os_error()))?;
+        }
+        return Ok(());
+    }
+
+    /// Reads the effective capability set from the content of
+    /// /proc/[pid]/status.
+    pub fn has_capability(status: &str, capability: u32) -> Result<bool, Box<dyn Error>> {
+        let value = match status.lines().find_map(|line| {
+            return line.strip_prefix(EFFECTIVE_CAPABILITIES_KEY);
+        }) {
+            Some(value) => value.trim(),
+            None => Err("Effective capabilities not found in process status")?,
+        };
+        return match u64::from_str_radix(value, 16) {
+            Ok(capabilities) => Ok(capabilities & (1 << capability) != 0),
+            Err(_) => Err(format!("Invalid effective capabilities {}", value))?,
+        };
+    }
+}
+
+#[cfg(test)]
+#[path = "./kernel_power_tests.rs"]
+mod tests;
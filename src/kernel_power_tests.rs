@@ -0,0 +1,75 @@
+use std::env::temp_dir;
+use std::fs::{remove_file, write};
+
+use crate::assert_error;
+use crate::kernel_power::KernelPower;
+
+const STATUS_WITHOUT_CAPABILITIES: &str =
+    "Name:\tauth-monitor\nUid:\t999\t999\t999\t999\nCapInh:\t0000000000000000\nCapPrm:\t0000000000000000\nCapEff:\t0000000000000000\n";
+const STATUS_WITH_SYS_BOOT: &str =
+    "Name:\tauth-monitor\nUid:\t999\t999\t999\t999\nCapInh:\t0000000000000000\nCapPrm:\t0000000000400000\nCapEff:\t0000000000400000\n";
+
+fn write_status(name: &str, status: &str) -> String {
+    let path = temp_dir().join(format!(
+        "auth-monitor-{}-{}.status",
+        name,
+        std::process::id()
+    ));
+    write(&path, status).expect("Error writing test status");
+    return String::from(path.to_str().unwrap());
+}
+
+#[test]
+fn when_capability_is_effective_then_has_capability_returns_true() {
+    assert!(KernelPower::has_capability(STATUS_WITH_SYS_BOOT, 22).unwrap());
+    assert!(!KernelPower::has_capability(STATUS_WITH_SYS_BOOT, 21).unwrap());
+    assert!(!KernelPower::has_capability(STATUS_WITHOUT_CAPABILITIES, 22).unwrap());
+    assert!(KernelPower::has_capability("CapEff:\t000001ffffffffff\n", 22).unwrap());
+}
+
+#[test]
+fn when_status_has_no_valid_capabilities_then_return_error() {
+    assert_error!(
+        KernelPower::has_capability("Name:\tauth-monitor\n", 22),
+        "Effective capabilities not found in process status"
+    );
+    assert_error!(
+        KernelPower::has_capability("CapEff:\tzz\n", 22),
+        "Invalid effective capabilities zz"
+    );
+}
+
+#[test]
+fn when_checking_with_sys_boot_then_check_succeeds() {
+    let path = write_status("with-sys-boot", STATUS_WITH_SYS_BOOT);
+
+    let result = KernelPower::check(&path);
+
+    remove_file(&path).unwrap();
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn when_sys_boot_is_missing_then_power_off_fails_before_calling_reboot() {
+    let path = write_status("without-sys-boot", STATUS_WITHOUT_CAPABILITIES);
+
+    let result = KernelPower::power_off(&path);
+
+    remove_file(&path).unwrap();
+    assert_error!(result, "CAP_SYS_BOOT capability is missing");
+}
+
+#[test]
+fn when_status_cannot_be_read_then_return_error() {
+    assert_error!(
+        KernelPower::power_off("/nonexistent/status"),
+        "Unable to read /nonexistent/status: No such file or directory (os error 2)"
+    );
+}
+
+#[test]
+fn when_checking_own_process_then_capabilities_are_read() {
+    let status = std::fs::read_to_string("/proc/self/status").unwrap();
+
+    assert!(KernelPower::has_capability(&status, 22).is_ok());
+}
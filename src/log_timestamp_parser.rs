@@ -0,0 +1,57 @@
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone};
+
+const SYSLOG_TIMESTAMP_LENGTH: usize = "Feb 10 14:27:16".len();
+const SYSLOG_TIMESTAMP_FORMAT: &str = "%Y %b %e %H:%M:%S";
+const SYSLOG_YEAR_ROLLOVER_MARGIN: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub struct LogTimestampParser {}
+
+impl LogTimestampParser {
+    pub fn new() -> LogTimestampParser {
+        return LogTimestampParser {};
+    }
+
+    /// Parses timestamp at the beginning of the line. Classic syslog timestamps do not contain
+    /// year nor time zone, so local time zone is assumed and the year is chosen so that the
+    /// timestamp is not later than `reference_time`.
+    pub fn parse(&self, line: &str, reference_time: SystemTime) -> Option<SystemTime> {
+        return match self.parse_iso_timestamp(line) {
+            Some(time) => Some(time),
+            None => self.parse_syslog_timestamp(line, reference_time),
+        };
+    }
+
+    fn parse_iso_timestamp(&self, line: &str) -> Option<SystemTime> {
+        let timestamp = line.split_whitespace().next()?;
+        return match DateTime::parse_from_rfc3339(timestamp) {
+            Ok(date_time) => Some(SystemTime::from(date_time)),
+            Err(_) => None,
+        };
+    }
+
+    fn parse_syslog_timestamp(&self, line: &str, reference_time: SystemTime) -> Option<SystemTime> {
+        let timestamp = line.get(..SYSLOG_TIMESTAMP_LENGTH)?;
+        let reference_year = DateTime::<Local>::from(reference_time).year();
+        let latest_time = reference_time + SYSLOG_YEAR_ROLLOVER_MARGIN;
+        if let Some(time) = Self::parse_syslog_timestamp_in_year(timestamp, reference_year) {
+            if time <= latest_time {
+                return Some(time);
+            }
+        }
+        return Self::parse_syslog_timestamp_in_year(timestamp, reference_year - 1);
+    }
+
+    fn parse_syslog_timestamp_in_year(timestamp: &str, year: i32) -> Option<SystemTime> {
+        let timestamp_with_year = format!("{} {}", year, timestamp);
+        let date_time =
+            NaiveDateTime::parse_from_str(&timestamp_with_year, SYSLOG_TIMESTAMP_FORMAT).ok()?;
+        let local_date_time = Local.from_local_datetime(&date_time).earliest()?;
+        return Some(SystemTime::from(local_date_time));
+    }
+}
+
+#[cfg(test)]
+#[path = "./log_timestamp_parser_tests.rs"]
+mod tests;
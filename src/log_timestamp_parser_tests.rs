@@ -0,0 +1,75 @@
+use std::time::{Duration, SystemTime};
+
+use chrono::{Local, TimeZone};
+
+use crate::log_timestamp_parser::LogTimestampParser;
+
+fn local_time(year: i32, month: u32, day: u32, hour: u32, min: u32, sec: u32) -> SystemTime {
+    let date_time = Local
+        .with_ymd_and_hms(year, month, day, hour, min, sec)
+        .earliest()
+        .expect("Invalid local time");
+    return SystemTime::from(date_time);
+}
+
+#[test]
+fn when_line_starts_with_iso_8601_timestamp_then_return_its_time() {
+    let parser = LogTimestampParser::new();
+    let line = "2024-02-10T14:27:16.721168+01:00 workstation sddm-helper: pam_unix(sddm-greeter:session): session opened";
+    let expected = SystemTime::UNIX_EPOCH + Duration::from_micros(1_707_571_636_721_168);
+    assert_eq!(parser.parse(line, SystemTime::now()), Some(expected));
+}
+
+#[test]
+fn when_line_starts_with_syslog_timestamp_then_return_local_time_in_reference_year() {
+    let parser = LogTimestampParser::new();
+    let reference_time = local_time(2024, 6, 1, 12, 0, 0);
+    let lines = [
+        (
+            "Feb 10 14:27:16 workstation unix_chkpwd[222793]: password check failed for user (john)",
+            local_time(2024, 2, 10, 14, 27, 16),
+        ),
+        (
+            "Mar  2 08:01:05 workstation sudo: pam_unix(sudo:auth): authentication failure",
+            local_time(2024, 3, 2, 8, 1, 5),
+        ),
+    ];
+    for (line, expected) in lines {
+        assert_eq!(
+            parser.parse(line, reference_time),
+            Some(expected),
+            "Line: {}",
+            line
+        );
+    }
+}
+
+#[test]
+fn when_syslog_timestamp_is_later_than_reference_time_then_return_time_in_previous_year() {
+    let parser = LogTimestampParser::new();
+    let reference_time = local_time(2024, 1, 2, 10, 0, 0);
+    let line = "Dec 31 23:59:59 workstation unix_chkpwd[222793]: password check failed";
+    let expected = local_time(2023, 12, 31, 23, 59, 59);
+    assert_eq!(parser.parse(line, reference_time), Some(expected));
+}
+
+#[test]
+fn when_line_does_not_start_with_timestamp_then_return_none() {
+    let parser = LogTimestampParser::new();
+    let lines = [
+        "",
+        "workstation unix_chkpwd[222793]: password check failed for user (john)",
+        "2024-02-10 workstation unix_chkpwd[222793]: password check failed for user (john)",
+        "2024-02-30T14:27:16+01:00 workstation unix_chkpwd[222793]: password check failed",
+        "Feb 30 14:27:16 workstation unix_chkpwd[222793]: password check failed",
+        "Foo 10 14:27:16 workstation unix_chkpwd[222793]: password check failed",
+    ];
+    for line in lines {
+        assert_eq!(
+            parser.parse(line, SystemTime::now()),
+            None,
+            "Line: {}",
+            line
+        );
+    }
+}
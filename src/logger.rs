@@ -0,0 +1,234 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use chrono::{Local, SecondsFormat};
+
+use crate::json::Json;
+
+const ERROR_LEVEL: &str = "error";
+const WARNING_LEVEL: &str = "warning";
+const INFO_LEVEL: &str = "info";
+const DEBUG_LEVEL: &str = "debug";
+
+const TEXT_FORMAT: &str = "text";
+const JSON_FORMAT: &str = "json";
+
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+static LOG_FORMAT: AtomicU8 = AtomicU8::new(LogFormat::Text as u8);
+
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+pub enum LogLevel {
+    Error,
+    Warning,
+    Info,
+    Debug,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+pub struct Logger {}
+
+impl Logger {
+    pub fn configure(level: LogLevel, format: LogFormat) {
+        LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+        LOG_FORMAT.store(format as u8, Ordering::Relaxed);
+    }
+
+    fn level() -> LogLevel {
+        return match LOG_LEVEL.load(Ordering::Relaxed) {
+            0 => LogLevel::Error,
+            1 => LogLevel::Warning,
+            2 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        };
+    }
+
+    fn format() -> LogFormat {
+        return match LOG_FORMAT.load(Ordering::Relaxed) {
+            0 => LogFormat::Text,
+            _ => LogFormat::Json,
+        };
+    }
+}
+
+/// A single log line. The event kind is a stable identifier that can be used
+/// for filtering, while the message is meant to be read by humans.
+pub struct LogEntry {
+    level: LogLevel,
+    event: &'static str,
+    message: String,
+    counter: Option<i32>,
+    user: Option<String>,
+    service: Option<String>,
+    file: Option<String>,
+}
+
+impl LogEntry {
+    pub fn new(level: LogLevel, event: &'static str, message: &str) -> LogEntry {
+        return LogEntry {
+            level,
+            event,
+            message: String::from(message),
+            counter: None,
+            user: None,
+            service: None,
+            file: None,
+        };
+    }
+
+    pub fn error(event: &'static str, message: &str) -> LogEntry {
+        return Self::new(LogLevel::Error, event, message);
+    }
+
+    pub fn warning(event: &'static str, message: &str) -> LogEntry {
+        return Self::new(LogLevel::Warning, event, message);
+    }
+
+    pub fn info(event: &'static str, message: &str) -> LogEntry {
+        return Self::new(LogLevel::Info, event, message);
+    }
+
+    pub fn debug(event: &'static str, message: &str) -> LogEntry {
+        return Self::new(LogLevel::Debug, event, message);
+    }
+
+    pub fn counter(mut self, counter: i32) -> LogEntry {
+        self.counter = Some(counter);
+        return self;
+    }
+
+    pub fn user(mut self, user: Option<&str>) -> LogEntry {
+        self.user = user.map(String::from);
+        return self;
+    }
+
+    pub fn service(mut self, service: Option<&str>) -> LogEntry {
+        self.service = service.map(String::from);
+        return self;
+    }
+
+    pub fn file(mut self, file: &str) -> LogEntry {
+        self.file = Some(String::from(file));
+        return self;
+    }
+
+    pub fn log(self) {
+        if self.level > Logger::level() {
+            return;
+        }
+        let line = self.format(Logger::format());
+        match self.level {
+            LogLevel::Error | LogLevel::Warning => eprintln!("{}", line),
+            LogLevel::Info | LogLevel::Debug => println!("{}", line),
+        }
+    }
+
+    pub fn format(&self, format: LogFormat) -> String {
+        return match format {
+            LogFormat::Text => self.format_text(),
+            LogFormat::Json => self.format_json(),
+        };
+    }
+
+    fn format_text(&self) -> String {
+        let mut context = Vec::new();
+        if let Some(counter) = self.counter {
+            context.push(format!("counter={}", counter));
+        }
+        if let Some(user) = &self.user {
+            context.push(format!("user={}", user));
+        }
+        if let Some(service) = &self.service {
+            context.push(format!("service={}", service));
+        }
+        if let Some(file) = &self.file {
+            context.push(format!("file={}", file));
+        }
+        let level = self.level.to_string().to_uppercase();
+        return match context.is_empty() {
+            true => format!("{:<7} {}", level, self.message),
+            false => format!("{:<7} {} ({})", level, self.message, context.join(", ")),
+        };
+    }
+
+    fn format_json(&self) -> String {
+        let timestamp = Local::now().to_rfc3339_opts(SecondsFormat::Micros, false);
+        let mut fields = vec![
+            ("timestamp", Json::string(&timestamp)),
+            ("level", Json::string(&self.level.to_string())),
+            ("event", Json::string(self.event)),
+            ("message", Json::string(&self.message)),
+        ];
+        if let Some(counter) = self.counter {
+            fields.push(("counter", counter.to_string()));
+        }
+        if let Some(user) = &self.user {
+            fields.push(("user", Json::string(user)));
+        }
+        if let Some(service) = &self.service {
+            fields.push(("service", Json::string(service)));
+        }
+        if let Some(file) = &self.file {
+            fields.push(("file", Json::string(file)));
+        }
+        return Json::object(&fields);
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LogLevel::Error => ERROR_LEVEL,
+            LogLevel::Warning => WARNING_LEVEL,
+            LogLevel::Info => INFO_LEVEL,
+            LogLevel::Debug => DEBUG_LEVEL,
+        };
+        return write!(formatter, "{}", name);
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = Box<dyn Error>;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return match name {
+            ERROR_LEVEL => Ok(LogLevel::Error),
+            WARNING_LEVEL => Ok(LogLevel::Warning),
+            INFO_LEVEL => Ok(LogLevel::Info),
+            DEBUG_LEVEL => Ok(LogLevel::Debug),
+            _ => Err(format!("Unknown log level {}", name))?,
+        };
+    }
+}
+
+impl Display for LogFormat {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LogFormat::Text => TEXT_FORMAT,
+            LogFormat::Json => JSON_FORMAT,
+        };
+        return write!(formatter, "{}", name);
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = Box<dyn Error>;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return match name {
+            TEXT_FORMAT => Ok(LogFormat::Text),
+            JSON_FORMAT => Ok(LogFormat::Json),
+            _ => Err(format!("Unknown log format {}", name))?,
+        };
+    }
+}
+
+#[cfg(test)]
+#[path = "./logger_tests.rs"]
+mod tests;
@@ -0,0 +1,49 @@
+use crate::assert_error;
+use crate::logger::{LogEntry, LogFormat, LogLevel};
+
+#[test]
+fn when_formatting_entry_as_text_then_context_follows_message() {
+    let entry = LogEntry::info("auth_failed", "Authentication failed 3 time(s)")
+        .counter(3)
+        .user(Some("john"))
+        .service(None)
+        .file("/var/log/auth.log");
+    assert_eq!(
+        entry.format(LogFormat::Text),
+        "INFO    Authentication failed 3 time(s) (counter=3, user=john, file=/var/log/auth.log)"
+    );
+    assert_eq!(
+        LogEntry::warning("file_error", "Unable to open monitored file").format(LogFormat::Text),
+        "WARNING Unable to open monitored file"
+    );
+}
+
+#[test]
+fn when_formatting_entry_as_json_then_fields_are_escaped() {
+    let entry = LogEntry::error("action_error", "Unable to shutdown: \"sudo\"\n")
+        .counter(5)
+        .user(Some("jo\\hn"))
+        .service(Some("sshd"));
+    let json = entry.format(LogFormat::Json);
+    assert!(json.starts_with("{\"timestamp\":\""));
+    assert!(json.ends_with(
+        "\"level\":\"error\",\"event\":\"action_error\",\"message\":\"Unable to shutdown: \\\"sudo\\\"\\n\",\"counter\":5,\"user\":\"jo\\\\hn\",\"service\":\"sshd\"}"
+    ));
+}
+
+#[test]
+fn when_parsing_log_level_and_format_then_names_match_display() {
+    for level in [
+        LogLevel::Error,
+        LogLevel::Warning,
+        LogLevel::Info,
+        LogLevel::Debug,
+    ] {
+        assert_eq!(level.to_string().parse::<LogLevel>().unwrap(), level);
+    }
+    for format in [LogFormat::Text, LogFormat::Json] {
+        assert_eq!(format.to_string().parse::<LogFormat>().unwrap(), format);
+    }
+    assert_error!("verbose".parse::<LogLevel>(), "Unknown log level verbose");
+    assert_error!("xml".parse::<LogFormat>(), "Unknown log format xml");
+}
@@ -0,0 +1,199 @@
+use std::error::Error;
+
+use crate::dbus_connection::DBusConnection;
+use crate::dbus_message::DBusValue;
+
+pub const LOGIND_NAME: &str = "org.freedesktop.login1";
+pub const LOGIND_PATH: &str = "/org/freedesktop/login1";
+pub const MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+const PEER_INTERFACE: &str = "org.freedesktop.DBus.Peer";
+
+pub const CAN_POWER_OFF_METHOD: &str = "CanPowerOff";
+pub const CAN_SUSPEND_METHOD: &str = "CanSuspend";
+const CAN_RESULT_YES: &str = "yes";
+const CAN_RESULT_CHALLENGE: &str = "challenge";
+
+const GRAPHICAL_SESSION_TYPES: [&str; 3] = ["x11", "wayland", "mir"];
+const USER_SESSION_CLASS: &str = "user";
+
+/// An inhibitor lock, such as one taken by an application to finish writing
+/// a file before the machine shuts down.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LogindInhibitor {
+    /// Colon separated operations, such as "shutdown:sleep".
+    pub what: String,
+    pub who: String,
+    pub why: String,
+    /// "block" or "block-weak" refuse the operation, "delay" delays it.
+    pub mode: String,
+    pub uid: u32,
+    pub pid: u32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct LogindSession {
+    pub id: String,
+    pub uid: u32,
+    pub user: String,
+    pub session_type: String,
+    pub class: String,
+    pub active: bool,
+}
+
+/// Client of the systemd-logind manager on the system bus.
+pub struct Logind {}
+
+impl Logind {
+    pub fn sessions(connection: &mut DBusConnection) -> Result<Vec<LogindSession>, Box<dyn Error>> {
+        let reply = connection.call(
+            LOGIND_NAME,
+            LOGIND_PATH,
+            MANAGER_INTERFACE,
+            "ListSessions",
+            Vec::new(),
+        )?;
+        let mut sessions = Vec::new();
+        for item in reply.first().map(DBusValue::items).unwrap_or_default() {
+            let (id, uid, user, path) = match item.items() {
+                [id, DBusValue::UInt32(uid), user, _, path] => (id, *uid, user, path),
+                _ => Err("Unexpected ListSessions reply")?,
+            };
+            let properties = connection.call(
+                LOGIND_NAME,
+                path.as_str().unwrap_or_default(),
+                PROPERTIES_INTERFACE,
+                "GetAll",
+                vec![DBusValue::string(SESSION_INTERFACE)],
+            )?;
+            let properties = match properties.first() {
+                Some(properties) => properties,
+                None => Err("Unexpected GetAll reply")?,
+            };
+            sessions.push(LogindSession {
+                id: String::from(id.as_str().unwrap_or_default()),
+                uid,
+                user: String::from(user.as_str().unwrap_or_default()),
+                session_type: Self::string_property(properties, "Type"),
+                class: Self::string_property(properties, "Class"),
+                active: properties
+                    .get("Active")
+                    .and_then(DBusValue::as_bool)
+                    .unwrap_or(false),
+            });
+        }
+        return Ok(sessions);
+    }
+
+    pub fn inhibitors(
+        connection: &mut DBusConnection,
+    ) -> Result<Vec<LogindInhibitor>, Box<dyn Error>> {
+        let reply = connection.call(
+            LOGIND_NAME,
+            LOGIND_PATH,
+            MANAGER_INTERFACE,
+            "ListInhibitors",
+            Vec::new(),
+        )?;
+        let mut inhibitors = Vec::new();
+        for item in reply.first().map(DBusValue::items).unwrap_or_default() {
+            let inhibitor = match item.items() {
+                [what, who, why, mode, DBusValue::UInt32(uid), DBusValue::UInt32(pid)] => {
+                    LogindInhibitor {
+                        what: String::from(what.as_str().unwrap_or_default()),
+                        who: String::from(who.as_str().unwrap_or_default()),
+                        why: String::from(why.as_str().unwrap_or_default()),
+                        mode: String::from(mode.as_str().unwrap_or_default()),
+                        uid: *uid,
+                        pid: *pid,
+                    }
+                }
+                _ => Err("Unexpected ListInhibitors reply")?,
+            };
+            inhibitors.push(inhibitor);
+        }
+        return Ok(inhibitors);
+    }
+
+    /// Powers off without asking for authentication, polkit decides whether
+    /// the caller is allowed to.
+    pub fn power_off(connection: &mut DBusConnection) -> Result<(), Box<dyn Error>> {
+        return Self::call_manager(connection, "PowerOff", vec![DBusValue::Boolean(false)]);
+    }
+
+    pub fn suspend(connection: &mut DBusConnection) -> Result<(), Box<dyn Error>> {
+        return Self::call_manager(connection, "Suspend", vec![DBusValue::Boolean(false)]);
+    }
+
+    pub fn lock_sessions(connection: &mut DBusConnection) -> Result<(), Box<dyn Error>> {
+        return Self::call_manager(connection, "LockSessions", Vec::new());
+    }
+
+    /// Checks with one of the Can* methods that the operation is allowed
+    /// without authentication.
+    pub fn check_allowed(
+        connection: &mut DBusConnection,
+        method: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let reply = connection.call(
+            LOGIND_NAME,
+            LOGIND_PATH,
+            MANAGER_INTERFACE,
+            method,
+            Vec::new(),
+        )?;
+        return match reply.first().and_then(DBusValue::as_str) {
+            Some(CAN_RESULT_YES) => Ok(()),
+            Some(CAN_RESULT_CHALLENGE) => Err(format!(
+                "{} requires authentication, polkit does not allow it",
+                method
+            ))?,
+            Some(result) => Err(format!("{} returned {}", method, result))?,
+            None => Err(format!("Unexpected {} reply", method))?,
+        };
+    }
+
+    pub fn ping(connection: &mut DBusConnection) -> Result<(), Box<dyn Error>> {
+        connection.call(LOGIND_NAME, LOGIND_PATH, PEER_INTERFACE, "Ping", Vec::new())?;
+        return Ok(());
+    }
+
+    fn call_manager(
+        connection: &mut DBusConnection,
+        method: &str,
+        body: Vec<DBusValue>,
+    ) -> Result<(), Box<dyn Error>> {
+        connection.call(LOGIND_NAME, LOGIND_PATH, MANAGER_INTERFACE, method, body)?;
+        return Ok(());
+    }
+
+    fn string_property(properties: &DBusValue, name: &str) -> String {
+        return String::from(
+            properties
+                .get(name)
+                .and_then(DBusValue::as_str)
+                .unwrap_or_default(),
+        );
+    }
+}
+
+impl LogindSession {
+    pub fn is_active_graphical(&self) -> bool {
+        return self.active
+            && self.class == USER_SESSION_CLASS
+            && GRAPHICAL_SESSION_TYPES.contains(&self.session_type.as_str());
+    }
+}
+
+impl LogindInhibitor {
+    pub fn inhibits(&self, operation: &str) -> bool {
+        return self.what.split(':').any(|what| {
+            return what == operation;
+        });
+    }
+
+    pub fn is_blocking(&self) -> bool {
+        return self.mode.starts_with("block");
+    }
+}
@@ -0,0 +1,133 @@
+use std::error::Error;
+use std::fs;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::action::Action;
+use crate::command_runner::CommandRunner;
+use crate::dbus_connection::DBusConnection;
+use crate::kernel_power::{KernelPower, PROCESS_STATUS_PATH};
+use crate::logger::LogEntry;
+use crate::logind::Logind;
+use crate::luks_suspend_options::LuksSuspendOptions;
+use crate::power_options::{PowerBackend, PowerOptions};
+
+const DROP_CACHES_PATH: &str = "/proc/sys/vm/drop_caches";
+/// Frees the page cache as well as dentries and inodes.
+const DROP_ALL_CACHES: &str = "3";
+const LUKS_SUSPEND_COMMAND: &str = "luksSuspend";
+
+/// Makes the data on encrypted disks unreadable before powering off, for a
+/// machine stolen while running: locks the sessions, drops the caches,
+/// suspends the LUKS devices, which wipes their keys from memory, runs the
+/// key wipe hook and powers off. A failed step is logged and does not stop
+/// the following ones, the power off is always attempted.
+pub struct LuksSuspend {
+    options: LuksSuspendOptions,
+    power_options: PowerOptions,
+    drop_caches_path: String,
+}
+
+impl LuksSuspend {
+    pub fn new(options: &LuksSuspendOptions, power_options: &PowerOptions) -> LuksSuspend {
+        return LuksSuspend {
+            options: options.clone(),
+            power_options: power_options.clone(),
+            drop_caches_path: String::from(DROP_CACHES_PATH),
+        };
+    }
+
+    pub fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let mut failed_steps = Vec::new();
+        Self::run_step("Locking sessions", self.lock_sessions(), &mut failed_steps);
+        Self::run_step("Dropping caches", self.drop_caches(), &mut failed_steps);
+        for device in &self.options.devices {
+            let name = format!("Suspending {}", device);
+            Self::run_step(&name, self.suspend_device(device), &mut failed_steps);
+        }
+        if !self.options.hook.is_empty() {
+            Self::run_step("Running key wipe hook", self.run_hook(), &mut failed_steps);
+        }
+        self.power_off()?;
+        if !failed_steps.is_empty() {
+            Err(format!("Failed steps: {}", failed_steps.join(", ")))?;
+        }
+        return Ok(());
+    }
+
+    fn run_step(name: &str, result: Result<(), Box<dyn Error>>, failed_steps: &mut Vec<String>) {
+        match result {
+            Ok(_) => LogEntry::info("luks_suspend_step", &format!("{} done", name)).log(),
+            Err(error) => {
+                let message = format!("{} failed: {}", name, error);
+                LogEntry::error("luks_suspend_error", &message).log();
+                failed_steps.push(String::from(name));
+            }
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        return Duration::from_secs(self.options.timeout_seconds as u64);
+    }
+
+    fn lock_sessions(&self) -> Result<(), Box<dyn Error>> {
+        let mut connection = DBusConnection::open(&self.power_options.system_bus, self.timeout())?;
+        return Logind::lock_sessions(&mut connection);
+    }
+
+    fn drop_caches(&self) -> Result<(), Box<dyn Error>> {
+        let path = self.drop_caches_path.clone();
+        // Only clean pages are dropped, dirty ones are written first.
+        return CommandRunner::run_function("Dropping caches", self.timeout(), move || {
+            unsafe { libc::sync() };
+            return fs::write(&path, DROP_ALL_CACHES).map_err(|error| {
+                return format!("Unable to write {}: {}", path, error);
+            });
+        });
+    }
+
+    fn suspend_device(&self, device: &str) -> Result<(), Box<dyn Error>> {
+        let mut command = Command::new(&self.options.cryptsetup);
+        command.args([LUKS_SUSPEND_COMMAND, device]);
+        return Self::run_command(&mut command, self.timeout());
+    }
+
+    fn run_hook(&self) -> Result<(), Box<dyn Error>> {
+        let mut command = Command::new(&self.options.hook[0]);
+        command.args(&self.options.hook[1..]);
+        return Self::run_command(&mut command, self.timeout());
+    }
+
+    fn run_command(command: &mut Command, timeout: Duration) -> Result<(), Box<dyn Error>> {
+        let output = CommandRunner::run(command, timeout)?;
+        if !output.status.success() {
+            Err(format!(
+                "{} failed with {}: {}",
+                command.get_program().to_string_lossy(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            ))?;
+        }
+        return Ok(());
+    }
+
+    /// Disks of suspended devices block all I/O, so the kernel backend powers
+    /// off without syncing. The other backends may hang on reading programs
+    /// from such disks, hence the timeout.
+    fn power_off(&self) -> Result<(), Box<dyn Error>> {
+        let power_options = self.power_options.clone();
+        return CommandRunner::run_function("Powering off", self.timeout(), move || {
+            let result = match power_options.backend {
+                PowerBackend::Kernel => KernelPower::power_off_without_sync(PROCESS_STATUS_PATH),
+                _ => Action::PowerOff.execute(&power_options),
+            };
+            return result.map_err(|error| {
+                return error.to_string();
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+#[path = "./luks_suspend_tests.rs"]
+mod tests;
@@ -0,0 +1,39 @@
+use std::fmt::{Display, Formatter};
+
+pub const CRYPTSETUP_PATH: &str = "/usr/sbin/cryptsetup";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct LuksSuspendOptions {
+    /// Device mapper names of the LUKS devices to suspend.
+    pub devices: Vec<String>,
+    /// Command with arguments wiping the keys, run after the devices have
+    /// been suspended.
+    pub hook: Vec<String>,
+    pub cryptsetup: String,
+    /// Timeout of each step.
+    pub timeout_seconds: i32,
+}
+
+impl Default for LuksSuspendOptions {
+    fn default() -> Self {
+        return LuksSuspendOptions {
+            devices: Vec::new(),
+            hook: Vec::new(),
+            cryptsetup: String::from(CRYPTSETUP_PATH),
+            timeout_seconds: 10,
+        };
+    }
+}
+
+impl Display for LuksSuspendOptions {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            formatter,
+            "luks-suspend-devices={}, luks-suspend-hook={}, luks-suspend-cryptsetup={}, luks-suspend-timeout-seconds={}",
+            self.devices.join(","),
+            self.hook.join(" "),
+            self.cryptsetup,
+            self.timeout_seconds
+        );
+    }
+}
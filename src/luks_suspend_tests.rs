@@ -0,0 +1,119 @@
+use std::env::temp_dir;
+use std::fs::{read_to_string, remove_file, write};
+
+use crate::assert_error;
+use crate::dbus_message::DBusValue;
+use crate::luks_suspend::LuksSuspend;
+use crate::luks_suspend_options::LuksSuspendOptions;
+use crate::power_options::{InhibitorPolicy, PowerBackend, PowerOptions};
+use crate::test_utils::dbus_stand_in::DBusStandIn;
+
+fn start_logind() -> DBusStandIn {
+    return DBusStandIn::start(|call| {
+        return match call.member.as_deref() {
+            Some("LockSessions") | Some("PowerOff") => Ok(Vec::new()),
+            _ => Err(String::from("org.freedesktop.DBus.Error.UnknownMethod")),
+        };
+    });
+}
+
+fn temp_path(name: &str) -> String {
+    let path = temp_dir().join(format!("auth-monitor-{}-{}", name, std::process::id()));
+    return String::from(path.to_str().unwrap());
+}
+
+fn luks_suspend(
+    bus: &DBusStandIn,
+    options: LuksSuspendOptions,
+    drop_caches_path: &str,
+) -> LuksSuspend {
+    let power_options = PowerOptions {
+        backend: PowerBackend::Logind,
+        inhibitors: InhibitorPolicy::None,
+        fallback_backend: PowerBackend::Kernel,
+        system_bus: bus.address(),
+        timeout_seconds: 5,
+        ..PowerOptions::default()
+    };
+    let mut luks_suspend = LuksSuspend::new(&options, &power_options);
+    luks_suspend.drop_caches_path = String::from(drop_caches_path);
+    return luks_suspend;
+}
+
+fn members(bus: &DBusStandIn) -> Vec<String> {
+    return bus
+        .calls()
+        .iter()
+        .map(|call| {
+            return call.member.clone().unwrap_or_default();
+        })
+        .collect();
+}
+
+#[test]
+fn when_executed_then_all_steps_run_before_power_off() {
+    let bus = start_logind();
+    let drop_caches_path = temp_path("drop-caches");
+    write(&drop_caches_path, "").unwrap();
+    let hook_output_path = temp_path("hook-output");
+    let options = LuksSuspendOptions {
+        devices: vec![String::from("luks-home")],
+        hook: vec![
+            String::from("sh"),
+            String::from("-c"),
+            format!("echo wiped > {}", hook_output_path),
+        ],
+        cryptsetup: String::from("true"),
+        timeout_seconds: 5,
+    };
+
+    let result = luks_suspend(&bus, options, &drop_caches_path).execute();
+
+    let drop_caches = read_to_string(&drop_caches_path).unwrap();
+    let hook_output = read_to_string(&hook_output_path).unwrap_or_default();
+    remove_file(&drop_caches_path).unwrap();
+    let _ = remove_file(&hook_output_path);
+    assert!(result.is_ok(), "{:?}", result.err());
+    assert_eq!(members(&bus), ["LockSessions", "PowerOff"]);
+    assert_eq!(bus.calls()[1].body, [DBusValue::Boolean(false)]);
+    assert_eq!(drop_caches, "3");
+    assert_eq!(hook_output, "wiped\n");
+}
+
+#[test]
+fn when_steps_fail_then_machine_is_still_powered_off() {
+    let bus = start_logind();
+    let options = LuksSuspendOptions {
+        devices: vec![String::from("luks-root"), String::from("luks-home")],
+        hook: Vec::new(),
+        cryptsetup: String::from("false"),
+        timeout_seconds: 5,
+    };
+
+    let result = luks_suspend(&bus, options, "/nonexistent/drop_caches").execute();
+
+    assert_error!(
+        result,
+        "Failed steps: Dropping caches, Suspending luks-root, Suspending luks-home"
+    );
+    assert_eq!(members(&bus), ["LockSessions", "PowerOff"]);
+}
+
+#[test]
+fn when_hook_hangs_then_it_is_stopped_after_timeout() {
+    let bus = start_logind();
+    let drop_caches_path = temp_path("drop-caches-timeout");
+    write(&drop_caches_path, "").unwrap();
+    let options = LuksSuspendOptions {
+        devices: Vec::new(),
+        hook: vec![String::from("sleep"), String::from("30")],
+        cryptsetup: String::from("true"),
+        timeout_seconds: 1,
+    };
+
+    let result = luks_suspend(&bus, options, &drop_caches_path).execute();
+
+    remove_file(&drop_caches_path).unwrap();
+    assert_error!(result, "Failed steps: Running key wipe hook");
+    assert_eq!(members(&bus), ["LockSessions", "PowerOff"]);
+}
@@ -1,24 +1,91 @@
 #![deny(clippy::implicit_return)]
 #![allow(clippy::needless_return)]
 
-use std::process::{Command, ExitCode};
-use std::time::Duration;
-use std::{env, thread};
+use std::io::BufRead;
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime};
+use std::{env, io, thread};
 
-use signal_hook::consts::{SIGABRT, SIGINT};
-use signal_hook::iterator::Signals;
+use signal_hook::consts::{SIGABRT, SIGHUP, SIGINT, SIGTERM};
+use signal_hook::iterator::exfiltrator::WithOrigin;
+use signal_hook::iterator::SignalsInfo;
 
+use crate::action_executor::ActionExecutor;
+use crate::action_worker::ActionWorker;
+use crate::auth_log_replay::AuthLogReplay;
+use crate::auth_message_parser::AuthMessageParser;
 use crate::auth_monitor::AuthMonitor;
 use crate::auth_monitor_params::AuthMonitorParams;
+use crate::command_line::{
+    CommandLine, ARM_COMMAND, CHECK_CONFIG_COMMAND, DISARM_COMMAND, PROGRAM_NAME, PROGRAM_VERSION,
+    RECORD_TERMINATION_COMMAND, REPLAY_COMMAND, RESET_COMMAND, RUN_COMMAND, STATUS_COMMAND,
+    TEST_PATTERN_COMMAND,
+};
+use crate::config_check::ConfigCheck;
+use crate::control_client::ControlClient;
+use crate::control_params::ControlParams;
+use crate::control_socket::{ControlRequest, ControlSocket};
+use crate::logger::{LogEntry, Logger};
+use crate::metrics_exporter::MetricsExporter;
+use crate::pattern_tester::PatternTester;
+use crate::replay_params::ReplayParams;
+use crate::systemd_notify::SystemdNotify;
+use crate::termination_log::TerminationLog;
 
+mod action;
+mod action_context;
+mod action_executor;
+mod action_worker;
 mod auth_file_reader;
 mod auth_file_watcher;
+mod auth_log_replay;
 mod auth_message_parser;
 mod auth_monitor;
+mod auth_monitor_config;
 mod auth_monitor_options;
 mod auth_monitor_params;
+mod auth_monitor_status;
+mod command_action;
+mod command_line;
+mod command_options;
+mod command_runner;
+mod config_check;
+mod control_client;
+mod control_params;
+mod control_socket;
+mod dbus_connection;
+mod dbus_message;
+mod desktop_notifier;
+mod desktop_options;
+mod disarm_state;
+mod duration_parser;
+mod email_notifier;
+mod email_options;
+mod failed_attempts_counter;
 mod file_event_filter;
 mod file_path;
+mod http_client;
+mod json;
+mod kernel_power;
+mod log_timestamp_parser;
+mod logger;
+mod logind;
+mod luks_suspend;
+mod luks_suspend_options;
+mod metrics;
+mod metrics_exporter;
+mod notification_queue;
+mod pattern_tester;
+mod power_options;
+mod replay_params;
+mod smtp_client;
+mod systemd_notify;
+mod tamper_detector;
+mod tamper_options;
+mod terminal_broadcast;
+mod termination_log;
+mod webhook_notifier;
+mod webhook_options;
 
 #[cfg(test)]
 mod test_utils;
@@ -27,39 +94,144 @@ const SLEEP_DURATION: Duration = Duration::from_millis(500);
 
 fn main() -> ExitCode {
     let arguments: Vec<String> = env::args().skip(1).collect();
-    let params = match AuthMonitorParams::from_arguments(&arguments) {
+    let (command, arguments) = match CommandLine::parse(&arguments) {
+        Ok(CommandLine::Command(command, arguments)) => (command, arguments),
+        Ok(CommandLine::Help(usage)) => {
+            println!("{}", usage);
+            return ExitCode::SUCCESS;
+        }
+        Ok(CommandLine::Version) => {
+            println!("{} {}", PROGRAM_NAME, PROGRAM_VERSION);
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("Invalid arguments: {}", error);
+            eprintln!("Run \"{} --help\" for usage.", PROGRAM_NAME);
+            return ExitCode::FAILURE;
+        }
+    };
+    return match command {
+        RUN_COMMAND => run(&arguments),
+        REPLAY_COMMAND => replay(&arguments),
+        CHECK_CONFIG_COMMAND => check_config(&arguments),
+        STATUS_COMMAND | RESET_COMMAND | DISARM_COMMAND | ARM_COMMAND => {
+            control(command, &arguments)
+        }
+        TEST_PATTERN_COMMAND => test_pattern(&arguments),
+        RECORD_TERMINATION_COMMAND => record_termination(&arguments),
+        _ => unreachable!("Command {} is not handled", command),
+    };
+}
+
+fn run(arguments: &[String]) -> ExitCode {
+    let params = match AuthMonitorParams::from_arguments(arguments) {
         Ok(params) => params,
         Err(error) => {
             eprintln!("Invalid arguments: {}", error);
             return ExitCode::FAILURE;
         }
     };
-    println!("Monitoring process started with parameters {}", params);
-    let exit_code = start_monitoring(params);
-    println!("Monitoring process stopped");
+    Logger::configure(params.log_level, params.log_format);
+    let message = format!("Monitoring process started with parameters {}", params);
+    LogEntry::info("started", &message).log();
+    let exit_code = start_monitoring(params, arguments);
+    LogEntry::info("stopped", "Monitoring process stopped").log();
     return exit_code;
 }
 
-fn start_monitoring(params: AuthMonitorParams) -> ExitCode {
+fn start_monitoring(params: AuthMonitorParams, arguments: &[String]) -> ExitCode {
+    let mut control_socket = match ControlSocket::new(&params.control_socket) {
+        Ok(control_socket) => Some(control_socket),
+        Err(error) => {
+            let message = format!(
+                "Unable to create control socket {}: {}",
+                params.control_socket, error
+            );
+            LogEntry::error("control_error", &message).log();
+            None
+        }
+    };
+    let mut metrics_exporter =
+        match MetricsExporter::new(params.metrics_textfile.as_deref(), params.metrics_address) {
+            Ok(metrics_exporter) => Some(metrics_exporter),
+            Err(error) => {
+                let message = format!("Unable to export metrics: {}", error);
+                LogEntry::error("metrics_error", &message).log();
+                None
+            }
+        };
+    let action_worker = match ActionExecutor::new(&params).and_then(ActionWorker::new) {
+        Ok(action_worker) => action_worker,
+        Err(error) => {
+            let message = format!("Error creating action executor: {}", error);
+            LogEntry::error("startup_error", &message).log();
+            return ExitCode::FAILURE;
+        }
+    };
     let mut auth_monitor = match AuthMonitor::new(params) {
         Ok(auth_monitor) => auth_monitor,
         Err(error) => {
-            eprintln!("Error creating AuthMonitor: {}", error);
+            let message = format!("Error creating AuthMonitor: {}", error);
+            LogEntry::error("startup_error", &message).log();
             return ExitCode::FAILURE;
         }
     };
-    let mut signals = match Signals::new([SIGABRT, SIGINT]) {
+    let mut signals = match SignalsInfo::<WithOrigin>::new([SIGABRT, SIGHUP, SIGINT, SIGTERM]) {
         Ok(signals) => signals,
         Err(error) => {
-            eprintln!("Error creating signals: {}", error);
+            let message = format!("Error creating signals: {}", error);
+            LogEntry::error("startup_error", &message).log();
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut systemd_notify = match SystemdNotify::from_environment() {
+        Ok(systemd_notify) => systemd_notify,
+        Err(error) => {
+            let message = format!("Error creating systemd notification socket: {}", error);
+            LogEntry::error("startup_error", &message).log();
             return ExitCode::FAILURE;
         }
     };
+    systemd_notify.ready(&auth_monitor.status().summary());
     loop {
-        auth_monitor.update(shutdown);
+        auth_monitor.update(|actions, context| {
+            action_worker.execute(actions, context);
+        });
+        auth_monitor.record_action_failures(action_worker.take_failures());
+        if let Some(metrics_exporter) = &mut metrics_exporter {
+            metrics_exporter.update(&auth_monitor.metrics());
+        }
+        systemd_notify.watchdog();
+        systemd_notify.status(&auth_monitor.status().summary());
+        if let Some(control_socket) = &mut control_socket {
+            control_socket.update(|request| {
+                return handle_control_request(&mut auth_monitor, request);
+            });
+        }
         match signals.pending().next() {
-            Some(signal) => {
-                println!("Received signal {}", signal);
+            Some(origin) if origin.signal == SIGHUP => {
+                reload(&mut auth_monitor, &action_worker, arguments)
+            }
+            Some(origin) => {
+                let sender = origin.process.map(|process| {
+                    return process.pid;
+                });
+                systemd_notify.stopping();
+                auth_monitor.print_dry_run_summary();
+                if !systemd_notify.is_requested_stop(sender) {
+                    // Exiting with failure lets record-termination count the kill.
+                    let message = format!(
+                        "Received signal {} from process {} instead of systemd",
+                        origin.signal,
+                        sender.map_or(String::from("unknown"), |pid| {
+                            return pid.to_string();
+                        })
+                    );
+                    LogEntry::warning("signal", &message).log();
+                    return ExitCode::FAILURE;
+                }
+                let message = format!("Received signal {}", origin.signal);
+                LogEntry::info("signal", &message).log();
                 return ExitCode::SUCCESS;
             }
             None => thread::sleep(SLEEP_DURATION),
@@ -67,30 +239,169 @@ fn start_monitoring(params: AuthMonitorParams) -> ExitCode {
     }
 }
 
-const SUDO_COMMAND: &str = "sudo";
-const SYSTEMCTL_POWER_OFF_ARGS: [&str; 2] = ["systemctl", "poweroff"];
+fn handle_control_request(
+    auth_monitor: &mut AuthMonitor,
+    request: ControlRequest,
+) -> Result<String, String> {
+    match request {
+        ControlRequest::Status => return Ok(auth_monitor.status().to_string()),
+        ControlRequest::Reset => auth_monitor.reset(),
+        ControlRequest::Disarm(duration) => {
+            if let Err(error) = auth_monitor.disarm(duration) {
+                return Err(error.to_string());
+            }
+        }
+        ControlRequest::Arm => auth_monitor.arm(),
+    }
+    return Ok(String::new());
+}
 
-fn shutdown() {
-    let output = match Command::new(SUDO_COMMAND)
-        .args(SYSTEMCTL_POWER_OFF_ARGS)
-        .output()
-    {
-        Ok(output) => output,
+fn reload(auth_monitor: &mut AuthMonitor, action_worker: &ActionWorker, arguments: &[String]) {
+    LogEntry::info("reloading", "Reloading configuration").log();
+    let params = match AuthMonitorParams::from_arguments(arguments) {
+        Ok(params) => params,
+        Err(error) => {
+            let message = format!("Invalid configuration, keeping previous one: {}", error);
+            LogEntry::error("config_invalid", &message).log();
+            return;
+        }
+    };
+    let params_description = params.to_string();
+    let (log_level, log_format) = (params.log_level, params.log_format);
+    // Both are built before either is swapped, so a failure keeps the
+    // previous configuration entirely.
+    let action_executor = match ActionExecutor::new(&params) {
+        Ok(action_executor) => action_executor,
         Err(error) => {
-            eprintln!("Unable to shutdown: {}", error);
+            let message = format!("Invalid configuration, keeping previous one: {}", error);
+            LogEntry::error("config_invalid", &message).log();
             return;
         }
     };
-    let output_data = if output.status.success() {
-        output.stdout
-    } else {
-        output.stderr
+    match auth_monitor.reload(params) {
+        Ok(_) => {
+            action_worker.replace(action_executor);
+            Logger::configure(log_level, log_format);
+            let message = format!(
+                "Configuration reloaded with parameters {}",
+                params_description
+            );
+            LogEntry::info("reloaded", &message).log();
+        }
+        Err(error) => {
+            let message = format!("Invalid configuration, keeping previous one: {}", error);
+            LogEntry::error("config_invalid", &message).log();
+        }
+    }
+}
+
+fn replay(arguments: &[String]) -> ExitCode {
+    let params = match ReplayParams::from_arguments(arguments) {
+        Ok(params) => params,
+        Err(error) => {
+            eprintln!("Invalid arguments: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("Replaying with parameters {}", params);
+    let auth_message_parser = AuthMessageParser::with_rules(
+        &params.patterns,
+        &params.ignore_users,
+        &params.ignore_services,
+    );
+    let mut auth_log_replay = AuthLogReplay::new(params.options, auth_message_parser);
+    for filepath in &params.filepaths {
+        if let Err(error) = auth_log_replay.replay_file(filepath) {
+            eprintln!("Error replaying file {}: {}", filepath, error);
+            return ExitCode::FAILURE;
+        }
+    }
+    auth_log_replay.print_summary();
+    return ExitCode::SUCCESS;
+}
+
+fn check_config(arguments: &[String]) -> ExitCode {
+    let config_check = ConfigCheck::run(arguments, true);
+    config_check.print_report();
+    return match config_check.is_successful() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    };
+}
+
+fn control(command: &str, arguments: &[String]) -> ExitCode {
+    let params = match ControlParams::from_arguments(command, arguments) {
+        Ok(params) => params,
+        Err(error) => {
+            eprintln!("Invalid arguments: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    return match ControlClient::send(&params.control_socket, &params.request) {
+        Ok(response) => {
+            if !response.is_empty() {
+                println!("{}", response);
+            }
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            ExitCode::FAILURE
+        }
+    };
+}
+
+fn test_pattern(arguments: &[String]) -> ExitCode {
+    let pattern_tester = match PatternTester::from_arguments(arguments) {
+        Ok(pattern_tester) => pattern_tester,
+        Err(error) => {
+            eprintln!("Invalid arguments: {}", error);
+            return ExitCode::FAILURE;
+        }
     };
-    if output_data.is_empty() {
-        return;
+    if !pattern_tester.lines.is_empty() {
+        for line in &pattern_tester.lines {
+            println!("{}: {}", pattern_tester.classify(line), line);
+        }
+        return ExitCode::SUCCESS;
     }
-    match String::from_utf8(output_data) {
-        Ok(output_message) => println!("Shutdown output: {}", output_message),
-        Err(error) => eprintln!("Error converting command output to string: {}", error),
+    for line in io::stdin().lock().lines() {
+        match line {
+            Ok(line) => println!("{}: {}", pattern_tester.classify(&line), line),
+            Err(error) => {
+                eprintln!("Error reading standard input: {}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn record_termination(arguments: &[String]) -> ExitCode {
+    let params = match AuthMonitorParams::from_arguments(arguments) {
+        Ok(params) => params,
+        Err(error) => {
+            eprintln!("Invalid arguments: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    Logger::configure(params.log_level, params.log_format);
+    let environment: Vec<(String, String)> = env::vars().collect();
+    let reason = match TerminationLog::reason_from_environment(&environment) {
+        Some(reason) => reason,
+        None => return ExitCode::SUCCESS,
+    };
+    let termination_log = TerminationLog::new(&params.state_directory);
+    let window = Duration::from_secs(params.tamper.terminations_window_seconds as u64);
+    return match termination_log.record(SystemTime::now(), &reason, window) {
+        Ok(_) => {
+            let message = format!("Monitoring process terminated unexpectedly: {}", reason);
+            LogEntry::warning("terminated", &message).log();
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            LogEntry::error("state_error", &error.to_string()).log();
+            ExitCode::FAILURE
+        }
     };
 }
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+const METRIC_PREFIX: &str = "auth_monitor_";
+
+/// Counters exposed in the Prometheus text format.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Metrics {
+    pub lines_read: u64,
+    pub failures_by_pattern: BTreeMap<String, u64>,
+    pub counter_resets: u64,
+    pub actions_fired: u64,
+    pub action_failures: u64,
+    pub file_reopens: u64,
+    pub file_rotations: u64,
+    pub tamper_events: u64,
+    pub failed_attempts: i32,
+}
+
+impl Metrics {
+    pub fn record_failure(&mut self, pattern: &str) {
+        *self
+            .failures_by_pattern
+            .entry(String::from(pattern))
+            .or_insert(0) += 1;
+    }
+
+    fn write_metric(
+        formatter: &mut Formatter<'_>,
+        name: &str,
+        metric_type: &str,
+        help: &str,
+        samples: &[(String, String)],
+    ) -> std::fmt::Result {
+        writeln!(formatter, "# HELP {}{} {}", METRIC_PREFIX, name, help)?;
+        writeln!(
+            formatter,
+            "# TYPE {}{} {}",
+            METRIC_PREFIX, name, metric_type
+        )?;
+        for (labels, value) in samples {
+            writeln!(formatter, "{}{}{} {}", METRIC_PREFIX, name, labels, value)?;
+        }
+        return Ok(());
+    }
+
+    fn label_value(value: &str) -> String {
+        return value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+    }
+}
+
+impl Display for Metrics {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let counters = [
+            (
+                "lines_read_total",
+                "Lines read from the monitored file.",
+                self.lines_read,
+            ),
+            (
+                "counter_resets_total",
+                "Resets of the failed attempts counter.",
+                self.counter_resets,
+            ),
+            (
+                "actions_fired_total",
+                "Actions executed after reaching the failed attempts limit.",
+                self.actions_fired,
+            ),
+            (
+                "action_failures_total",
+                "Actions that failed to execute.",
+                self.action_failures,
+            ),
+            (
+                "file_reopens_total",
+                "Times the monitored file has been opened again after being recreated.",
+                self.file_reopens,
+            ),
+            (
+                "file_rotations_total",
+                "Times the monitored file has been moved, deleted or truncated.",
+                self.file_rotations,
+            ),
+            (
+                "tamper_events_total",
+                "Deletions, truncations, absence and silence of the monitored file detected.",
+                self.tamper_events,
+            ),
+        ];
+        for (name, help, value) in counters {
+            let samples = [(String::new(), value.to_string())];
+            Self::write_metric(formatter, name, "counter", help, &samples)?;
+        }
+        let samples: Vec<(String, String)> = self
+            .failures_by_pattern
+            .iter()
+            .map(|(pattern, count)| {
+                let labels = format!("{{pattern=\"{}\"}}", Self::label_value(pattern));
+                return (labels, count.to_string());
+            })
+            .collect();
+        Self::write_metric(
+            formatter,
+            "failures_matched_total",
+            "counter",
+            "Failed authentication messages matched, by pattern.",
+            &samples,
+        )?;
+        return Self::write_metric(
+            formatter,
+            "failed_attempts",
+            "gauge",
+            "Current value of the failed attempts counter.",
+            &[(String::new(), self.failed_attempts.to_string())],
+        );
+    }
+}
+
+#[cfg(test)]
+#[path = "./metrics_tests.rs"]
+mod tests;
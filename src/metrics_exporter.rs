@@ -0,0 +1,196 @@
+use std::error::Error;
+use std::fs;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::logger::LogEntry;
+use crate::metrics::Metrics;
+
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(1);
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_REQUEST_SIZE: usize = 8192;
+const READ_BUFFER_SIZE: usize = 1024;
+const SERVER_NAME: &str = "metrics";
+const METRICS_PATH: &str = "/metrics";
+const GET_METHOD: &str = "GET";
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const TEMPORARY_FILE_SUFFIX: &str = ".tmp";
+
+/// Writes the metrics to a textfile and serves them over HTTP from a
+/// background thread, so that slow clients never delay monitoring.
+pub struct MetricsExporter {
+    textfile: Option<String>,
+    content: Arc<Mutex<String>>,
+    stopped: Arc<AtomicBool>,
+    server: Option<JoinHandle<()>>,
+    written_content: String,
+}
+
+impl MetricsExporter {
+    pub fn new(
+        textfile: Option<&str>,
+        address: Option<SocketAddr>,
+    ) -> Result<MetricsExporter, Box<dyn Error>> {
+        let content = Arc::new(Mutex::new(Metrics::default().to_string()));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let server = match address {
+            Some(address) => {
+                let listener = match TcpListener::bind(address) {
+                    Ok(listener) => listener,
+                    Err(error) => Err(format!("Unable to listen on {}: {}", address, error))?,
+                };
+                listener.set_nonblocking(true)?;
+                let (content, stopped) = (content.clone(), stopped.clone());
+                Some(
+                    thread::Builder::new()
+                        .name(String::from(SERVER_NAME))
+                        .spawn(move || {
+                            Self::serve(&listener, &content, &stopped);
+                        })?,
+                )
+            }
+            None => None,
+        };
+        return Ok(MetricsExporter {
+            textfile: textfile.map(String::from),
+            content,
+            stopped,
+            server,
+            written_content: String::new(),
+        });
+    }
+
+    pub fn update(&mut self, metrics: &Metrics) {
+        if self.textfile.is_none() && self.server.is_none() {
+            return;
+        }
+        let content = metrics.to_string();
+        if let Some(textfile) = &self.textfile {
+            if content != self.written_content {
+                match Self::write_textfile(textfile, &content) {
+                    Ok(_) => self.written_content = content.clone(),
+                    Err(error) => {
+                        let message = format!("Error writing metrics file: {}", error);
+                        LogEntry::error("metrics_error", &message)
+                            .file(textfile)
+                            .log();
+                    }
+                }
+            }
+        }
+        if self.server.is_some() {
+            if let Ok(mut served_content) = self.content.lock() {
+                *served_content = content;
+            }
+        }
+    }
+
+    /// Writes to a temporary file first, so that the textfile collector never
+    /// reads a partially written file.
+    fn write_textfile(path: &str, content: &str) -> Result<(), Box<dyn Error>> {
+        let temporary_path = format!("{}{}", path, TEMPORARY_FILE_SUFFIX);
+        fs::write(&temporary_path, content)?;
+        fs::rename(&temporary_path, path)?;
+        return Ok(());
+    }
+
+    fn serve(listener: &TcpListener, content: &Mutex<String>, stopped: &AtomicBool) {
+        while !stopped.load(Ordering::SeqCst) {
+            let stream = match listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(ACCEPT_INTERVAL);
+                    continue;
+                }
+                Err(error) => {
+                    let message = format!("Error accepting metrics connection: {}", error);
+                    LogEntry::error("metrics_error", &message).log();
+                    thread::sleep(ACCEPT_INTERVAL);
+                    continue;
+                }
+            };
+            let content = match content.lock() {
+                Ok(content) => content.clone(),
+                Err(_) => return,
+            };
+            if let Err(error) = Self::handle_connection(stream, &content) {
+                let message = format!("Error handling metrics connection: {}", error);
+                LogEntry::warning("metrics_error", &message).log();
+            }
+        }
+    }
+
+    fn handle_connection(mut stream: TcpStream, content: &str) -> Result<(), Box<dyn Error>> {
+        stream.set_nonblocking(false)?;
+        stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+        let request = Self::read_request(&mut stream)?;
+        let request_line = request.lines().next().unwrap_or_default();
+        let mut parts = request_line.split_whitespace();
+        let response = match (parts.next(), parts.next()) {
+            (Some(GET_METHOD), Some(METRICS_PATH)) => Self::response("200 OK", content),
+            (Some(GET_METHOD), Some(_)) => Self::response("404 Not Found", "Not Found\n"),
+            _ => Self::response("405 Method Not Allowed", "Method Not Allowed\n"),
+        };
+        stream.write_all(response.as_bytes())?;
+        return Ok(());
+    }
+
+    /// Reads the request line and headers, giving up on requests that take
+    /// longer than the client timeout in total or exceed the size limit.
+    fn read_request(stream: &mut TcpStream) -> Result<String, Box<dyn Error>> {
+        let deadline = Instant::now() + CLIENT_TIMEOUT;
+        let mut request = Vec::new();
+        let mut buffer = [0u8; READ_BUFFER_SIZE];
+        while !Self::has_headers_end(&request) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                Err("Request timed out")?;
+            }
+            stream.set_read_timeout(Some(remaining))?;
+            let size = stream.read(&mut buffer)?;
+            if size == 0 {
+                break;
+            }
+            request.extend_from_slice(&buffer[..size]);
+            if request.len() > MAX_REQUEST_SIZE {
+                Err(format!("Request is larger than {} bytes", MAX_REQUEST_SIZE))?;
+            }
+        }
+        return Ok(String::from_utf8_lossy(&request).into_owned());
+    }
+
+    fn has_headers_end(request: &[u8]) -> bool {
+        return request.windows(4).any(|window| {
+            return window == b"\r\n\r\n";
+        }) || request.windows(2).any(|window| {
+            return window == b"\n\n";
+        });
+    }
+
+    fn response(status: &str, body: &str) -> String {
+        return format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            CONTENT_TYPE,
+            body.len(),
+            body
+        );
+    }
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        if let Some(server) = self.server.take() {
+            let _ = server.join();
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./metrics_exporter_tests.rs"]
+mod tests;
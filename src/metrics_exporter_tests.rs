@@ -0,0 +1,116 @@
+use std::env::temp_dir;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::metrics::Metrics;
+use crate::metrics_exporter::MetricsExporter;
+
+fn get(address: SocketAddr, path: &str, metrics: &Metrics) -> String {
+    let mut metrics_exporter =
+        MetricsExporter::new(None, Some(address)).expect("Error creating metrics exporter");
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+    let client = thread::spawn(move || {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        return response;
+    });
+    while !client.is_finished() {
+        metrics_exporter.update(metrics);
+        thread::sleep(Duration::from_millis(10));
+    }
+    return client.join().unwrap();
+}
+
+#[test]
+fn when_metrics_are_requested_over_http_then_they_are_returned() {
+    let address: SocketAddr = "127.0.0.1:19157".parse().unwrap();
+    let metrics = Metrics {
+        lines_read: 5,
+        ..Metrics::default()
+    };
+    let response = get(address, "/metrics", &metrics);
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    assert!(response.ends_with(&metrics.to_string()), "{}", response);
+
+    let response = get(address, "/", &metrics);
+    assert!(
+        response.starts_with("HTTP/1.1 404 Not Found\r\n"),
+        "{}",
+        response
+    );
+}
+
+#[test]
+fn when_textfile_is_configured_then_metrics_are_written_to_it() {
+    let path = temp_dir().join("auth-monitor-metrics-test.prom");
+    let path = path.to_str().unwrap();
+    let mut metrics_exporter =
+        MetricsExporter::new(Some(path), None).expect("Error creating metrics exporter");
+    let mut metrics = Metrics::default();
+    metrics_exporter.update(&metrics);
+    assert_eq!(fs::read_to_string(path).unwrap(), metrics.to_string());
+
+    metrics.failed_attempts = 2;
+    metrics_exporter.update(&metrics);
+    assert_eq!(fs::read_to_string(path).unwrap(), metrics.to_string());
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn when_client_sends_request_slowly_then_connection_is_closed_without_blocking_updates() {
+    let address: SocketAddr = "127.0.0.1:19158".parse().unwrap();
+    let mut metrics_exporter =
+        MetricsExporter::new(None, Some(address)).expect("Error creating metrics exporter");
+    let client = thread::spawn(move || {
+        let start = Instant::now();
+        let mut stream = TcpStream::connect(address).unwrap();
+        for byte in b"GET /metrics HTTP/1.1\r\n" {
+            if stream.write_all(&[*byte]).is_err() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response);
+        return (response, start.elapsed());
+    });
+    let mut longest_update = Duration::ZERO;
+    while !client.is_finished() {
+        let start = Instant::now();
+        metrics_exporter.update(&Metrics::default());
+        longest_update = longest_update.max(start.elapsed());
+        thread::sleep(Duration::from_millis(10));
+    }
+    let (response, elapsed) = client.join().unwrap();
+
+    assert_eq!(response, "");
+    assert!(elapsed < Duration::from_secs(4), "{:?}", elapsed);
+    assert!(
+        longest_update < Duration::from_millis(100),
+        "{:?}",
+        longest_update
+    );
+}
+
+#[test]
+fn when_request_is_too_large_then_it_is_not_answered() {
+    let address: SocketAddr = "127.0.0.1:19159".parse().unwrap();
+    let _metrics_exporter =
+        MetricsExporter::new(None, Some(address)).expect("Error creating metrics exporter");
+    let mut stream = TcpStream::connect(address).unwrap();
+    let request = format!(
+        "GET /metrics HTTP/1.1\r\nX-Padding: {}\r\n",
+        "a".repeat(10000)
+    );
+    let _ = stream.write_all(request.as_bytes());
+
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response);
+
+    assert_eq!(response, "");
+}
@@ -0,0 +1,43 @@
+use crate::metrics::Metrics;
+
+#[test]
+fn when_metrics_are_formatted_then_prometheus_text_format_is_returned() {
+    let mut metrics = Metrics {
+        lines_read: 12,
+        counter_resets: 1,
+        actions_fired: 1,
+        file_rotations: 2,
+        failed_attempts: 3,
+        ..Metrics::default()
+    };
+    metrics.record_failure("pam_unix: authentication failure");
+    metrics.record_failure("pam_unix: authentication failure");
+    metrics.record_failure("sshd: \"Failed\" password");
+
+    let text = metrics.to_string();
+    let expected_lines = [
+        "# TYPE auth_monitor_lines_read_total counter",
+        "auth_monitor_lines_read_total 12",
+        "auth_monitor_counter_resets_total 1",
+        "auth_monitor_actions_fired_total 1",
+        "auth_monitor_action_failures_total 0",
+        "auth_monitor_file_reopens_total 0",
+        "auth_monitor_file_rotations_total 2",
+        "# TYPE auth_monitor_failures_matched_total counter",
+        "auth_monitor_failures_matched_total{pattern=\"pam_unix: authentication failure\"} 2",
+        "auth_monitor_failures_matched_total{pattern=\"sshd: \\\"Failed\\\" password\"} 1",
+        "# TYPE auth_monitor_failed_attempts gauge",
+        "auth_monitor_failed_attempts 3",
+    ];
+    for expected_line in expected_lines {
+        assert!(
+            text.lines().any(|line| {
+                return line == expected_line;
+            }),
+            "Missing line {} in:\n{}",
+            expected_line,
+            text
+        );
+    }
+    assert!(text.ends_with('\n'));
+}
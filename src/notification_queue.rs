@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::logger::LogEntry;
+
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Delivers notifications from a background thread, so that a slow endpoint
+/// never blocks monitoring. Notifications that do not fit in the queue are
+/// dropped.
+pub struct NotificationQueue {
+    name: &'static str,
+    sender: SyncSender<String>,
+    pending: Arc<AtomicUsize>,
+    max_delivery_time: Duration,
+}
+
+struct NotificationWorker<D: Fn(&str) -> Result<(), Box<dyn Error>>> {
+    name: &'static str,
+    retries: u32,
+    deliver: D,
+    pending: Arc<AtomicUsize>,
+}
+
+impl NotificationQueue {
+    pub fn new(
+        name: &'static str,
+        queue_size: usize,
+        retries: u32,
+        timeout: Duration,
+        deliver: impl Fn(&str) -> Result<(), Box<dyn Error>> + Send + 'static,
+    ) -> Result<NotificationQueue, Box<dyn Error>> {
+        let (sender, receiver) = mpsc::sync_channel(queue_size);
+        let pending = Arc::new(AtomicUsize::new(0));
+        let worker = NotificationWorker {
+            name,
+            retries,
+            deliver,
+            pending: pending.clone(),
+        };
+        thread::Builder::new()
+            .name(String::from(name))
+            .spawn(move || {
+                worker.run(receiver);
+            })?;
+        let attempts = retries + 1;
+        return Ok(NotificationQueue {
+            name,
+            sender,
+            pending,
+            max_delivery_time: (timeout + RETRY_DELAY * attempts) * attempts,
+        });
+    }
+
+    pub fn send(&self, notification: String) -> Result<(), Box<dyn Error>> {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        return match self.sender.try_send(notification) {
+            Ok(_) => Ok(()),
+            Err(error) => {
+                self.pending.fetch_sub(1, Ordering::SeqCst);
+                match error {
+                    TrySendError::Full(_) => {
+                        Err(format!("{} queue is full, notification dropped", self.name))?
+                    }
+                    TrySendError::Disconnected(_) => {
+                        Err(format!("{} sender has stopped", self.name))?
+                    }
+                }
+            }
+        };
+    }
+
+    /// Waits until queued notifications have been delivered or given up on,
+    /// but no longer than it takes to retry a single notification and never
+    /// past the deadline.
+    pub fn wait_until_idle(&self, deadline: Instant) {
+        let deadline = deadline.min(Instant::now() + self.max_delivery_time);
+        while self.pending.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+            thread::sleep(IDLE_CHECK_INTERVAL);
+        }
+    }
+}
+
+impl<D: Fn(&str) -> Result<(), Box<dyn Error>>> NotificationWorker<D> {
+    fn run(&self, receiver: Receiver<String>) {
+        for notification in receiver {
+            self.deliver(&notification);
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    fn deliver(&self, notification: &str) {
+        let attempts = self.retries + 1;
+        for attempt in 1..=attempts {
+            match (self.deliver)(notification) {
+                Ok(_) => {
+                    let message = format!("{} notification sent", self.name);
+                    LogEntry::info("notification_sent", &message).log();
+                    return;
+                }
+                Err(error) => {
+                    let message = format!(
+                        "{} notification attempt {}/{} failed: {}",
+                        self.name, attempt, attempts, error
+                    );
+                    LogEntry::warning("notification_error", &message).log();
+                }
+            }
+            if attempt < attempts {
+                thread::sleep(RETRY_DELAY * attempt);
+            }
+        }
+        let message = format!("{} notification dropped after retries", self.name);
+        LogEntry::error("notification_error", &message).log();
+    }
+}
+
+#[cfg(test)]
+#[path = "./notification_queue_tests.rs"]
+mod tests;
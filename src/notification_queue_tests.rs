@@ -0,0 +1,36 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::notification_queue::NotificationQueue;
+
+#[test]
+fn when_delivery_hangs_then_waiting_stops_at_deadline() {
+    let queue = NotificationQueue::new("Test", 1, 3, Duration::from_secs(10), |_| {
+        thread::sleep(Duration::from_secs(5));
+        return Err("Unreachable")?;
+    })
+    .unwrap();
+    queue.send(String::from("notification")).unwrap();
+
+    let start = Instant::now();
+    queue.wait_until_idle(start + Duration::from_millis(300));
+
+    let waited = start.elapsed();
+    assert!(waited >= Duration::from_millis(300), "{:?}", waited);
+    assert!(waited < Duration::from_secs(2), "{:?}", waited);
+}
+
+#[test]
+fn when_notifications_are_delivered_then_waiting_stops_early() {
+    let queue = NotificationQueue::new("Test", 2, 0, Duration::from_secs(10), |_| {
+        return Ok(());
+    })
+    .unwrap();
+    queue.send(String::from("first")).unwrap();
+    queue.send(String::from("second")).unwrap();
+
+    let start = Instant::now();
+    queue.wait_until_idle(start + Duration::from_secs(10));
+
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
@@ -0,0 +1,54 @@
+use std::error::Error;
+
+use crate::auth_message_parser::AuthMessageParser;
+use crate::auth_monitor_config::AuthMonitorConfig;
+use crate::auth_monitor_params::AuthMonitorParams;
+
+const UNKNOWN_VALUE: &str = "unknown";
+
+pub struct PatternTester {
+    auth_message_parser: AuthMessageParser,
+    pub lines: Vec<String>,
+}
+
+impl PatternTester {
+    pub fn from_arguments(arguments: &[String]) -> Result<PatternTester, Box<dyn Error>> {
+        let mut params = AuthMonitorParams::default();
+        if let Some(config_path) = AuthMonitorParams::find_config_path(arguments)? {
+            AuthMonitorConfig::load(&config_path, &mut params)?;
+        }
+        let lines = arguments
+            .iter()
+            .filter(|argument| {
+                return !AuthMonitorParams::is_option(argument);
+            })
+            .cloned()
+            .collect();
+        return Ok(PatternTester {
+            auth_message_parser: AuthMessageParser::with_rules(
+                &params.patterns,
+                &params.ignore_users,
+                &params.ignore_services,
+            ),
+            lines,
+        });
+    }
+
+    pub fn classify(&self, line: &str) -> String {
+        if let Some(message) = self.auth_message_parser.parse_auth_failed_message(line) {
+            return format!(
+                "failed (user={}, service={})",
+                message.user.as_deref().unwrap_or(UNKNOWN_VALUE),
+                message.service.as_deref().unwrap_or(UNKNOWN_VALUE)
+            );
+        }
+        if let Some(user) = self.auth_message_parser.get_auth_succeeded_user(line) {
+            return format!("succeeded (user={})", user);
+        }
+        return String::from("no match");
+    }
+}
+
+#[cfg(test)]
+#[path = "./pattern_tester_tests.rs"]
+mod tests;
@@ -0,0 +1,39 @@
+use std::fs;
+
+use crate::pattern_tester::PatternTester;
+
+const FAILED_LINE: &str = "2024-06-03T21:32:01.912344+02:00 workstation unix_chkpwd[3214]: password check failed for user (john)";
+const SUCCEEDED_LINE: &str = "2024-06-03T21:33:01.912344+02:00 workstation sshd[3214]: Accepted password for john from 192.168.1.2 port 22 ssh2";
+const SSHD_FAILED_LINE: &str = "2024-06-03T21:34:01.912344+02:00 workstation sshd[3214]: Failed password for john from 192.168.1.2 port 22 ssh2";
+
+#[test]
+fn when_classifying_lines_then_failed_and_succeeded_attempts_are_recognized() {
+    let pattern_tester = PatternTester::from_arguments(&[String::from(FAILED_LINE)]).unwrap();
+    assert_eq!(pattern_tester.lines, [FAILED_LINE]);
+    assert_eq!(
+        pattern_tester.classify(FAILED_LINE),
+        "failed (user=john, service=unix_chkpwd)"
+    );
+    assert_eq!(
+        pattern_tester.classify(SUCCEEDED_LINE),
+        "succeeded (user=john)"
+    );
+    assert_eq!(pattern_tester.classify(SSHD_FAILED_LINE), "no match");
+}
+
+#[test]
+fn when_config_defines_patterns_then_they_are_used_for_classification() {
+    let config_path = "/tmp/auth-monitor-pattern-tester.toml";
+    fs::write(
+        config_path,
+        "[[patterns]]\nprefix = \"sshd\"\nmessage = \"Failed password for\"\n",
+    )
+    .unwrap();
+    let arguments = [format!("--config={}", config_path)];
+    let pattern_tester = PatternTester::from_arguments(&arguments).unwrap();
+    fs::remove_file(config_path).unwrap();
+    assert!(pattern_tester.lines.is_empty());
+    assert!(pattern_tester
+        .classify(SSHD_FAILED_LINE)
+        .starts_with("failed"));
+}
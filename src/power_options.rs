@@ -0,0 +1,127 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::desktop_options::SYSTEM_BUS_ADDRESS;
+
+const SUDO_BACKEND: &str = "sudo";
+const LOGIND_BACKEND: &str = "logind";
+const KERNEL_BACKEND: &str = "kernel";
+
+const NONE_INHIBITOR_POLICY: &str = "none";
+const IGNORE_INHIBITOR_POLICY: &str = "ignore";
+const FALLBACK_INHIBITOR_POLICY: &str = "fallback";
+
+/// How the poweroff action turns the machine off.
+const DEFAULT_SUDO_USER: &str = "auth-monitor";
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PowerBackend {
+    /// Runs "sudo systemctl poweroff", allowed by a sudoers entry.
+    Sudo,
+    /// Calls logind on the system bus, allowed by a polkit rule.
+    Logind,
+    /// Calls reboot(2) directly, allowed by the CAP_SYS_BOOT capability.
+    Kernel,
+}
+
+/// What the poweroff action does about logind inhibitor locks, which can
+/// delay or refuse a shutdown.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum InhibitorPolicy {
+    /// Inhibitors are not checked.
+    None,
+    /// Inhibitors are logged and the power off is forced past them.
+    Ignore,
+    /// Inhibitors are logged and blocking ones make the power off use the
+    /// fallback backend.
+    Fallback,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct PowerOptions {
+    pub backend: PowerBackend,
+    pub inhibitors: InhibitorPolicy,
+    pub fallback_backend: PowerBackend,
+    pub system_bus: String,
+    pub timeout_seconds: i32,
+    /// Account the daemon runs as, whose sudo rights are checked when the
+    /// check runs as root.
+    pub sudo_user: String,
+}
+
+impl Default for PowerOptions {
+    fn default() -> Self {
+        return PowerOptions {
+            backend: PowerBackend::Sudo,
+            inhibitors: InhibitorPolicy::None,
+            fallback_backend: PowerBackend::Kernel,
+            system_bus: String::from(SYSTEM_BUS_ADDRESS),
+            timeout_seconds: 5,
+            sudo_user: String::from(DEFAULT_SUDO_USER),
+        };
+    }
+}
+
+impl Display for PowerOptions {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            formatter,
+            "power-backend={}, power-inhibitors={}, power-fallback-backend={}, power-system-bus={}, power-timeout-seconds={}, power-sudo-user={}",
+            self.backend,
+            self.inhibitors,
+            self.fallback_backend,
+            self.system_bus,
+            self.timeout_seconds,
+            self.sudo_user
+        );
+    }
+}
+
+impl Display for PowerBackend {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PowerBackend::Sudo => SUDO_BACKEND,
+            PowerBackend::Logind => LOGIND_BACKEND,
+            PowerBackend::Kernel => KERNEL_BACKEND,
+        };
+        return write!(formatter, "{}", name);
+    }
+}
+
+impl FromStr for PowerBackend {
+    type Err = Box<dyn Error>;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return match name {
+            SUDO_BACKEND => Ok(PowerBackend::Sudo),
+            LOGIND_BACKEND => Ok(PowerBackend::Logind),
+            KERNEL_BACKEND => Ok(PowerBackend::Kernel),
+            _ => Err(format!("Unknown power backend {}", name))?,
+        };
+    }
+}
+
+impl Display for InhibitorPolicy {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            InhibitorPolicy::None => NONE_INHIBITOR_POLICY,
+            InhibitorPolicy::Ignore => IGNORE_INHIBITOR_POLICY,
+            InhibitorPolicy::Fallback => FALLBACK_INHIBITOR_POLICY,
+        };
+        return write!(formatter, "{}", name);
+    }
+}
+
+impl FromStr for InhibitorPolicy {
+    type Err = Box<dyn Error>;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return match name {
+            NONE_INHIBITOR_POLICY => Ok(InhibitorPolicy::None),
+            IGNORE_INHIBITOR_POLICY => Ok(InhibitorPolicy::Ignore),
+            FALLBACK_INHIBITOR_POLICY => Ok(InhibitorPolicy::Fallback),
+            _ => Err(format!("Unknown inhibitor policy {}", name))?,
+        };
+    }
+}
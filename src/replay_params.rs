@@ -0,0 +1,67 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::auth_message_parser::AuthFailedMessagePattern;
+use crate::auth_monitor_config::AuthMonitorConfig;
+use crate::auth_monitor_options::AuthMonitorOptions;
+use crate::auth_monitor_params::{AuthMonitorParams, CONFIG_OPTION};
+
+pub struct ReplayParams {
+    pub filepaths: Vec<String>,
+    pub options: AuthMonitorOptions,
+    pub patterns: Vec<AuthFailedMessagePattern>,
+    pub ignore_users: Vec<String>,
+    pub ignore_services: Vec<String>,
+}
+
+impl ReplayParams {
+    /// Starts from the configuration file, like the daemon, so that the replay
+    /// counts the same messages the deployed daemon would.
+    pub fn from_arguments(arguments: &[String]) -> Result<ReplayParams, Box<dyn Error>> {
+        let mut config = AuthMonitorParams::default();
+        if let Some(config_path) = AuthMonitorParams::find_config_path(arguments)? {
+            AuthMonitorConfig::load(&config_path, &mut config)?;
+        }
+        let mut params = ReplayParams {
+            filepaths: Vec::new(),
+            options: config.options,
+            patterns: config.patterns,
+            ignore_users: config.ignore_users,
+            ignore_services: config.ignore_services,
+        };
+        for argument in arguments {
+            if AuthMonitorParams::is_option(argument) {
+                let (option_name, _) = AuthMonitorParams::split_option(argument);
+                if option_name.trim_start_matches('-') == CONFIG_OPTION {
+                    continue;
+                }
+                AuthMonitorParams::parse_option(&mut params.options, argument)?;
+            } else {
+                params.filepaths.push(String::from(argument));
+            }
+        }
+        if params.filepaths.is_empty() {
+            Err("No files to replay specified")?;
+        }
+        AuthMonitorParams::validate_options(&params.options)?;
+        return Ok(params);
+    }
+}
+
+impl Display for ReplayParams {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            formatter,
+            "files={}, patterns={}, ignore-users={}, ignore-services={}, options: {}",
+            self.filepaths.join(" "),
+            self.patterns.len(),
+            self.ignore_users.join(","),
+            self.ignore_services.join(","),
+            self.options
+        );
+    }
+}
+
+#[cfg(test)]
+#[path = "./replay_params_tests.rs"]
+mod tests;
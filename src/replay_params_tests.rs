@@ -0,0 +1,80 @@
+use std::env::temp_dir;
+use std::fs;
+
+use crate::assert_error;
+use crate::auth_monitor_params::{MAX_FAILED_ATTEMPTS_OPTION, RESET_AFTER_SECONDS_OPTION};
+use crate::replay_params::ReplayParams;
+
+#[test]
+fn when_parsing_no_files_then_return_no_files_error() {
+    let arguments = [format!("--{}=3", MAX_FAILED_ATTEMPTS_OPTION)];
+    assert_error!(
+        ReplayParams::from_arguments(&arguments),
+        "No files to replay specified"
+    );
+}
+
+#[test]
+fn when_parsing_files_and_options_then_return_params_with_all_files_in_order() {
+    let arguments = [
+        String::from("/var/log/auth.log.2.gz"),
+        format!("--{}=3", MAX_FAILED_ATTEMPTS_OPTION),
+        String::from("/var/log/auth.log.1"),
+        format!("--{}=60", RESET_AFTER_SECONDS_OPTION),
+        String::from("/var/log/auth.log"),
+    ];
+    let params = ReplayParams::from_arguments(&arguments).unwrap();
+    assert_eq!(
+        params.filepaths,
+        [
+            "/var/log/auth.log.2.gz",
+            "/var/log/auth.log.1",
+            "/var/log/auth.log"
+        ]
+    );
+    assert_eq!(params.options.max_failed_attempts, 3);
+    assert_eq!(params.options.reset_after_seconds, 60);
+}
+
+#[test]
+fn when_parsing_invalid_option_value_then_return_same_error_as_monitor() {
+    let arguments = [
+        String::from("/var/log/auth.log"),
+        format!("--{}=0", MAX_FAILED_ATTEMPTS_OPTION),
+    ];
+    assert_error!(
+        ReplayParams::from_arguments(&arguments),
+        format!("{} must be greater than 0", MAX_FAILED_ATTEMPTS_OPTION)
+    );
+}
+
+#[test]
+fn when_parsing_config_option_then_params_have_configured_filters_and_thresholds() {
+    let config_path = temp_dir().join("auth-monitor-replay-params-test.toml");
+    fs::write(
+        &config_path,
+        r#"
+[thresholds]
+max-failed-attempts = 4
+
+[filters]
+ignore-users = ["guest"]
+ignore-services = ["kde"]
+"#,
+    )
+    .unwrap();
+    let arguments = [
+        format!("--config={}", config_path.display()),
+        format!("--{}=60", RESET_AFTER_SECONDS_OPTION),
+        String::from("/var/log/auth.log"),
+    ];
+
+    let params = ReplayParams::from_arguments(&arguments).unwrap();
+    fs::remove_file(&config_path).unwrap();
+
+    assert_eq!(params.filepaths, ["/var/log/auth.log"]);
+    assert_eq!(params.options.max_failed_attempts, 4);
+    assert_eq!(params.options.reset_after_seconds, 60);
+    assert_eq!(params.ignore_users, ["guest"]);
+    assert_eq!(params.ignore_services, ["kde"]);
+}
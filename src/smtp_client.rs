@@ -0,0 +1,222 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::time::Duration;
+
+use native_tls::{TlsConnector, TlsStream};
+
+const SMTP_PORT: u16 = 25;
+
+const NONE_SECURITY: &str = "none";
+const STARTTLS_SECURITY: &str = "starttls";
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SmtpSecurity {
+    None,
+    StartTls,
+}
+
+pub struct SmtpServer {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub credentials: Option<(String, String)>,
+    pub timeout: Duration,
+}
+
+pub struct SmtpClient {}
+
+enum SmtpStream {
+    Plain(BufReader<TcpStream>),
+    Tls(BufReader<TlsStream<TcpStream>>),
+}
+
+impl SmtpStream {
+    fn read_response(&mut self, expected_code: &str) -> Result<String, Box<dyn Error>> {
+        let mut response = String::new();
+        loop {
+            let mut line = String::new();
+            let bytes_read = match self {
+                SmtpStream::Plain(reader) => reader.read_line(&mut line)?,
+                SmtpStream::Tls(reader) => reader.read_line(&mut line)?,
+            };
+            if bytes_read == 0 {
+                Err("Connection closed by server")?;
+            }
+            response.push_str(&line);
+            // Continuation lines of a multiline response have "-" after the code.
+            if line.len() < 4 || line.as_bytes()[3] != b'-' {
+                break;
+            }
+        }
+        if !response.starts_with(expected_code) {
+            Err(format!("Unexpected response \"{}\"", response.trim_end()))?;
+        }
+        return Ok(response);
+    }
+
+    fn write(&mut self, data: &str) -> Result<(), Box<dyn Error>> {
+        match self {
+            SmtpStream::Plain(reader) => reader.get_mut().write_all(data.as_bytes())?,
+            SmtpStream::Tls(reader) => reader.get_mut().write_all(data.as_bytes())?,
+        }
+        return Ok(());
+    }
+
+    fn command(&mut self, command: &str, expected_code: &str) -> Result<String, Box<dyn Error>> {
+        self.write(&format!("{}\r\n", command))?;
+        return self.read_response(expected_code);
+    }
+
+    fn start_tls(self, host: &str) -> Result<SmtpStream, Box<dyn Error>> {
+        let stream = match self {
+            SmtpStream::Plain(reader) => reader.into_inner(),
+            SmtpStream::Tls(_) => Err("TLS is already active")?,
+        };
+        let connector = TlsConnector::new()?;
+        return Ok(SmtpStream::Tls(BufReader::new(
+            connector.connect(host, stream)?,
+        )));
+    }
+}
+
+impl SmtpClient {
+    pub fn send(
+        server: &SmtpServer,
+        hello_name: &str,
+        from: &str,
+        recipients: &[String],
+        message: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        if server.credentials.is_some() && server.security == SmtpSecurity::None {
+            Err("Refusing to send SMTP credentials over an unencrypted connection")?;
+        }
+        let mut stream = SmtpStream::Plain(BufReader::new(Self::connect(server)?));
+        stream.read_response("220")?;
+        let hello = format!("EHLO {}", hello_name);
+        let mut extensions = stream.command(&hello, "250")?;
+        if server.security == SmtpSecurity::StartTls {
+            stream.command("STARTTLS", "220")?;
+            stream = stream.start_tls(&server.host)?;
+            extensions = stream.command(&hello, "250")?;
+        }
+        if let Some((username, password)) = &server.credentials {
+            if !extensions.contains("AUTH") {
+                Err("Server does not support authentication")?;
+            }
+            let token = Self::base64(format!("\0{}\0{}", username, password).as_bytes());
+            stream.command(&format!("AUTH PLAIN {}", token), "235")?;
+        }
+        stream.command(&format!("MAIL FROM:<{}>", from), "250")?;
+        for recipient in recipients {
+            stream.command(&format!("RCPT TO:<{}>", recipient), "25")?;
+        }
+        stream.command("DATA", "354")?;
+        stream.write(&Self::dot_stuff(message))?;
+        stream.command(".", "250")?;
+        // The message has been accepted, a failed QUIT does not matter.
+        let _ = stream.command("QUIT", "221");
+        return Ok(());
+    }
+
+    fn connect(server: &SmtpServer) -> Result<TcpStream, Box<dyn Error>> {
+        let mut last_error = None;
+        for address in (server.host.as_str(), server.port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, server.timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(server.timeout))?;
+                    stream.set_write_timeout(Some(server.timeout))?;
+                    return Ok(stream);
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+        return match last_error {
+            Some(error) => Err(format!(
+                "Unable to connect to {}:{}: {}",
+                server.host, server.port, error
+            ))?,
+            None => Err(format!("Unable to resolve {}", server.host))?,
+        };
+    }
+
+    pub fn parse_address(address: &str) -> Result<(String, u16), Box<dyn Error>> {
+        let (host, port) = match address.rsplit_once(':') {
+            Some((host, port)) => match port.parse::<u16>() {
+                Ok(port) => (host, port),
+                Err(_) => Err(format!("{} has an invalid port", address))?,
+            },
+            None => (address, SMTP_PORT),
+        };
+        if host.is_empty() {
+            Err(format!("{} has an invalid host", address))?;
+        }
+        return Ok((String::from(host), port));
+    }
+
+    /// Converts line endings to CRLF and escapes lines starting with a dot,
+    /// which would otherwise end the message early.
+    fn dot_stuff(message: &str) -> String {
+        let mut data = String::new();
+        for line in message.lines() {
+            if line.starts_with('.') {
+                data.push('.');
+            }
+            data.push_str(line);
+            data.push_str("\r\n");
+        }
+        return data;
+    }
+
+    fn base64(data: &[u8]) -> String {
+        let mut encoded = String::new();
+        for chunk in data.chunks(3) {
+            let bytes = [
+                chunk[0],
+                *chunk.get(1).unwrap_or(&0),
+                *chunk.get(2).unwrap_or(&0),
+            ];
+            let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+            for index in 0..4 {
+                if index <= chunk.len() {
+                    let position = (value >> (18 - 6 * index)) & 0x3f;
+                    encoded.push(BASE64_ALPHABET[position as usize] as char);
+                } else {
+                    encoded.push('=');
+                }
+            }
+        }
+        return encoded;
+    }
+}
+
+impl Display for SmtpSecurity {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SmtpSecurity::None => NONE_SECURITY,
+            SmtpSecurity::StartTls => STARTTLS_SECURITY,
+        };
+        return write!(formatter, "{}", name);
+    }
+}
+
+impl FromStr for SmtpSecurity {
+    type Err = Box<dyn Error>;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return match name {
+            NONE_SECURITY => Ok(SmtpSecurity::None),
+            STARTTLS_SECURITY => Ok(SmtpSecurity::StartTls),
+            _ => Err(format!("Unknown SMTP security {}", name))?,
+        };
+    }
+}
+
+#[cfg(test)]
+#[path = "./smtp_client_tests.rs"]
+mod tests;
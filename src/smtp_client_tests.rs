@@ -0,0 +1,188 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+use crate::assert_error;
+use crate::smtp_client::{SmtpClient, SmtpSecurity, SmtpServer};
+
+/// Accepts a single SMTP session with scripted replies and returns the
+/// commands and the message data the client sent.
+fn serve_session(listener: TcpListener) -> (Vec<String>, String) {
+    let (stream, _) = listener.accept().unwrap();
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut commands = Vec::new();
+    let mut data = String::new();
+    writer.write_all(b"220 localhost ESMTP\r\n").unwrap();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        let command = String::from(line.trim_end());
+        let reply: &[u8] = match command.split(' ').next().unwrap() {
+            "EHLO" => b"250-localhost\r\n250-AUTH PLAIN\r\n250 8BITMIME\r\n",
+            "AUTH" => b"235 Authentication successful\r\n",
+            "DATA" => b"354 End data with <CR><LF>.<CR><LF>\r\n",
+            "QUIT" => b"221 Bye\r\n",
+            _ => b"250 OK\r\n",
+        };
+        commands.push(command.clone());
+        writer.write_all(reply).unwrap();
+        if command == "DATA" {
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == ".\r\n" {
+                    break;
+                }
+                data.push_str(&line);
+            }
+            writer.write_all(b"250 Queued\r\n").unwrap();
+        }
+        if command == "QUIT" {
+            break;
+        }
+    }
+    return (commands, data);
+}
+
+fn server(listener: &TcpListener, credentials: Option<(String, String)>) -> SmtpServer {
+    let address = listener.local_addr().unwrap();
+    return SmtpServer {
+        host: address.ip().to_string(),
+        port: address.port(),
+        security: SmtpSecurity::None,
+        credentials,
+        timeout: Duration::from_secs(5),
+    };
+}
+
+#[test]
+fn when_sending_message_then_server_receives_envelope_and_dot_stuffed_data() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = server(&listener, None);
+    let session = thread::spawn(move || {
+        return serve_session(listener);
+    });
+    let recipients = [
+        String::from("admin@example.com"),
+        String::from("ops@example.com"),
+    ];
+    let message = "Subject: Alert\r\n\r\nFirst line\r\n.hidden line\r\nLast line";
+
+    let result = SmtpClient::send(
+        &server,
+        "workstation",
+        "monitor@example.com",
+        &recipients,
+        message,
+    );
+
+    let (commands, data) = session.join().unwrap();
+    assert!(result.is_ok(), "{:?}", result.err());
+    assert_eq!(
+        commands,
+        [
+            "EHLO workstation",
+            "MAIL FROM:<monitor@example.com>",
+            "RCPT TO:<admin@example.com>",
+            "RCPT TO:<ops@example.com>",
+            "DATA",
+            "QUIT",
+        ]
+    );
+    assert_eq!(
+        data,
+        "Subject: Alert\r\n\r\nFirst line\r\n..hidden line\r\nLast line\r\n"
+    );
+}
+
+#[test]
+fn when_server_rejects_recipient_then_return_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = server(&listener, None);
+    let session = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        writer.write_all(b"220 localhost ESMTP\r\n").unwrap();
+        for reply in ["250 localhost\r\n", "250 OK\r\n", "550 No such user\r\n"] {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            writer.write_all(reply.as_bytes()).unwrap();
+        }
+    });
+    let recipients = [String::from("nobody@example.com")];
+
+    let result = SmtpClient::send(
+        &server,
+        "workstation",
+        "monitor@example.com",
+        &recipients,
+        "",
+    );
+
+    session.join().unwrap();
+    assert_error!(result, "Unexpected response \"550 No such user\"");
+}
+
+#[test]
+fn when_credentials_are_set_without_encryption_then_refuse_to_connect() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let credentials = Some((String::from("alice"), String::from("secret")));
+    let server = server(&listener, credentials);
+    listener.set_nonblocking(true).unwrap();
+
+    let result = SmtpClient::send(&server, "workstation", "monitor@example.com", &[], "");
+
+    assert_error!(
+        result,
+        "Refusing to send SMTP credentials over an unencrypted connection"
+    );
+    assert!(listener.accept().is_err());
+}
+
+#[test]
+fn when_server_is_not_listening_then_return_connection_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = server(&listener, None);
+    drop(listener);
+
+    let result = SmtpClient::send(&server, "workstation", "monitor@example.com", &[], "");
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.starts_with("Unable to connect to 127.0.0.1:"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn when_parsing_address_then_return_host_and_port() {
+    assert_eq!(
+        SmtpClient::parse_address("mail.example.com").unwrap(),
+        (String::from("mail.example.com"), 25)
+    );
+    assert_eq!(
+        SmtpClient::parse_address("mail.example.com:587").unwrap(),
+        (String::from("mail.example.com"), 587)
+    );
+    assert_error!(
+        SmtpClient::parse_address("mail.example.com:smtp"),
+        "mail.example.com:smtp has an invalid port"
+    );
+    assert_error!(SmtpClient::parse_address(":25"), ":25 has an invalid host");
+}
+
+#[test]
+fn when_parsing_security_then_return_security_or_error() {
+    assert_eq!("none".parse::<SmtpSecurity>().unwrap(), SmtpSecurity::None);
+    assert_eq!(
+        "starttls".parse::<SmtpSecurity>().unwrap(),
+        SmtpSecurity::StartTls
+    );
+    assert_error!("tls".parse::<SmtpSecurity>(), "Unknown SMTP security tls");
+}
@@ -0,0 +1,124 @@
+use std::env;
+use std::error::Error;
+use std::os::linux::net::SocketAddrExt;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::process;
+use std::time::{Duration, Instant};
+
+use crate::logger::LogEntry;
+
+const NOTIFY_SOCKET_VARIABLE: &str = "NOTIFY_SOCKET";
+const WATCHDOG_USEC_VARIABLE: &str = "WATCHDOG_USEC";
+const WATCHDOG_PID_VARIABLE: &str = "WATCHDOG_PID";
+const SYSTEMD_PID: libc::pid_t = 1;
+const ABSTRACT_SOCKET_PREFIX: char = '@';
+
+const READY_MESSAGE: &str = "READY=1";
+const WATCHDOG_MESSAGE: &str = "WATCHDOG=1";
+const STOPPING_MESSAGE: &str = "STOPPING=1";
+
+/// Implements the sd_notify protocol. When the daemon is not started by
+/// systemd, NOTIFY_SOCKET is not set and all notifications are skipped.
+pub struct SystemdNotify {
+    socket: Option<(UnixDatagram, SocketAddr)>,
+    watchdog_interval: Option<Duration>,
+    last_watchdog: Option<Instant>,
+    last_status: String,
+}
+
+impl SystemdNotify {
+    pub fn from_environment() -> Result<SystemdNotify, Box<dyn Error>> {
+        let socket_path = env::var(NOTIFY_SOCKET_VARIABLE).ok();
+        let watchdog_interval = Self::get_watchdog_interval();
+        return Self::new(socket_path.as_deref(), watchdog_interval);
+    }
+
+    pub fn new(
+        socket_path: Option<&str>,
+        watchdog_interval: Option<Duration>,
+    ) -> Result<SystemdNotify, Box<dyn Error>> {
+        let socket = match socket_path {
+            Some(path) if !path.is_empty() => {
+                let address = match path.strip_prefix(ABSTRACT_SOCKET_PREFIX) {
+                    Some(name) => SocketAddr::from_abstract_name(name)?,
+                    None => SocketAddr::from_pathname(path)?,
+                };
+                Some((UnixDatagram::unbound()?, address))
+            }
+            _ => None,
+        };
+        return Ok(SystemdNotify {
+            socket,
+            watchdog_interval,
+            last_watchdog: None,
+            last_status: String::new(),
+        });
+    }
+
+    fn get_watchdog_interval() -> Option<Duration> {
+        if let Ok(pid) = env::var(WATCHDOG_PID_VARIABLE) {
+            if pid != process::id().to_string() {
+                return None;
+            }
+        }
+        let microseconds = env::var(WATCHDOG_USEC_VARIABLE).ok()?.parse::<u64>().ok()?;
+        if microseconds == 0 {
+            return None;
+        }
+        return Some(Duration::from_micros(microseconds));
+    }
+
+    pub fn ready(&mut self, status: &str) {
+        self.last_status = String::from(status);
+        self.notify(&format!("{}\nSTATUS={}", READY_MESSAGE, status));
+    }
+
+    pub fn stopping(&self) {
+        self.notify(STOPPING_MESSAGE);
+    }
+
+    pub fn status(&mut self, status: &str) {
+        if self.last_status == status {
+            return;
+        }
+        self.last_status = String::from(status);
+        self.notify(&format!("STATUS={}", status));
+    }
+
+    /// Whether a stop signal from the given sender was requested through
+    /// systemd. Without systemd, any sender may stop the daemon.
+    pub fn is_requested_stop(&self, sender: Option<libc::pid_t>) -> bool {
+        return self.socket.is_none() || sender == Some(SYSTEMD_PID);
+    }
+
+    /// Sends a keepalive at half of the watchdog interval requested by systemd.
+    pub fn watchdog(&mut self) {
+        let interval = match self.watchdog_interval {
+            Some(interval) => interval,
+            None => return,
+        };
+        let now = Instant::now();
+        if let Some(last_watchdog) = self.last_watchdog {
+            if now.duration_since(last_watchdog) < interval / 2 {
+                return;
+            }
+        }
+        self.last_watchdog = Some(now);
+        self.notify(WATCHDOG_MESSAGE);
+    }
+
+    fn notify(&self, message: &str) {
+        let (socket, address) = match &self.socket {
+            Some(socket) => socket,
+            None => return,
+        };
+        if let Err(error) = socket.send_to_addr(message.as_bytes(), address) {
+            let message = format!("Error sending notification to systemd: {}", error);
+            LogEntry::error("notify_error", &message).log();
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./systemd_notify_tests.rs"]
+mod tests;
@@ -0,0 +1,91 @@
+use std::fs;
+use std::os::unix::net::UnixDatagram;
+use std::time::Duration;
+
+use crate::systemd_notify::SystemdNotify;
+
+struct NotifySocket {
+    path: String,
+    socket: UnixDatagram,
+}
+
+impl NotifySocket {
+    fn new(name: &str) -> NotifySocket {
+        let path = format!("/tmp/auth-monitor-notify-{}.sock", name);
+        let _ = fs::remove_file(&path);
+        let socket = UnixDatagram::bind(&path).expect("Error binding notify socket");
+        socket.set_nonblocking(true).unwrap();
+        return NotifySocket { path, socket };
+    }
+
+    fn receive(&self) -> Vec<String> {
+        let mut messages = Vec::new();
+        let mut buffer = [0u8; 1024];
+        while let Ok(size) = self.socket.recv(&mut buffer) {
+            messages.push(String::from_utf8_lossy(&buffer[..size]).to_string());
+        }
+        return messages;
+    }
+}
+
+impl Drop for NotifySocket {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn when_ready_and_status_are_sent_then_socket_receives_them_once() {
+    let notify_socket = NotifySocket::new("status");
+    let mut systemd_notify = SystemdNotify::new(Some(&notify_socket.path), None).unwrap();
+
+    systemd_notify.ready("Failed attempts 0/5");
+    systemd_notify.status("Failed attempts 0/5");
+    systemd_notify.status("Failed attempts 1/5");
+    systemd_notify.status("Failed attempts 1/5");
+    systemd_notify.watchdog();
+    systemd_notify.stopping();
+
+    assert_eq!(
+        notify_socket.receive(),
+        [
+            "READY=1\nSTATUS=Failed attempts 0/5",
+            "STATUS=Failed attempts 1/5",
+            "STOPPING=1"
+        ]
+    );
+}
+
+#[test]
+fn when_watchdog_is_enabled_then_keepalive_is_sent_at_half_of_interval() {
+    let notify_socket = NotifySocket::new("watchdog");
+    let interval = Duration::from_millis(200);
+    let mut systemd_notify = SystemdNotify::new(Some(&notify_socket.path), Some(interval)).unwrap();
+
+    systemd_notify.watchdog();
+    systemd_notify.watchdog();
+    assert_eq!(notify_socket.receive(), ["WATCHDOG=1"]);
+
+    std::thread::sleep(interval / 2);
+    systemd_notify.watchdog();
+    assert_eq!(notify_socket.receive(), ["WATCHDOG=1"]);
+}
+
+#[test]
+fn when_notify_socket_is_not_set_then_notifications_are_skipped() {
+    let mut systemd_notify = SystemdNotify::new(None, Some(Duration::from_secs(1))).unwrap();
+    systemd_notify.ready("Failed attempts 0/5");
+    systemd_notify.watchdog();
+}
+
+#[test]
+fn when_supervised_then_only_systemd_may_request_stop() {
+    let notify_socket = NotifySocket::new("stop");
+    let supervised = SystemdNotify::new(Some(&notify_socket.path), None).unwrap();
+    let unsupervised = SystemdNotify::new(None, None).unwrap();
+
+    assert!(supervised.is_requested_stop(Some(1)));
+    assert!(!supervised.is_requested_stop(Some(4242)));
+    assert!(!supervised.is_requested_stop(None));
+    assert!(unsupervised.is_requested_stop(Some(4242)));
+}
@@ -0,0 +1,120 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, SystemTime};
+
+use crate::tamper_options::{TamperOptions, TamperResponse};
+
+/// A sign that logging of the monitored file has been interfered with.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TamperEvent {
+    Deleted,
+    Truncated,
+    Absent,
+    Silent,
+    Terminated,
+}
+
+/// Reports the absence of the monitored file and the silence of its writers
+/// once they exceed their thresholds, and again only after they have ended.
+#[derive(Default)]
+pub struct TamperDetector {
+    absence_reported: bool,
+    silence_reported: bool,
+}
+
+impl TamperDetector {
+    pub fn check(
+        &mut self,
+        options: &TamperOptions,
+        missing_since: Option<SystemTime>,
+        last_line_time: SystemTime,
+        now: SystemTime,
+    ) -> Vec<TamperEvent> {
+        let mut events = Vec::new();
+        match missing_since {
+            Some(since) => {
+                let exceeded = Self::exceeds(since, options.absent_seconds, now);
+                if exceeded && !self.absence_reported {
+                    self.absence_reported = true;
+                    events.push(TamperEvent::Absent);
+                }
+            }
+            None => self.absence_reported = false,
+        }
+        // A missing file is absent rather than silent.
+        let silent =
+            missing_since.is_none() && Self::exceeds(last_line_time, options.silent_seconds, now);
+        if silent && !self.silence_reported {
+            events.push(TamperEvent::Silent);
+        }
+        self.silence_reported = silent;
+        return events;
+    }
+
+    /// Whether the terminations within the window reach the maximum.
+    pub fn too_many_terminations(
+        options: &TamperOptions,
+        terminations: &[SystemTime],
+        now: SystemTime,
+    ) -> bool {
+        let recent = terminations
+            .iter()
+            .filter(|time| {
+                return !Self::exceeds(**time, options.terminations_window_seconds, now);
+            })
+            .count();
+        return recent >= options.max_terminations as usize;
+    }
+
+    fn exceeds(since: SystemTime, seconds: i32, now: SystemTime) -> bool {
+        let elapsed = now.duration_since(since).unwrap_or(Duration::ZERO);
+        return elapsed.as_secs() >= seconds as u64;
+    }
+}
+
+impl TamperEvent {
+    pub fn response<'a>(&self, options: &'a TamperOptions) -> &'a TamperResponse {
+        return match self {
+            TamperEvent::Deleted => &options.deleted,
+            TamperEvent::Truncated => &options.truncated,
+            TamperEvent::Absent => &options.absent,
+            TamperEvent::Silent => &options.silent,
+            TamperEvent::Terminated => &options.terminated,
+        };
+    }
+
+    pub fn description(&self, options: &TamperOptions) -> String {
+        return match self {
+            TamperEvent::Deleted => String::from("Monitored file has been deleted"),
+            TamperEvent::Truncated => String::from("Monitored file has been truncated"),
+            TamperEvent::Absent => format!(
+                "Monitored file has been missing for {} second(s)",
+                options.absent_seconds
+            ),
+            TamperEvent::Silent => format!(
+                "Nothing has been logged to the monitored file for {} second(s)",
+                options.silent_seconds
+            ),
+            TamperEvent::Terminated => format!(
+                "Monitoring process has been terminated unexpectedly {} time(s) within {} second(s)",
+                options.max_terminations, options.terminations_window_seconds
+            ),
+        };
+    }
+}
+
+impl Display for TamperEvent {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TamperEvent::Deleted => "deleted",
+            TamperEvent::Truncated => "truncated",
+            TamperEvent::Absent => "absent",
+            TamperEvent::Silent => "silent",
+            TamperEvent::Terminated => "terminated",
+        };
+        return write!(formatter, "{}", name);
+    }
+}
+
+#[cfg(test)]
+#[path = "./tamper_detector_tests.rs"]
+mod tests;
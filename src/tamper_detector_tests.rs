@@ -0,0 +1,113 @@
+use std::time::{Duration, SystemTime};
+
+use crate::tamper_detector::{TamperDetector, TamperEvent};
+use crate::tamper_options::TamperOptions;
+
+fn options() -> TamperOptions {
+    return TamperOptions {
+        absent_seconds: 60,
+        silent_seconds: 600,
+        ..TamperOptions::default()
+    };
+}
+
+fn seconds(seconds: u64) -> SystemTime {
+    return SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + seconds);
+}
+
+#[test]
+fn when_file_is_missing_beyond_threshold_then_absence_is_reported_once() {
+    let mut detector = TamperDetector::default();
+    let options = options();
+
+    let before = detector.check(&options, Some(seconds(0)), seconds(0), seconds(59));
+    let after = detector.check(&options, Some(seconds(0)), seconds(0), seconds(60));
+    let again = detector.check(&options, Some(seconds(0)), seconds(0), seconds(120));
+
+    assert_eq!(before, vec![]);
+    assert_eq!(after, vec![TamperEvent::Absent]);
+    assert_eq!(again, vec![]);
+}
+
+#[test]
+fn when_file_reappears_then_absence_is_reported_again_next_time() {
+    let mut detector = TamperDetector::default();
+    let options = options();
+
+    detector.check(&options, Some(seconds(0)), seconds(0), seconds(60));
+    detector.check(&options, None, seconds(70), seconds(70));
+    let events = detector.check(&options, Some(seconds(100)), seconds(70), seconds(160));
+
+    assert_eq!(events, vec![TamperEvent::Absent]);
+}
+
+#[test]
+fn when_nothing_is_logged_beyond_threshold_then_silence_is_reported_once() {
+    let mut detector = TamperDetector::default();
+    let options = options();
+
+    let before = detector.check(&options, None, seconds(0), seconds(599));
+    let after = detector.check(&options, None, seconds(0), seconds(600));
+    let again = detector.check(&options, None, seconds(0), seconds(1200));
+    detector.check(&options, None, seconds(1300), seconds(1300));
+    let next = detector.check(&options, None, seconds(1300), seconds(1900));
+
+    assert_eq!(before, vec![]);
+    assert_eq!(after, vec![TamperEvent::Silent]);
+    assert_eq!(again, vec![]);
+    assert_eq!(next, vec![TamperEvent::Silent]);
+}
+
+#[test]
+fn when_file_is_missing_then_silence_is_not_reported() {
+    let mut detector = TamperDetector::default();
+    let options = TamperOptions {
+        absent_seconds: 6000,
+        ..options()
+    };
+
+    let events = detector.check(&options, Some(seconds(0)), seconds(0), seconds(1000));
+
+    assert_eq!(events, vec![]);
+}
+
+#[test]
+fn when_describing_events_then_thresholds_are_included() {
+    let options = options();
+
+    assert_eq!(
+        TamperEvent::Absent.description(&options),
+        "Monitored file has been missing for 60 second(s)"
+    );
+    assert_eq!(
+        TamperEvent::Silent.description(&options),
+        "Nothing has been logged to the monitored file for 600 second(s)"
+    );
+    assert_eq!(TamperEvent::Truncated.to_string(), "truncated");
+}
+
+#[test]
+fn when_terminations_within_window_reach_maximum_then_there_are_too_many() {
+    let options = TamperOptions {
+        max_terminations: 2,
+        terminations_window_seconds: 600,
+        ..options()
+    };
+    let terminations = [seconds(0), seconds(500), seconds(700)];
+
+    assert!(!TamperDetector::too_many_terminations(
+        &options,
+        &terminations[..2],
+        seconds(650)
+    ));
+    assert!(TamperDetector::too_many_terminations(
+        &options,
+        &terminations,
+        seconds(800)
+    ));
+    assert!(!TamperDetector::too_many_terminations(
+        &options,
+        &terminations,
+        seconds(1150)
+    ));
+}
@@ -0,0 +1,117 @@
+use std::fmt::{Display, Formatter};
+
+use crate::action::Action;
+
+const IGNORE_RESPONSE: &str = "ignore";
+const COUNT_RESPONSE: &str = "count";
+
+/// What to do when the monitored file shows signs of tampering.
+#[derive(Clone, PartialEq, Debug)]
+pub enum TamperResponse {
+    /// Only log the event.
+    Ignore,
+    /// Count the event as a failed authentication attempt.
+    Count,
+    /// Execute the actions.
+    Actions(Vec<Action>),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TamperOptions {
+    /// The file has been deleted. Moving it, as log rotation does, is not
+    /// tampering on its own, but leaves the file absent until recreated.
+    pub deleted: TamperResponse,
+    pub truncated: TamperResponse,
+    /// The file has been missing for longer than `absent_seconds`.
+    pub absent: TamperResponse,
+    pub absent_seconds: i32,
+    /// No line has been written for longer than `silent_seconds`.
+    pub silent: TamperResponse,
+    pub silent_seconds: i32,
+    /// The daemon has been terminated unexpectedly `max_terminations` times
+    /// within `terminations_window_seconds`.
+    pub terminated: TamperResponse,
+    pub max_terminations: i32,
+    pub terminations_window_seconds: i32,
+}
+
+impl TamperResponse {
+    pub fn parse(name: &str) -> Option<TamperResponse> {
+        return match name {
+            IGNORE_RESPONSE => Some(TamperResponse::Ignore),
+            COUNT_RESPONSE => Some(TamperResponse::Count),
+            _ => None,
+        };
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        return match self {
+            TamperResponse::Actions(actions) => actions,
+            _ => &[],
+        };
+    }
+}
+
+impl TamperOptions {
+    pub fn responses(&self) -> [&TamperResponse; 5] {
+        return [
+            &self.deleted,
+            &self.truncated,
+            &self.absent,
+            &self.silent,
+            &self.terminated,
+        ];
+    }
+}
+
+impl Default for TamperOptions {
+    fn default() -> Self {
+        return TamperOptions {
+            deleted: TamperResponse::Ignore,
+            truncated: TamperResponse::Ignore,
+            absent: TamperResponse::Ignore,
+            absent_seconds: 300,
+            silent: TamperResponse::Ignore,
+            silent_seconds: 86400,
+            terminated: TamperResponse::Ignore,
+            max_terminations: 3,
+            terminations_window_seconds: 3600,
+        };
+    }
+}
+
+impl Display for TamperResponse {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            TamperResponse::Ignore => write!(formatter, "{}", IGNORE_RESPONSE),
+            TamperResponse::Count => write!(formatter, "{}", COUNT_RESPONSE),
+            TamperResponse::Actions(actions) => write!(
+                formatter,
+                "{}",
+                actions
+                    .iter()
+                    .map(Action::to_string)
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+        };
+    }
+}
+
+impl Display for TamperOptions {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            formatter,
+            "tamper-deleted={}, tamper-truncated={}, tamper-absent={}, tamper-absent-seconds={}, tamper-silent={}, tamper-silent-seconds={}, tamper-terminated={}, tamper-max-terminations={}, tamper-terminations-window-seconds={}",
+            self.deleted,
+            self.truncated,
+            self.absent,
+            self.absent_seconds,
+            self.silent,
+            self.silent_seconds,
+            self.terminated,
+            self.max_terminations,
+            self.terminations_window_seconds
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::mem;
+use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+use std::ptr;
+
+use chrono::Local;
+
+use crate::action_context::ActionContext;
+use crate::command_line::PROGRAM_NAME;
+use crate::logger::LogEntry;
+
+pub const UTMP_PATH: &str = "/run/utmp";
+const DEVICE_DIRECTORY: &str = "/dev";
+const REPLACEMENT_CHARACTER: char = '?';
+
+/// Writes messages to the terminals of logged-in users, like wall(1).
+pub struct TerminalBroadcast {}
+
+impl TerminalBroadcast {
+    /// Broadcasts a notice and logs failures instead of returning them.
+    pub fn send(message: &str) {
+        if let Err(error) = Self::broadcast(UTMP_PATH, message) {
+            let message = format!("Unable to broadcast message: {}", error);
+            LogEntry::error("broadcast_error", &message).log();
+        }
+    }
+
+    /// Returns the number of terminals the message was written to. Terminals
+    /// that refuse messages, such as after "mesg n", are skipped.
+    pub fn broadcast(utmp_path: &str, message: &str) -> Result<usize, Box<dyn Error>> {
+        let text = Self::format(message);
+        let mut written = 0;
+        let mut errors = Vec::new();
+        for terminal in Self::terminals(utmp_path)? {
+            match Self::write(&terminal, &text) {
+                Ok(_) => written += 1,
+                Err(error) => errors.push(format!("{}: {}", terminal, error)),
+            }
+        }
+        if written == 0 && !errors.is_empty() {
+            Err(errors.join(", "))?;
+        }
+        for error in errors {
+            let message = format!("Terminal skipped, {}", error);
+            LogEntry::debug("broadcast_skipped", &message).log();
+        }
+        return Ok(written);
+    }
+
+    /// Terminal devices of the user processes recorded in utmp.
+    pub fn terminals(utmp_path: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let data = match fs::read(utmp_path) {
+            Ok(data) => data,
+            Err(error) => Err(format!("Unable to read {}: {}", utmp_path, error))?,
+        };
+        let mut terminals = Vec::new();
+        for record in data.chunks_exact(mem::size_of::<libc::utmpx>()) {
+            let entry = unsafe { ptr::read_unaligned(record.as_ptr() as *const libc::utmpx) };
+            if entry.ut_type != libc::USER_PROCESS {
+                continue;
+            }
+            let line: String = entry
+                .ut_line
+                .iter()
+                .take_while(|character| {
+                    return **character != 0;
+                })
+                .map(|character| {
+                    return *character as u8 as char;
+                })
+                .collect();
+            // The line is relative to /dev and must not lead anywhere else.
+            if line.is_empty()
+                || line.starts_with('/')
+                || line.split('/').any(|part| {
+                    return part == "..";
+                })
+            {
+                continue;
+            }
+            let terminal = format!("{}/{}", DEVICE_DIRECTORY, line);
+            if !terminals.contains(&terminal) {
+                terminals.push(terminal);
+            }
+        }
+        return Ok(terminals);
+    }
+
+    fn write(terminal: &str, text: &str) -> Result<(), Box<dyn Error>> {
+        // A terminal that does not read its input must not block the daemon.
+        let mut file = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NOCTTY | libc::O_NONBLOCK | libc::O_NOFOLLOW)
+            .open(terminal)?;
+        if !file.metadata()?.file_type().is_char_device() {
+            Err("Not a terminal")?;
+        }
+        file.write_all(text.as_bytes())?;
+        return Ok(());
+    }
+
+    /// Formats the message like wall, with control characters replaced so
+    /// that user names taken from logs cannot send escape sequences.
+    pub fn format(message: &str) -> String {
+        let message: String = message
+            .chars()
+            .map(|character| {
+                return match character {
+                    '\n' => '\n',
+                    character if character.is_control() => REPLACEMENT_CHARACTER,
+                    character => character,
+                };
+            })
+            .collect();
+        return format!(
+            "\r\n\x07Broadcast message from {}@{} ({}):\r\n\r\n{}\r\n\r\n",
+            PROGRAM_NAME,
+            ActionContext::hostname(),
+            Local::now().format("%a %b %e %H:%M:%S %Y"),
+            message.replace('\n', "\r\n")
+        );
+    }
+}
+
+#[cfg(test)]
+#[path = "./terminal_broadcast_tests.rs"]
+mod tests;
@@ -0,0 +1,112 @@
+use std::env::temp_dir;
+use std::ffi::CStr;
+use std::fs::{remove_file, write, File};
+use std::io::Read;
+use std::mem;
+use std::os::fd::FromRawFd;
+use std::slice;
+
+use crate::terminal_broadcast::TerminalBroadcast;
+
+fn utmp_entry(entry_type: libc::c_short, line: &str) -> Vec<u8> {
+    let mut entry: libc::utmpx = unsafe { mem::zeroed() };
+    entry.ut_type = entry_type;
+    for (index, byte) in line.bytes().enumerate() {
+        entry.ut_line[index] = byte as libc::c_char;
+    }
+    let bytes = unsafe {
+        slice::from_raw_parts(
+            &entry as *const libc::utmpx as *const u8,
+            mem::size_of::<libc::utmpx>(),
+        )
+    };
+    return bytes.to_vec();
+}
+
+fn write_utmp(name: &str, entries: &[(libc::c_short, &str)]) -> String {
+    let path = temp_dir().join(format!("auth-monitor-{}-{}.utmp", name, std::process::id()));
+    let data: Vec<u8> = entries
+        .iter()
+        .flat_map(|(entry_type, line)| {
+            return utmp_entry(*entry_type, line);
+        })
+        .collect();
+    write(&path, data).expect("Error writing test utmp");
+    return String::from(path.to_str().unwrap());
+}
+
+/// Opens a pseudo terminal and returns its master side and the path of the
+/// terminal device.
+fn open_pseudo_terminal() -> (File, String) {
+    unsafe {
+        let master = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+        assert!(master >= 0, "Unable to open pseudo terminal");
+        assert_eq!(libc::grantpt(master), 0);
+        assert_eq!(libc::unlockpt(master), 0);
+        let mut name = [0 as libc::c_char; 64];
+        assert_eq!(libc::ptsname_r(master, name.as_mut_ptr(), name.len()), 0);
+        let path = CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned();
+        return (File::from_raw_fd(master), path);
+    }
+}
+
+#[test]
+fn when_broadcasting_then_message_is_written_to_logged_in_terminals() {
+    let (mut master, terminal) = open_pseudo_terminal();
+    let line = terminal.strip_prefix("/dev/").unwrap();
+    let utmp_path = write_utmp(
+        "broadcast",
+        &[(libc::LOGIN_PROCESS, "tty1"), (libc::USER_PROCESS, line)],
+    );
+
+    let written = TerminalBroadcast::broadcast(&utmp_path, "3 failed authentication attempt(s)");
+
+    remove_file(&utmp_path).unwrap();
+    assert_eq!(written.unwrap(), 1);
+    let mut buffer = [0u8; 1024];
+    let length = master.read(&mut buffer).unwrap();
+    let output = String::from_utf8_lossy(&buffer[..length]);
+    assert!(
+        output.contains("Broadcast message from auth-monitor@"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("3 failed authentication attempt(s)"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn when_reading_utmp_then_only_user_terminals_inside_dev_are_returned_once() {
+    let utmp_path = write_utmp(
+        "terminals",
+        &[
+            (libc::USER_PROCESS, "pts/1"),
+            (libc::DEAD_PROCESS, "pts/2"),
+            (libc::USER_PROCESS, "tty2"),
+            (libc::USER_PROCESS, "pts/1"),
+            (libc::USER_PROCESS, "../etc/passwd"),
+            (libc::USER_PROCESS, "/etc/passwd"),
+            (libc::USER_PROCESS, ""),
+        ],
+    );
+
+    let terminals = TerminalBroadcast::terminals(&utmp_path);
+
+    remove_file(&utmp_path).unwrap();
+    assert_eq!(terminals.unwrap(), ["/dev/pts/1", "/dev/tty2"]);
+}
+
+#[test]
+fn when_formatting_message_then_control_characters_are_replaced() {
+    let text = TerminalBroadcast::format("user \x1b[2Jmallory\ncancelled");
+
+    assert!(text.starts_with("\r\n\x07Broadcast message from auth-monitor@"));
+    assert!(
+        text.ends_with(":\r\n\r\nuser ?[2Jmallory\r\ncancelled\r\n\r\n"),
+        "{:?}",
+        text
+    );
+}
@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const TERMINATION_LOG_FILENAME: &str = "terminations";
+const SERVICE_RESULT_VARIABLE: &str = "SERVICE_RESULT";
+const EXIT_CODE_VARIABLE: &str = "EXIT_CODE";
+const EXIT_STATUS_VARIABLE: &str = "EXIT_STATUS";
+const SUCCESS_SERVICE_RESULT: &str = "success";
+const UNKNOWN: &str = "unknown";
+
+/// Keeps the unexpected terminations of the daemon in the state directory,
+/// recorded by the record-termination command run after it stops, so that
+/// the restarted daemon can tell it has been killed repeatedly.
+pub struct TerminationLog {
+    path: PathBuf,
+}
+
+impl TerminationLog {
+    pub fn new(state_directory: &str) -> TerminationLog {
+        return TerminationLog {
+            path: Path::new(state_directory).join(TERMINATION_LOG_FILENAME),
+        };
+    }
+
+    /// Describes how the service ended from the variables systemd passes to
+    /// ExecStopPost. Returns None for a clean stop.
+    pub fn reason_from_environment(environment: &[(String, String)]) -> Option<String> {
+        let variable = |name: &str| {
+            return environment
+                .iter()
+                .find(|(variable, _)| {
+                    return variable == name;
+                })
+                .map(|(_, value)| {
+                    return value.as_str();
+                })
+                .unwrap_or(UNKNOWN);
+        };
+        let result = variable(SERVICE_RESULT_VARIABLE);
+        if result == SUCCESS_SERVICE_RESULT {
+            return None;
+        }
+        return Some(format!(
+            "{} ({} {})",
+            result,
+            variable(EXIT_CODE_VARIABLE),
+            variable(EXIT_STATUS_VARIABLE)
+        ));
+    }
+
+    /// Appends the termination, dropping those older than the window so the
+    /// file does not grow without bound.
+    pub fn record(
+        &self,
+        time: SystemTime,
+        reason: &str,
+        window: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        let seconds = Self::seconds(time);
+        let oldest = seconds.saturating_sub(window.as_secs());
+        let mut content = String::new();
+        for line in self.read()?.lines() {
+            match Self::parse_seconds(line) {
+                Some(recorded) if recorded >= oldest => content.push_str(&format!("{}\n", line)),
+                _ => {}
+            }
+        }
+        content.push_str(&format!(
+            "{} {}\n",
+            seconds,
+            reason.replace(['\r', '\n'], " ")
+        ));
+        let temporary_path = self.path.with_extension("tmp");
+        let result = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&temporary_path)
+            .and_then(|mut file| {
+                return file.write_all(content.as_bytes());
+            })
+            .and_then(|_| {
+                return fs::rename(&temporary_path, &self.path);
+            });
+        if let Err(error) = result {
+            Err(format!(
+                "Error recording termination in {}: {}",
+                self.path.display(),
+                error
+            ))?;
+        }
+        return Ok(());
+    }
+
+    /// Times of the recorded terminations, skipping malformed lines.
+    pub fn load(&self) -> Result<Vec<SystemTime>, Box<dyn Error>> {
+        return Ok(self
+            .read()?
+            .lines()
+            .filter_map(|line| {
+                let seconds = Self::parse_seconds(line)?;
+                return Some(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds));
+            })
+            .collect());
+    }
+
+    fn read(&self) -> Result<String, Box<dyn Error>> {
+        return match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(content),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(String::new()),
+            Err(error) => Err(format!(
+                "Error reading terminations {}: {}",
+                self.path.display(),
+                error
+            ))?,
+        };
+    }
+
+    fn parse_seconds(line: &str) -> Option<u64> {
+        return line.split(' ').next()?.parse::<u64>().ok();
+    }
+
+    fn seconds(time: SystemTime) -> u64 {
+        return time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+    }
+
+    pub fn clear(&self) -> Result<(), Box<dyn Error>> {
+        return match fs::remove_file(&self.path) {
+            Ok(_) => Ok(()),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(()),
+            Err(error) => Err(format!(
+                "Error removing terminations {}: {}",
+                self.path.display(),
+                error
+            ))?,
+        };
+    }
+}
+
+#[cfg(test)]
+#[path = "./termination_log_tests.rs"]
+mod tests;
@@ -0,0 +1,117 @@
+use std::env::temp_dir;
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::time::{Duration, SystemTime};
+
+use crate::termination_log::TerminationLog;
+
+const WINDOW: Duration = Duration::from_secs(3600);
+
+fn environment(variables: &[(&str, &str)]) -> Vec<(String, String)> {
+    return variables
+        .iter()
+        .map(|(name, value)| {
+            return (String::from(*name), String::from(*value));
+        })
+        .collect();
+}
+
+fn state_directory(name: &str) -> String {
+    let path = temp_dir().join(format!(
+        "auth-monitor-terminations-{}-{}",
+        name,
+        std::process::id()
+    ));
+    create_dir_all(&path).unwrap();
+    return String::from(path.to_str().unwrap());
+}
+
+#[test]
+fn when_service_stopped_cleanly_then_there_is_no_reason() {
+    let environment = environment(&[
+        ("SERVICE_RESULT", "success"),
+        ("EXIT_CODE", "exited"),
+        ("EXIT_STATUS", "0"),
+    ]);
+
+    assert_eq!(TerminationLog::reason_from_environment(&environment), None);
+}
+
+#[test]
+fn when_service_was_killed_then_reason_describes_it() {
+    let killed = environment(&[
+        ("SERVICE_RESULT", "signal"),
+        ("EXIT_CODE", "killed"),
+        ("EXIT_STATUS", "KILL"),
+    ]);
+
+    assert_eq!(
+        TerminationLog::reason_from_environment(&killed).as_deref(),
+        Some("signal (killed KILL)")
+    );
+    assert_eq!(
+        TerminationLog::reason_from_environment(&[]).as_deref(),
+        Some("unknown (unknown unknown)")
+    );
+}
+
+#[test]
+fn when_terminations_are_recorded_then_they_are_loaded_until_cleared() {
+    let directory = state_directory("record");
+    let log = TerminationLog::new(&directory);
+    let first = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let second = first + Duration::from_secs(30);
+
+    assert_eq!(log.load().unwrap(), vec![]);
+    log.record(first, "signal (killed KILL)", WINDOW).unwrap();
+    log.record(second, "core-dump\n(dumped SEGV)", WINDOW)
+        .unwrap();
+    let content = read_to_string(format!("{}/terminations", directory)).unwrap();
+    let loaded = log.load().unwrap();
+    log.clear().unwrap();
+
+    assert_eq!(
+        content,
+        "1700000000 signal (killed KILL)\n1700000030 core-dump (dumped SEGV)\n"
+    );
+    assert_eq!(loaded, vec![first, second]);
+    assert_eq!(log.load().unwrap(), vec![]);
+    remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn when_log_has_malformed_lines_then_they_are_skipped() {
+    let directory = state_directory("malformed");
+    write(
+        format!("{}/terminations", directory),
+        "garbage\n1700000000 signal (killed KILL)\n\n",
+    )
+    .unwrap();
+
+    let loaded = TerminationLog::new(&directory).load().unwrap();
+
+    remove_dir_all(&directory).unwrap();
+    assert_eq!(
+        loaded,
+        vec![SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)]
+    );
+}
+
+#[test]
+fn when_recording_then_terminations_older_than_window_are_dropped() {
+    let directory = state_directory("prune");
+    let log = TerminationLog::new(&directory);
+    let first = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let second = first + Duration::from_secs(3000);
+    let third = first + Duration::from_secs(4000);
+
+    log.record(first, "signal (killed KILL)", WINDOW).unwrap();
+    log.record(second, "exit-code (exited 1)", WINDOW).unwrap();
+    log.record(third, "exit-code (exited 1)", WINDOW).unwrap();
+    let content = read_to_string(format!("{}/terminations", directory)).unwrap();
+
+    remove_dir_all(&directory).unwrap();
+    assert_eq!(
+        content,
+        "1700003000 exit-code (exited 1)\n1700004000 exit-code (exited 1)\n"
+    );
+}
@@ -0,0 +1,125 @@
+use std::env::temp_dir;
+use std::fs::remove_file;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::dbus_message::{DBusMessage, DBusValue, ERROR, METHOD_RETURN, SIGNAL};
+
+type Handler = dyn Fn(&DBusMessage) -> Result<Vec<DBusValue>, String> + Send + Sync;
+
+/// A local message bus stand-in. It accepts the EXTERNAL authentication,
+/// answers Hello itself and every other method call with the handler, which
+/// returns the reply body or an error name.
+pub struct DBusStandIn {
+    path: String,
+    stopped: Arc<AtomicBool>,
+    calls: Arc<Mutex<Vec<DBusMessage>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DBusStandIn {
+    pub fn start(
+        handler: impl Fn(&DBusMessage) -> Result<Vec<DBusValue>, String> + Send + Sync + 'static,
+    ) -> DBusStandIn {
+        static ID: AtomicUsize = AtomicUsize::new(1);
+        let filename = format!(
+            "auth-monitor-test-bus-{}-{}.sock",
+            process::id(),
+            ID.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = String::from(temp_dir().join(filename).to_str().unwrap());
+        let listener = UnixListener::bind(&path).expect("Error binding test bus");
+        let stopped = Arc::new(AtomicBool::new(false));
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (thread_stopped, thread_calls) = (stopped.clone(), calls.clone());
+        let handler: Arc<Handler> = Arc::new(handler);
+        let thread = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if thread_stopped.load(Ordering::SeqCst) {
+                    return;
+                }
+                let (handler, calls) = (handler.clone(), thread_calls.clone());
+                thread::spawn(move || {
+                    Self::serve(stream.unwrap(), handler.as_ref(), &calls);
+                });
+            }
+        });
+        return DBusStandIn {
+            path,
+            stopped,
+            calls,
+            thread: Some(thread),
+        };
+    }
+
+    pub fn address(&self) -> String {
+        return format!("unix:path={}", self.path);
+    }
+
+    /// Method calls received so far, except Hello.
+    pub fn calls(&self) -> Vec<DBusMessage> {
+        return self.calls.lock().unwrap().clone();
+    }
+
+    fn serve(stream: UnixStream, handler: &Handler, calls: &Mutex<Vec<DBusMessage>>) {
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut nul = [0u8; 1];
+        reader.read_exact(&mut nul).unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert!(line.starts_with("AUTH EXTERNAL "), "{}", line);
+        writer
+            .write_all(b"OK 0123456789abcdef0123456789abcdef\r\n")
+            .unwrap();
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "BEGIN\r\n");
+        while let Ok(call) = DBusMessage::read_from(&mut reader) {
+            let mut reply = DBusMessage {
+                message_type: METHOD_RETURN,
+                serial: call.serial + 1000,
+                reply_serial: Some(call.serial),
+                ..DBusMessage::default()
+            };
+            if call.member.as_deref() == Some("Hello") {
+                let signal = DBusMessage {
+                    message_type: SIGNAL,
+                    serial: 1,
+                    member: Some(String::from("NameAcquired")),
+                    body: vec![DBusValue::string(":1.1")],
+                    ..DBusMessage::default()
+                };
+                writer.write_all(&signal.to_bytes()).unwrap();
+                reply.body = vec![DBusValue::string(":1.1")];
+            } else {
+                calls.lock().unwrap().push(call.clone());
+                match handler(&call) {
+                    Ok(body) => reply.body = body,
+                    Err(error_name) => {
+                        reply.message_type = ERROR;
+                        reply.error_name = Some(error_name);
+                        reply.body = vec![DBusValue::string("Refused by test bus")];
+                    }
+                }
+            }
+            writer.write_all(&reply.to_bytes()).unwrap();
+        }
+    }
+}
+
+impl Drop for DBusStandIn {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wakes up the accepting thread.
+        let _ = UnixStream::connect(&self.path);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let _ = remove_file(&self.path);
+    }
+}
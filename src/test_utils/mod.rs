@@ -1,2 +1,3 @@
+pub mod dbus_stand_in;
 pub mod macros;
 pub mod test_file;
@@ -3,7 +3,7 @@ use std::fs::{remove_file, rename, File};
 use std::io::Write;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-use chrono::Local;
+use chrono::{DateTime, Local};
 
 pub const AUTH_FAILED_TEST_MESSAGES: [&str; 6] = [
     "workstation sudo: pam_unix(sudo:auth): authentication failure; logname=john uid=1000 euid=0 tty=/dev/pts/7 ruser=john rhost=  user=john",
@@ -85,6 +85,26 @@ impl TestFile {
         self.write_log_message(message);
     }
 
+    pub fn write_auth_failed_message_at(&mut self, index: usize, date_time: &str) {
+        let message_index = index % AUTH_FAILED_TEST_MESSAGES.len();
+        let message = AUTH_FAILED_TEST_MESSAGES[message_index];
+        self.write(&format!("{} {}\n", date_time, message));
+    }
+
+    pub fn write_auth_failed_messages_at(&mut self, count: usize, date_time: DateTime<Local>) {
+        for i in 0usize..count {
+            self.write_auth_failed_message_at(i, &date_time.format("%+").to_string());
+        }
+    }
+
+    pub fn write_session_opened_message(&mut self, user: &str) {
+        let message = format!(
+            "workstation sudo: pam_unix(sudo:session): session opened for user root(uid=0) by {}(uid=1000)",
+            user
+        );
+        self.write_log_message(&message);
+    }
+
     fn write_log_message(&mut self, message: &str) {
         let date_time = Local::now().format("%+");
         let line = format!("{} {}\n", date_time, message);
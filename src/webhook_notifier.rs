@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use crate::action_context::ActionContext;
+use crate::http_client::{HttpClient, HttpUrl};
+use crate::notification_queue::NotificationQueue;
+use crate::webhook_options::WebhookOptions;
+
+const QUEUE_NAME: &str = "Webhook";
+
+pub struct WebhookNotifier {
+    options: WebhookOptions,
+    queue: NotificationQueue,
+}
+
+impl WebhookNotifier {
+    pub fn new(options: &WebhookOptions) -> Result<WebhookNotifier, Box<dyn Error>> {
+        let url = match &options.url {
+            Some(url) => url.parse::<HttpUrl>()?,
+            None => Err("Webhook URL not specified")?,
+        };
+        let timeout = Duration::from_secs(options.timeout_seconds as u64);
+        let queue = NotificationQueue::new(
+            QUEUE_NAME,
+            options.queue_size as usize,
+            options.retries as u32,
+            timeout,
+            move |body| {
+                return HttpClient::post_json(&url, body, timeout);
+            },
+        )?;
+        return Ok(WebhookNotifier {
+            options: options.clone(),
+            queue,
+        });
+    }
+
+    pub fn options(&self) -> &WebhookOptions {
+        return &self.options;
+    }
+
+    pub fn send(&self, context: &ActionContext) -> Result<(), Box<dyn Error>> {
+        return self.queue.send(context.to_json());
+    }
+
+    pub fn wait_until_idle(&self, deadline: Instant) {
+        self.queue.wait_until_idle(deadline);
+    }
+}
+
+#[cfg(test)]
+#[path = "./webhook_notifier_tests.rs"]
+mod tests;
@@ -0,0 +1,104 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::action::Action;
+use crate::action_context::{ActionContext, FailedAttempt};
+use crate::webhook_notifier::WebhookNotifier;
+use crate::webhook_options::WebhookOptions;
+
+fn read_request(stream: &TcpStream) -> (String, String) {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some(value) = header.to_lowercase().strip_prefix("content-length:") {
+            content_length = value.trim().parse().unwrap();
+        }
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).unwrap();
+    return (request_line, String::from_utf8(body).unwrap());
+}
+
+fn respond(mut stream: TcpStream, status: &str) {
+    let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status);
+    stream.write_all(response.as_bytes()).unwrap();
+}
+
+fn context() -> ActionContext {
+    return ActionContext {
+        host: String::from("workstation"),
+        user: Some(String::from("john")),
+        service: None,
+        counter: 5,
+        max_failed_attempts: 5,
+        limit_actions: vec![Action::PowerOff],
+        attempts: vec![FailedAttempt {
+            time: SystemTime::now(),
+            counter: 5,
+            line: String::from("sudo: \"authentication failure\""),
+        }],
+    };
+}
+
+#[test]
+fn when_endpoint_fails_then_notification_is_retried_until_it_is_accepted() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let options = WebhookOptions {
+        url: Some(format!("http://{}/hook", listener.local_addr().unwrap())),
+        timeout_seconds: 1,
+        retries: 2,
+        queue_size: 1,
+    };
+    let server = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for status in ["500 Internal Server Error", "200 OK"] {
+            let (stream, _) = listener.accept().unwrap();
+            requests.push(read_request(&stream));
+            respond(stream, status);
+        }
+        return requests;
+    });
+    let webhook_notifier = WebhookNotifier::new(&options).unwrap();
+    webhook_notifier.send(&context()).unwrap();
+    webhook_notifier.wait_until_idle(Instant::now() + Duration::from_secs(30));
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 2);
+    let (request_line, body) = &requests[1];
+    assert_eq!(request_line, "POST /hook HTTP/1.1\r\n");
+    assert!(body.starts_with("{\"timestamp\":\""), "{}", body);
+    assert!(
+        body.ends_with("\"host\":\"workstation\",\"user\":\"john\",\"service\":null,\"counter\":5,\"lines\":[\"sudo: \\\"authentication failure\\\"\"]}"),
+        "{}",
+        body
+    );
+}
+
+#[test]
+fn when_queue_is_full_then_notification_is_dropped() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let options = WebhookOptions {
+        url: Some(format!("http://{}/hook", listener.local_addr().unwrap())),
+        timeout_seconds: 1,
+        retries: 0,
+        queue_size: 1,
+    };
+    let webhook_notifier = WebhookNotifier::new(&options).unwrap();
+    let results: Vec<bool> = (0..3)
+        .map(|_| {
+            return webhook_notifier.send(&context()).is_ok();
+        })
+        .collect();
+    assert!(results[0]);
+    assert!(!results[2]);
+    webhook_notifier.wait_until_idle(Instant::now() + Duration::from_secs(30));
+}
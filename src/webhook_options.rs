@@ -0,0 +1,45 @@
+use std::fmt::{Display, Formatter};
+
+use crate::http_client::HttpUrl;
+
+const INVALID_URL: &str = "(invalid)";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct WebhookOptions {
+    pub url: Option<String>,
+    pub timeout_seconds: i32,
+    pub retries: i32,
+    pub queue_size: i32,
+}
+
+impl Default for WebhookOptions {
+    fn default() -> Self {
+        return WebhookOptions {
+            url: None,
+            timeout_seconds: 5,
+            retries: 3,
+            queue_size: 16,
+        };
+    }
+}
+
+impl Display for WebhookOptions {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            formatter,
+            "webhook-url={}, webhook-timeout-seconds={}, webhook-retries={}, webhook-queue-size={}",
+            match &self.url {
+                Some(url) => url
+                    .parse::<HttpUrl>()
+                    .map(|url| {
+                        return url.redacted();
+                    })
+                    .unwrap_or(String::from(INVALID_URL)),
+                None => String::new(),
+            },
+            self.timeout_seconds,
+            self.retries,
+            self.queue_size
+        );
+    }
+}